@@ -0,0 +1,97 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct Bloom {
+    threshold: f32,
+    knee: f32,
+    intensity: f32,
+    tint: vec3<f32>,
+};
+
+// The mip level this pass reads.
+@group(1) @binding(0)
+var mip: texture_2d<f32>;
+@group(1) @binding(1)
+var mip_sampler: sampler;
+@group(1) @binding(2)
+var<uniform> bloom: Bloom;
+
+// Keeps the parts of the color above the threshold.
+// Within the knee below the threshold the color is ramped in quadratically.
+fn soft_threshold(color: vec3<f32>) -> vec3<f32> {
+    let brightness = max(color.r, max(color.g, color.b));
+
+    var soft = clamp(brightness - bloom.threshold + bloom.knee, 0.0, 2.0 * bloom.knee);
+    soft = (soft * soft) / (4.0 * bloom.knee + 0.00001);
+
+    let contribution = max(soft, brightness - bloom.threshold) / max(brightness, 0.00001);
+
+    return color * contribution;
+}
+
+// Samples the center and the four diagonals half a texel away.
+// With bilinear filtering this covers a 4x4 texel area, weighted towards the center.
+fn downsample(tex: texture_2d<f32>, s: sampler, uv: vec2<f32>) -> vec3<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(tex));
+    let o = texel * 0.5;
+
+    var sum = textureSample(tex, s, uv).rgb * 4.0;
+    sum += textureSample(tex, s, uv + vec2<f32>(-o.x, -o.y)).rgb;
+    sum += textureSample(tex, s, uv + vec2<f32>(o.x, -o.y)).rgb;
+    sum += textureSample(tex, s, uv + vec2<f32>(-o.x, o.y)).rgb;
+    sum += textureSample(tex, s, uv + vec2<f32>(o.x, o.y)).rgb;
+
+    return sum / 8.0;
+}
+
+// A 3x3 tent filter, which smooths out the blockiness of the smaller mip level.
+fn upsample(uv: vec2<f32>) -> vec3<f32> {
+    let o = 1.0 / vec2<f32>(textureDimensions(mip));
+
+    var sum = textureSample(mip, mip_sampler, uv).rgb * 4.0;
+
+    sum += textureSample(mip, mip_sampler, uv + vec2<f32>(-o.x, 0.0)).rgb * 2.0;
+    sum += textureSample(mip, mip_sampler, uv + vec2<f32>(o.x, 0.0)).rgb * 2.0;
+    sum += textureSample(mip, mip_sampler, uv + vec2<f32>(0.0, -o.y)).rgb * 2.0;
+    sum += textureSample(mip, mip_sampler, uv + vec2<f32>(0.0, o.y)).rgb * 2.0;
+
+    sum += textureSample(mip, mip_sampler, uv + vec2<f32>(-o.x, -o.y)).rgb;
+    sum += textureSample(mip, mip_sampler, uv + vec2<f32>(o.x, -o.y)).rgb;
+    sum += textureSample(mip, mip_sampler, uv + vec2<f32>(-o.x, o.y)).rgb;
+    sum += textureSample(mip, mip_sampler, uv + vec2<f32>(o.x, o.y)).rgb;
+
+    return sum / 16.0;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+#ifdef FIRST_DOWNSAMPLE
+    return vec4<f32>(soft_threshold(downsample(t, ts, in.uv)), 1.0);
+#endif
+
+#ifdef DOWNSAMPLE
+    return vec4<f32>(downsample(mip, mip_sampler, in.uv), 1.0);
+#endif
+
+#ifdef UPSAMPLE
+    // Blended additively with the level being written to.
+    return vec4<f32>(upsample(in.uv), 1.0);
+#endif
+
+#ifdef COMPOSITE
+    let scene = textureSample(t, ts, in.uv);
+
+    // Each level has been summed into the first one, so average them.
+    let levels = f32(#{MIP_LEVELS});
+    let glow = textureSampleLevel(mip, mip_sampler, in.uv, 0.0).rgb / levels;
+
+    return vec4<f32>(scene.rgb + glow * bloom.tint * bloom.intensity, scene.a);
+#endif
+}
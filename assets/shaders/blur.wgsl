@@ -10,7 +10,8 @@ var<uniform> globals: Globals;
 
 struct Blur {
     amount: f32,
-    kernel_radius: f32
+    kernel_radius: f32,
+    sigma: f32,
 };
 @group(1) @binding(0)
 var<uniform> blur: Blur;
@@ -23,8 +24,7 @@ fn p(x: f32, y: f32) -> vec2<f32> {
     return vec2<f32>(x, y) * blur.kernel_radius;
 }
 
-// TODO: Use a specialized pipeline with keys
-// to allow different blur kernels
+#ifdef BOX
 fn s_blurred(uv: vec2<f32>) -> vec3<f32> {
     let r = p(1.0, 0.0);
     let tr = p(1.0, 1.0);
@@ -39,13 +39,70 @@ fn s_blurred(uv: vec2<f32>) -> vec3<f32> {
         (s(uv) + s(uv + r) + s(uv + tr) + s(uv + t) + s(uv + tl) + s(uv + l) + s(uv + bl) + s(uv + b) + s(uv + br)) / 9.
         ;
 }
+#endif
+
+#ifdef GAUSSIAN
+// The gaussian is separable, so blur horizontally in the first pass
+// and vertically in the second pass.
+#if PASS == 0
+const DIRECTION: vec2<f32> = vec2<f32>(1.0, 0.0);
+#else
+const DIRECTION: vec2<f32> = vec2<f32>(0.0, 1.0);
+#endif
+
+fn s_blurred(uv: vec2<f32>) -> vec3<f32> {
+    let taps = i32(#{TAPS});
+    let step = DIRECTION * blur.kernel_radius / f32(taps);
+
+    // Distances are relative to the kernel radius, so sigma is too.
+    let two_sigma_squared = 2.0 * max(blur.sigma * blur.sigma, 0.0001);
+
+    var total = vec3<f32>(0.0);
+    var weights = 0.0;
+
+    for (var i = -taps; i <= taps; i = i + 1) {
+        let x = f32(i) / f32(taps);
+        let weight = exp(-(x * x) / two_sigma_squared);
+
+        total += s(uv + step * f32(i)) * weight;
+        weights += weight;
+    }
+
+    return total / weights;
+}
+#endif
+
+#ifdef KAWASE
+#if PASS == 0
+// Like the downsampling step of a dual filter blur:
+// The origin texel weighted against its four diagonal neighbours.
+fn s_blurred(uv: vec2<f32>) -> vec3<f32> {
+    let diagonals = s(uv + p(1.0, 1.0)) + s(uv + p(-1.0, 1.0)) + s(uv + p(-1.0, -1.0)) + s(uv + p(1.0, -1.0));
+
+    return (s(uv) * 4.0 + diagonals) / 8.0;
+}
+#else
+// Like the upsampling step of a dual filter blur:
+// A wider ring of neighbours, with the diagonals weighted double.
+fn s_blurred(uv: vec2<f32>) -> vec3<f32> {
+    let axes = s(uv + p(2.0, 0.0)) + s(uv + p(-2.0, 0.0)) + s(uv + p(0.0, 2.0)) + s(uv + p(0.0, -2.0));
+    let diagonals = s(uv + p(1.0, 1.0)) + s(uv + p(-1.0, 1.0)) + s(uv + p(-1.0, -1.0)) + s(uv + p(1.0, -1.0));
+
+    return (axes + diagonals * 2.0) / 12.0;
+}
+#endif
+#endif
 
 @fragment
 fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
-    let original = s(in.uv);
     let blurred = s_blurred(in.uv);
 
-    let output = mix(original, blurred, blur.amount);
+#ifdef BOX
+    let output = mix(s(in.uv), blurred, blur.amount);
+#else
+    // Kernels with several passes are mixed with the input after the last pass.
+    let output = blurred;
+#endif
 
     return vec4<f32>(output, 1.0);
-}
\ No newline at end of file
+}
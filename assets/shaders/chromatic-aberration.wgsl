@@ -17,6 +17,8 @@ struct ChromaticAberration {
 
     dir_b: vec2<f32>,
     magnitude_b: f32,
+
+    center: vec2<f32>,
 };
 
 @group(1) @binding(0)
@@ -25,10 +27,22 @@ var<uniform> ca: ChromaticAberration;
 
 @fragment
 fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+#ifdef RADIAL
+    // Twice the distance from the center, so the offset at the edges is the magnitude.
+    let from_center = (in.uv - ca.center) * 2.0;
+    let dir_r = from_center;
+    let dir_g = from_center;
+    let dir_b = from_center;
+#else
+    let dir_r = ca.dir_r;
+    let dir_g = ca.dir_g;
+    let dir_b = ca.dir_b;
+#endif
+
     let out = vec3<f32>(
-        textureSample(t, ts, in.uv + (ca.dir_r * ca.magnitude_r)).r,
-        textureSample(t, ts, in.uv + (ca.dir_g * ca.magnitude_g)).g,
-        textureSample(t, ts, in.uv + (ca.dir_b * ca.magnitude_b)).b,
+        textureSample(t, ts, in.uv + (dir_r * ca.magnitude_r)).r,
+        textureSample(t, ts, in.uv + (dir_g * ca.magnitude_g)).g,
+        textureSample(t, ts, in.uv + (dir_b * ca.magnitude_b)).b,
     );
 
     return vec4<f32>(out, 1.0);
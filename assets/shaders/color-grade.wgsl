@@ -0,0 +1,46 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct ColorGrade {
+    // White balance and exposure.
+    balance: mat3x3<f32>,
+    hue: mat3x3<f32>,
+    lift: vec3<f32>,
+    contrast: f32,
+    gain: vec3<f32>,
+    pivot: f32,
+    inverse_gamma: vec3<f32>,
+    saturation: f32,
+    vibrance: f32,
+};
+@group(1) @binding(0)
+var<uniform> color_grade: ColorGrade;
+
+// Mirrors `ColorGradeUniform::grade`, which is used to bake grades into LUTs.
+fn grade(input: vec3<f32>) -> vec3<f32> {
+    var color = color_grade.balance * input;
+    color = pow(max(color, vec3<f32>(0.0)) / color_grade.pivot, vec3<f32>(color_grade.contrast)) * color_grade.pivot;
+    color = color_grade.gain * (color + color_grade.lift * (1.0 - color));
+    color = pow(max(color, vec3<f32>(0.0)), color_grade.inverse_gamma);
+    color = color_grade.hue * color;
+
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let chroma = saturate(max(color.r, max(color.g, color.b)) - min(color.r, min(color.g, color.b)));
+    let saturation = color_grade.saturation * (1.0 + color_grade.vibrance * (1.0 - chroma));
+
+    return max(luminance + (color - luminance) * saturation, vec3<f32>(0.0));
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let sample = textureSample(t, ts, in.uv);
+
+    return vec4<f32>(grade(sample.rgb), 1.0);
+}
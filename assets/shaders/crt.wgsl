@@ -0,0 +1,121 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct Crt {
+    curvature: f32,
+    lines: f32,
+    scanlines: f32,
+    mask_intensity: f32,
+    mask_size: f32,
+    bleed: f32,
+    edge: f32,
+    brightness: f32,
+};
+@group(1) @binding(0)
+var<uniform> crt: Crt;
+
+// Bends the view outwards like the glass of a CRT.
+// Points pushed outside of the view are outside of zero to one.
+fn curve(uv: vec2<f32>) -> vec2<f32> {
+    var centered = uv * 2.0 - 1.0;
+    centered *= 1.0 + crt.curvature * centered.yx * centered.yx;
+
+    return centered * 0.5 + 0.5;
+}
+
+// Samples a line of the virtual resolution, smeared horizontally by phosphor bleed.
+fn bleed(uv: vec2<f32>, virtual_width: f32) -> vec3<f32> {
+    let step = vec2<f32>(0.5 * crt.bleed / virtual_width, 0.0);
+
+    return textureSample(t, ts, uv - 2.0 * step).rgb * 0.06
+        + textureSample(t, ts, uv - step).rgb * 0.24
+        + textureSample(t, ts, uv).rgb * 0.4
+        + textureSample(t, ts, uv + step).rgb * 0.24
+        + textureSample(t, ts, uv + 2.0 * step).rgb * 0.06;
+}
+
+// How bright a point is between two scanlines, where `offset` is the distance to the center of its line.
+// The beam is wider for bright colors.
+fn scanline(offset: f32, luminance: f32) -> f32 {
+    let width = mix(0.25, 0.45, saturate(luminance));
+    let beam = exp(-offset * offset / (2.0 * width * width));
+
+    return mix(1.0, beam, crt.scanlines);
+}
+
+// The color of the phosphors at a position in pixels.
+// Only one of red, green, or blue is lit at any position, the others are dimmed by the mask intensity.
+fn phosphors(position: vec2<f32>) -> vec3<f32> {
+    let p = position / max(crt.mask_size, 0.0001);
+    let dim = 1.0 - crt.mask_intensity;
+    var mask = vec3<f32>(1.0);
+
+#ifdef APERTURE_GRILLE
+    // Vertical stripes of red, green, and blue.
+    let phosphor = u32(floor(p.x)) % 3u;
+#endif
+
+#ifdef SLOT_MASK
+    // Stripes in slots, where every other triad is shifted by half a slot.
+    let phosphor = u32(floor(p.x)) % 3u;
+    let triad = u32(floor(p.x / 3.0));
+    let row = p.y + f32(triad % 2u) * 2.0;
+    if fract(row / 4.0) < 0.25 {
+        mask = vec3<f32>(dim);
+    }
+#endif
+
+#ifdef SHADOW_MASK
+    // Dots, where every other row is shifted by half a triad.
+    let phosphor = u32(floor(p.x + floor(p.y) * 1.5)) % 3u;
+#endif
+
+#ifdef PHOSPHOR_MASK
+    var lit = vec3<f32>(dim);
+    lit[phosphor] = 1.0;
+    mask *= lit;
+#endif
+
+    return mask;
+}
+
+// Darkens the edges in an oval shape, like `Mask::crt`.
+fn edge(uv: vec2<f32>) -> f32 {
+    if crt.edge <= 0.0 {
+        return 1.0;
+    }
+
+    let square = uv * (1.0 - uv);
+    let norm = square.x * square.y;
+
+    return saturate(norm * norm * crt.edge);
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let resolution = vec2<f32>(textureDimensions(t));
+    let virtual_width = crt.lines * resolution.x / resolution.y;
+
+    // Points bent outside of the view are black.
+    let uv = curve(in.uv);
+    let inside = f32(all(uv >= vec2<f32>(0.0)) && all(uv <= vec2<f32>(1.0)));
+
+    // Each line of the virtual resolution is drawn by one pass of the beam.
+    let line = uv.y * crt.lines;
+    let line_center = (floor(line) + 0.5) / crt.lines;
+    let color = bleed(vec2<f32>(uv.x, line_center), virtual_width);
+
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    let scan = scanline(fract(line) - 0.5, luminance);
+
+    let result = color * scan * phosphors(in.position.xy) * edge(uv) * inside * crt.brightness;
+
+    return vec4<f32>(result, 1.0);
+}
@@ -0,0 +1,28 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+#import bevy_vfx_bag::depth
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct DepthFog {
+    color: vec4<f32>,
+    start: f32,
+    density: f32,
+};
+@group(1) @binding(0)
+var<uniform> fog: DepthFog;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let scene = textureSample(t, ts, in.uv);
+
+    let distance = length(view_position(in.uv));
+    let amount = 1.0 - exp(-fog.density * max(distance - fog.start, 0.0));
+
+    return vec4<f32>(mix(scene.rgb, fog.color.rgb, amount * fog.color.a), 1.0);
+}
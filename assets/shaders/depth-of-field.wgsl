@@ -0,0 +1,59 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+#import bevy_vfx_bag::depth
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct DepthOfField {
+    focal_distance: f32,
+    aperture: f32,
+    max_coc: f32,
+};
+@group(1) @binding(0)
+var<uniform> dof: DepthOfField;
+
+const SAMPLES: i32 = 48;
+const GOLDEN_ANGLE: f32 = 2.39996323;
+
+// The radius of the circle of confusion in pixels, for something at the given depth.
+fn coc(depth: f32) -> f32 {
+    let blur = dof.aperture * abs(depth - dof.focal_distance) / max(depth, 0.0001);
+    return saturate(blur) * dof.max_coc;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(t));
+
+    let center_depth = view_depth(in.uv);
+    let center_coc = coc(center_depth);
+
+    var color = textureSampleLevel(t, ts, in.uv, 0.0).rgb;
+    var weight = 1.0;
+
+    // Gathers samples from a disk the size of the largest CoC, spread out evenly along a spiral.
+    // A sample contributes if its own blur reaches the center, such that blurry foreground spreads over what is behind it.
+    // Samples behind the center can not blur more than the center, such that blurry background does not spread over sharp foreground.
+    for (var i = 0; i < SAMPLES; i += 1) {
+        let radius = sqrt((f32(i) + 0.5) / f32(SAMPLES)) * dof.max_coc;
+        let angle = f32(i) * GOLDEN_ANGLE;
+        let uv = in.uv + vec2<f32>(cos(angle), sin(angle)) * radius * texel;
+
+        let sample_depth = view_depth(uv);
+        var reach = coc(sample_depth);
+        if sample_depth > center_depth {
+            reach = min(reach, center_coc);
+        }
+
+        let sample_weight = saturate(reach - radius + 1.0);
+        color += textureSampleLevel(t, ts, uv, 0.0).rgb * sample_weight;
+        weight += sample_weight;
+    }
+
+    return vec4<f32>(color / weight, 1.0);
+}
@@ -0,0 +1,41 @@
+#define_import_path bevy_vfx_bag::depth
+
+#import bevy_render::view
+
+#ifdef MULTISAMPLED
+@group(0) @binding(3)
+var depth_prepass_texture: texture_multisampled_2d<f32>;
+#else
+@group(0) @binding(3)
+var depth_prepass_texture: texture_2d<f32>;
+#endif
+@group(0) @binding(4)
+var<uniform> view: View;
+
+// The depth prepass at `uv`, where one is at the near plane and zero is infinitely far away.
+// Multisampled depth is read from the first sample.
+fn prepass_depth(uv: vec2<f32>) -> f32 {
+    let size = vec2<f32>(textureDimensions(depth_prepass_texture));
+    let coords = vec2<i32>(clamp(uv * size, vec2<f32>(0.0), size - 1.0));
+
+    return textureLoad(depth_prepass_texture, coords, 0).x;
+}
+
+// The view space position of what is seen at `uv`.
+// Where nothing is seen, the position is very far away.
+fn view_position(uv: vec2<f32>) -> vec3<f32> {
+    let depth = prepass_depth(uv);
+    if depth <= 0.0 {
+        return vec3<f32>(0.0, 0.0, -1e18);
+    }
+
+    let ndc = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, depth, 1.0);
+    let position = view.inverse_projection * ndc;
+
+    return position.xyz / position.w;
+}
+
+// The distance along the view direction to what is seen at `uv`.
+fn view_depth(uv: vec2<f32>) -> f32 {
+    return -view_position(uv).z;
+}
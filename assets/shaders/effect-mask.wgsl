@@ -0,0 +1,82 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+
+// When saving, this is the input of the effect.
+// Else it is the output of the effect.
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+#ifndef SAVE
+struct EffectMask {
+    // 0: None, 1: Rectangle, 2: Ellipse, 3: Circle, 4: Image.
+    shape: u32,
+    inverted: u32,
+    feather: f32,
+    center: vec2<f32>,
+    size: vec2<f32>,
+    amount: f32,
+};
+
+// The saved input of the effect.
+@group(1) @binding(0)
+var saved: texture_2d<f32>;
+@group(1) @binding(1)
+var saved_sampler: sampler;
+@group(1) @binding(2)
+var mask_image: texture_2d<f32>;
+@group(1) @binding(3)
+var<uniform> mask: EffectMask;
+
+// Turns a signed distance to the edge of a shape into how much the effect applies.
+// Negative distances are within the shape.
+fn edge(distance: f32) -> f32 {
+    return clamp(-distance / max(mask.feather, 0.00001), 0.0, 1.0);
+}
+
+// How much the effect applies at this position, from zero to one.
+fn coverage(uv: vec2<f32>) -> f32 {
+    var amount = 1.0;
+
+    switch mask.shape {
+        case 1u: {
+            let d = abs(uv - mask.center) - mask.size;
+            amount = edge(max(d.x, d.y));
+        }
+        case 2u: {
+            let p = (uv - mask.center) / mask.size;
+            amount = edge((length(p) - 1.0) * min(mask.size.x, mask.size.y));
+        }
+        case 3u: {
+            let dimensions = vec2<f32>(textureDimensions(saved));
+            let aspect = vec2<f32>(dimensions.x / dimensions.y, 1.0);
+            amount = edge(length((uv - mask.center) * aspect) - mask.size.x);
+        }
+        case 4u: {
+            amount = textureSampleLevel(mask_image, saved_sampler, uv, 0.0).r;
+        }
+        default: {}
+    }
+
+    if mask.inverted != 0u {
+        amount = 1.0 - amount;
+    }
+
+    return amount;
+}
+#endif
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+#ifdef SAVE
+    return textureSample(t, ts, in.uv);
+#else
+    let effect = textureSample(t, ts, in.uv);
+    let original = textureSample(saved, saved_sampler, in.uv);
+
+    return mix(original, effect, coverage(in.uv) * mask.amount);
+#endif
+}
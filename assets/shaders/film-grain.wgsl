@@ -0,0 +1,85 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct FilmGrain {
+    intensity: f32,
+    grains_per_height: f32,
+    frame_rate: f32,
+    shadows: f32,
+    midtones: f32,
+    highlights: f32,
+};
+@group(1) @binding(0)
+var<uniform> film_grain: FilmGrain;
+
+// PCG based hash, from zero to one.
+fn hash(p: vec3<u32>) -> f32 {
+    var v = p * 1664525u + 1013904223u;
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+    v = v ^ (v >> vec3<u32>(16u));
+    v.x += v.y * v.z;
+    v.y += v.z * v.x;
+    v.z += v.x * v.y;
+
+    return f32(v.x) / 4294967295.0;
+}
+
+// Smooth noise from minus one to one, which varies about once per unit of `p`.
+// Each `frame` and `channel` has its own noise.
+fn grain(p: vec2<f32>, frame: u32, channel: u32) -> f32 {
+    // Keep the cells positive, the view never spans anywhere near this many grains.
+    let i = vec2<u32>(vec2<i32>(floor(p)) + 65536);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    let seed = frame * 3u + channel;
+
+    let noise = mix(
+        mix(hash(vec3<u32>(i, seed)), hash(vec3<u32>(i + vec2<u32>(1u, 0u), seed)), u.x),
+        mix(hash(vec3<u32>(i + vec2<u32>(0u, 1u), seed)), hash(vec3<u32>(i + vec2<u32>(1u, 1u), seed)), u.x),
+        u.y
+    );
+
+    return noise * 2.0 - 1.0;
+}
+
+// How strong the grain is at a luminance, blending the shadows, midtones, and highlights response.
+fn response(luminance: f32) -> f32 {
+    let l = saturate(luminance);
+    let inverse = 1.0 - l;
+
+    return film_grain.shadows * inverse * inverse
+        + film_grain.midtones * 2.0 * l * inverse
+        + film_grain.highlights * l * l;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    // Make aspect-ratio independent UV coords, such that grains are square at any resolution.
+    let resolution = vec2<f32>(textureDimensions(t));
+    let uv_aspect_fixed = vec2<f32>(in.uv.x * resolution.x / resolution.y, in.uv.y);
+    let p = uv_aspect_fixed * film_grain.grains_per_height;
+
+    let frame = u32(max(globals.time * film_grain.frame_rate, 0.0));
+
+    let sample = textureSample(t, ts, in.uv);
+    let luminance = dot(sample.rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+
+#ifdef COLORED
+    let noise = vec3<f32>(grain(p, frame, 0u), grain(p, frame, 1u), grain(p, frame, 2u));
+#else
+    let noise = vec3<f32>(grain(p, frame, 0u));
+#endif
+
+    let amount = film_grain.intensity * response(luminance);
+
+    return vec4<f32>(max(sample.rgb + noise * amount, vec3<f32>(0.0)), sample.a);
+}
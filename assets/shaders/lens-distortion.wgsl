@@ -0,0 +1,68 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct LensDistortion {
+    center: vec2<f32>,
+    k1: f32,
+    k2: f32,
+    chromatic_aberration: f32,
+};
+@group(1) @binding(0)
+var<uniform> lens: LensDistortion;
+
+// The Brown-Conrady radial factor at the squared distance `r2` from the optical center.
+fn factor(r2: f32) -> f32 {
+    return 1.0 + lens.k1 * r2 + lens.k2 * r2 * r2;
+}
+
+// One within the view, zero beyond its edges.
+fn inside(uv: vec2<f32>) -> f32 {
+    let within = step(vec2<f32>(0.0), uv) * step(uv, vec2<f32>(1.0));
+    return within.x * within.y;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let resolution = vec2<f32>(textureDimensions(t));
+    let aspect = vec2<f32>(resolution.x / resolution.y, 1.0);
+
+    let from_center = in.uv - lens.center;
+    let r2 = dot(from_center * aspect * 2.0, from_center * aspect * 2.0);
+
+    let corner = max(lens.center, 1.0 - lens.center) * aspect * 2.0;
+    let corner_r2 = dot(corner, corner);
+
+#ifdef ZOOM_TO_FILL
+    // The largest factor within the view, which is at the center, the farthest corner, or the vertex in between.
+    let vertex = -lens.k1 / (2.0 * lens.k2);
+    var largest = max(factor(0.0), factor(corner_r2));
+    if lens.k2 < 0.0 && vertex > 0.0 && vertex < corner_r2 {
+        largest = max(largest, factor(vertex));
+    }
+    let zoom = largest * (1.0 + abs(lens.chromatic_aberration));
+#else
+    let zoom = 1.0;
+#endif
+
+    let distorted = from_center * factor(r2) / zoom;
+    let fringe = lens.chromatic_aberration * r2 / corner_r2;
+
+    let uv_r = lens.center + distorted * (1.0 + fringe);
+    let uv_g = lens.center + distorted;
+    let uv_b = lens.center + distorted * (1.0 - fringe);
+
+    let out = vec3<f32>(
+        textureSample(t, ts, uv_r).r * inside(uv_r),
+        textureSample(t, ts, uv_g).g * inside(uv_g),
+        textureSample(t, ts, uv_b).b * inside(uv_b),
+    );
+
+    return vec4<f32>(out, 1.0);
+}
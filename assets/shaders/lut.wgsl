@@ -14,19 +14,52 @@ var lut: texture_3d<f32>;
 @group(1) @binding(1)
 var luts: sampler;
 
+// The LUT to cross-fade to.
+@group(1) @binding(2)
+var lut_cross_fade: texture_3d<f32>;
+
+struct Lut {
+    intensity: f32,
+    blend: f32,
+    split_point: vec2<f32>,
+    split_normal: vec2<f32>,
+};
+
+@group(1) @binding(3)
+var<uniform> settings: Lut;
+
+fn sample_lut(lut_texture: texture_3d<f32>, color: vec3<f32>) -> vec3<f32> {
+    // https://developer.nvidia.com/gpugems/gpugems2/part-iii-high-quality-rendering/chapter-24-using-lookup-tables-accelerate-color
+    // The colors zero and one should map to the centers of the first and last texels,
+    // not their outer edges, so the color is scaled and offset by half a texel.
+    let size = f32(textureDimensions(lut_texture).x);
+    let scale = vec3<f32>((size - 1.0) / size);
+    let half_texel = vec3<f32>(0.5 / size);
+
+    return textureSample(lut_texture, luts, color * scale + half_texel).rgb;
+}
+
 @fragment
 fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
-    // https://developer.nvidia.com/gpugems/gpugems2/part-iii-high-quality-rendering/chapter-24-using-lookup-tables-accelerate-color
-    // I'm honestly not sure why this is necessary, I don't quite follow the reasoning.
-    // But the neutral LUT seems indistinguishable from the original input texture
-    // when this is used. Great!
-    let half_texel = vec3<f32>(1.0 / 64. / 2.);
+    let original = textureSample(t, ts, in.uv).rgb;
 
     // Notice the ".rbg".
     // If we sample the LUT using ".rgb" instead,
     // the way the 3D texture is loaded will mean the
     // green and blue colors are swapped.
     // This mitigates that.
-    let raw_color = textureSample(t, ts, in.uv).rbg;
-    return vec4<f32>(textureSample(lut, luts, raw_color + half_texel).rgb, 1.0);
-}
\ No newline at end of file
+    let raw_color = original.rbg;
+
+    let graded = mix(
+        sample_lut(lut, raw_color),
+        sample_lut(lut_cross_fade, raw_color),
+        settings.blend
+    );
+
+    // Without a split the normal is zero, so everything is graded.
+    if dot(in.uv - settings.split_point, settings.split_normal) < 0.0 {
+        return vec4<f32>(original, 1.0);
+    }
+
+    return vec4<f32>(mix(original, graded, settings.intensity), 1.0);
+}
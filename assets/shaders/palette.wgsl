@@ -0,0 +1,78 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+#import bevy_vfx_bag::pixelate
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct Palette {
+    // The colors in sRGB.
+    colors: array<vec4<f32>, 256>,
+    // The thresholds of the threshold map, four per element and row by row.
+    thresholds: array<vec4<f32>, 64>,
+    count: u32,
+    dither_size: u32,
+    dither_strength: f32,
+    block_size: vec2<f32>,
+    anchor: vec2<f32>,
+};
+@group(1) @binding(0)
+var<uniform> palette: Palette;
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+// The palette color nearest to the given sRGB color.
+fn nearest(color: vec3<f32>) -> vec3<f32> {
+    var best = color;
+    var best_distance = 1e20;
+
+    for (var i = 0u; i < palette.count; i++) {
+        let entry = palette.colors[i].rgb;
+        let difference = entry - color;
+        let distance = dot(difference, difference);
+
+        if distance < best_distance {
+            best = entry;
+            best_distance = distance;
+        }
+    }
+
+    return best;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let sample = textureSample(t, ts, in.uv);
+    var color = linear_to_srgb(max(sample.rgb, vec3<f32>(0.0)));
+
+#ifdef DITHER
+    // Blocks of a pixelated view get a single threshold, since they use the same grid.
+    let resolution = vec2<f32>(textureDimensions(t));
+    let block = pixelate_grid_block(in.uv * resolution, palette.block_size, palette.anchor);
+    // Blocks left of or above the anchor have negative indices, so wrap them around the threshold map.
+    let size = f32(max(palette.dither_size, 1u));
+    let cell = vec2<u32>(block - size * floor(block / size));
+    let index = cell.x + cell.y * palette.dither_size;
+    let threshold = palette.thresholds[index / 4u][index % 4u];
+
+    color += (threshold - 0.5) * palette.dither_strength;
+#endif
+
+    return vec4<f32>(srgb_to_linear(nearest(color)), 1.0);
+}
@@ -0,0 +1,21 @@
+#define_import_path bevy_vfx_bag::pixelate
+
+// The index of the block containing the pixel `position`,
+// when pixelating into blocks of `block_size` pixels with a block corner at `anchor`.
+fn pixelate_grid_block(position: vec2<f32>, block_size: vec2<f32>, anchor: vec2<f32>) -> vec2<f32> {
+    return floor((position - anchor) / block_size);
+}
+
+// The index of the block containing `uv`,
+// when pixelating a texture of the given resolution into square blocks of `block_size` texels.
+fn pixelate_block(uv: vec2<f32>, resolution: vec2<f32>, block_size: f32) -> vec2<f32> {
+    return pixelate_grid_block(uv * resolution, vec2<f32>(max(1.0, block_size)), vec2<f32>(0.0));
+}
+
+// The UV coordinates of the center of the block containing `uv`,
+// when pixelating a texture of the given resolution into square blocks of `block_size` texels.
+fn pixelate_uv(uv: vec2<f32>, resolution: vec2<f32>, block_size: f32) -> vec2<f32> {
+    let size = max(1.0, block_size);
+
+    return (pixelate_block(uv, resolution, block_size) + 0.5) * size / resolution;
+}
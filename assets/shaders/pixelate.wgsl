@@ -1,5 +1,6 @@
 #import bevy_core_pipeline::fullscreen_vertex_shader
 #import bevy_render::globals
+#import bevy_vfx_bag::pixelate
 
 @group(0) @binding(0)
 var t: texture_2d<f32>;
@@ -9,22 +10,70 @@ var ts: sampler;
 var<uniform> globals: Globals;
 
 struct Pixelate {
-    block_size: f32,
+    block_size: vec2<f32>,
+    anchor: vec2<f32>,
 };
 @group(1) @binding(0)
 var<uniform> pixelate: Pixelate;
 
+// Like `%`, but the result has the sign of `y`.
+fn modulo(x: vec2<f32>, y: vec2<f32>) -> vec2<f32> {
+    return x - y * floor(x / y);
+}
+
+// The center of the cell containing `q`, both in blocks from the anchor.
+fn cell_center(q: vec2<f32>) -> vec2<f32> {
+#ifdef HEXAGON
+    // The centers of the hexagons are on two rectangular grids, offset by half a cell.
+    // Whichever center of the two grids is nearest is the center of the hexagon.
+    let r = vec2<f32>(1.0, 1.7320508);
+    let h = r * 0.5;
+    let a = modulo(q, r) - h;
+    let b = modulo(q - h, r) - h;
+
+    return select(q - b, q - a, dot(a, a) < dot(b, b));
+#else ifdef TRIANGLE
+    let block = floor(q);
+    let f = q - block;
+
+    // Alternate between the diagonals, like a checkerboard, and use the centroid of the triangle.
+    if fract((block.x + block.y) * 0.5) < 0.25 {
+        return block + select(vec2<f32>(1.0, 2.0), vec2<f32>(2.0, 1.0), f.x > f.y) / 3.0;
+    }
+    return block + select(vec2<f32>(2.0), vec2<f32>(1.0), f.x + f.y < 1.0) / 3.0;
+#else
+    return floor(q) + 0.5;
+#endif
+}
+
 @fragment
 fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
     let resolution = vec2<f32>(textureDimensions(t));
+    let block_size = pixelate.block_size;
+
+    let q = (in.uv * resolution - pixelate.anchor) / block_size;
+    let center_q = cell_center(q);
+    let center = pixelate.anchor + center_q * block_size;
 
-    let width_height_over_block_size = resolution / max(1.0, pixelate.block_size);
+#ifdef AVERAGE
+    var color = vec4<f32>(0.0);
+    for (var y = 0; y < 4; y++) {
+        for (var x = 0; x < 4; x++) {
+            let offset = ((vec2<f32>(f32(x), f32(y)) + 0.5) / 4.0 - 0.5) * block_size;
+            color += textureSample(t, ts, (center + offset) / resolution);
+        }
+    }
+    color /= 16.0;
+#else
+    let texel = clamp(floor(center), vec2<f32>(0.0), resolution - 1.0);
+    var color = textureLoad(t, vec2<i32>(texel), 0);
+#endif
 
-    var uv = in.uv + 0.5;
-    uv *= width_height_over_block_size;
-    uv = floor(uv);
-    uv /= width_height_over_block_size;
-    uv -= 0.5;
+#ifdef CIRCLE
+    // Pixels from the edge of the dot, which is antialiased across a pixel.
+    let from_edge = (1.0 - length((q - center_q) * 2.0)) * 0.5 * min(block_size.x, block_size.y);
+    color = vec4<f32>(color.rgb * clamp(from_edge + 0.5, 0.0, 1.0), color.a);
+#endif
 
-    return textureSample(t, ts, uv); 
-}
\ No newline at end of file
+    return color;
+}
@@ -0,0 +1,86 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+#import bevy_vfx_bag::pixelate
+
+@group(0) @binding(0)
+var t: texture_2d<f32>;
+@group(0) @binding(1)
+var ts: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct Transition {
+    color: vec4<f32>,
+    direction: vec2<f32>,
+    center: vec2<f32>,
+    progress: f32,
+    softness: f32,
+    scale: f32,
+};
+@group(1) @binding(0)
+var<uniform> transition: Transition;
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+// Smooth noise from zero to one, which varies about once per unit.
+fn value_noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+
+    return mix(
+        mix(hash(i), hash(i + vec2<f32>(1.0, 0.0)), u.x),
+        mix(hash(i + vec2<f32>(0.0, 1.0)), hash(i + vec2<f32>(1.0, 1.0)), u.x),
+        u.y
+    );
+}
+
+// How much the transition color covers a point which becomes covered when the progress passes `threshold`.
+// The edge is widened by the softness, such that nothing is covered at zero progress and everything is covered at one.
+fn cover(threshold: f32) -> f32 {
+    let softness = max(transition.softness, 0.0001);
+    let edge = transition.progress * (1.0 + softness);
+
+    return 1.0 - smoothstep(edge - softness, edge, threshold);
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let resolution = vec2<f32>(textureDimensions(t));
+    let aspect = vec2<f32>(resolution.x / resolution.y, 1.0);
+
+    var uv = in.uv;
+
+#ifdef FADE
+    let amount = saturate(transition.progress);
+#endif
+
+#ifdef WIPE
+    let direction = normalize(transition.direction);
+    // Ranges from zero where the wipe starts to one where it ends.
+    let along = dot(in.uv - 0.5, direction) / (abs(direction.x) + abs(direction.y)) + 0.5;
+    let amount = cover(along);
+#endif
+
+#ifdef IRIS
+    let corners = max(transition.center, 1.0 - transition.center) * aspect;
+    let distance = length((in.uv - transition.center) * aspect) / length(corners);
+    let amount = cover(1.0 - distance);
+#endif
+
+#ifdef DISSOLVE
+    let amount = cover(value_noise(in.uv * aspect * transition.scale));
+#endif
+
+#ifdef PIXELATE_DISSOLVE
+    let block_size = mix(1.0, transition.scale, saturate(transition.progress));
+    uv = pixelate_uv(in.uv, resolution, block_size);
+    let amount = cover(value_noise(uv * aspect * 8.0));
+#endif
+
+    let sample = textureSample(t, ts, uv);
+
+    return vec4<f32>(mix(sample.rgb, transition.color.rgb, amount), 1.0);
+}
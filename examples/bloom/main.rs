@@ -0,0 +1,95 @@
+//! This example shows bloom in a 2D scene.
+//! Bright sprites glow, while dim sprites stay below the threshold.
+
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{post_processing::bloom::Bloom, BevyVfxBagPlugin};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_system(examples_common::print_on_change::<Bloom>)
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .add_system(orbit)
+        .run();
+}
+
+#[derive(Debug, Component)]
+struct Orbit {
+    radius: f32,
+    speed: f32,
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [up|down] to change the threshold, [left|right] to change the intensity, [T] to change the tint");
+
+    commands.spawn((Camera2dBundle::default(), Bloom::default()));
+
+    let colors = [
+        Color::rgb(1.0, 1.0, 1.0),
+        Color::rgb(1.0, 0.3, 0.1),
+        Color::rgb(0.2, 0.3, 0.4),
+        Color::rgb(0.1, 1.0, 0.6),
+        Color::rgb(0.4, 0.2, 0.3),
+        Color::rgb(0.9, 0.9, 0.2),
+    ];
+
+    for (i, color) in colors.into_iter().enumerate() {
+        let i = i as f32;
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(Vec2::splat(40. + 10. * i)),
+                    ..default()
+                },
+                ..default()
+            },
+            Orbit {
+                radius: 60. + 40. * i,
+                speed: 0.2 + 0.1 * i,
+            },
+        ));
+    }
+}
+
+fn orbit(time: Res<Time>, mut sprites: Query<(&mut Transform, &Orbit)>) {
+    let t = time.elapsed_seconds();
+
+    for (mut transform, orbit) in &mut sprites {
+        transform.translation = (Vec2::from_angle(t * orbit.speed) * orbit.radius).extend(0.);
+    }
+}
+
+fn update(keyboard_input: Res<Input<KeyCode>>, mut bloom: Query<&mut Bloom>) {
+    let mut bloom = bloom.single_mut();
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        bloom.threshold += 0.05;
+    } else if keyboard_input.just_pressed(KeyCode::Down) {
+        bloom.threshold = (bloom.threshold - 0.05).max(0.);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Right) {
+        bloom.intensity += 0.1;
+    } else if keyboard_input.just_pressed(KeyCode::Left) {
+        bloom.intensity = (bloom.intensity - 0.1).max(0.);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::T) {
+        bloom.tint = if bloom.tint == Color::WHITE {
+            Color::rgb(1.0, 0.6, 0.3)
+        } else if bloom.tint == Color::rgb(1.0, 0.6, 0.3) {
+            Color::rgb(0.4, 0.6, 1.0)
+        } else {
+            Color::WHITE
+        };
+    }
+}
@@ -3,7 +3,10 @@ mod examples_common;
 
 use bevy::prelude::*;
 
-use bevy_vfx_bag::{post_processing::blur::Blur, BevyVfxBagPlugin};
+use bevy_vfx_bag::{
+    post_processing::blur::{Blur, BlurKernel},
+    BevyVfxBagPlugin,
+};
 
 fn main() {
     let mut app = App::new();
@@ -11,13 +14,15 @@ fn main() {
     app.add_plugin(examples_common::SaneDefaultsPlugin)
         .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
         .add_system(examples_common::print_on_change::<Blur>)
-        .add_plugin(BevyVfxBagPlugin::default())
+        .add_plugin(BevyVfxBagPlugin)
         .add_startup_system(startup)
         .add_system(update)
         .run();
 }
 
 fn startup(mut commands: Commands) {
+    info!("Press [1|2|3] to change the blur kernel, [left|right] to change the radius, [up|down] to change the amount, [PgUp/PgDown] to change the gaussian taps");
+
     commands.spawn((
         Camera3dBundle {
             transform: Transform::from_xyz(0.0, 6., 12.0)
@@ -31,6 +36,22 @@ fn startup(mut commands: Commands) {
 fn update(keyboard_input: Res<Input<KeyCode>>, mut blur: Query<&mut Blur>) {
     let mut blur = blur.single_mut();
 
+    if keyboard_input.just_pressed(KeyCode::Key1) {
+        *blur = Blur::default();
+    } else if keyboard_input.just_pressed(KeyCode::Key2) {
+        *blur = Blur::gaussian();
+    } else if keyboard_input.just_pressed(KeyCode::Key3) {
+        *blur = Blur::kawase();
+    }
+
+    if let BlurKernel::Gaussian { taps, .. } = &mut blur.kernel {
+        if keyboard_input.just_pressed(KeyCode::PageUp) {
+            *taps += 1;
+        } else if keyboard_input.just_pressed(KeyCode::PageDown) {
+            *taps = taps.saturating_sub(1).max(1);
+        }
+    }
+
     if keyboard_input.just_pressed(KeyCode::Left) {
         blur.kernel_radius -= 0.001;
     } else if keyboard_input.just_pressed(KeyCode::Right) {
@@ -5,7 +5,10 @@
 mod examples_common;
 
 use bevy::prelude::*;
-use bevy_vfx_bag::{post_processing::chromatic_aberration::ChromaticAberration, BevyVfxBagPlugin};
+use bevy_vfx_bag::{
+    post_processing::chromatic_aberration::{ChromaticAberration, ChromaticAberrationMode},
+    BevyVfxBagPlugin,
+};
 
 fn main() {
     let mut app = App::new();
@@ -13,14 +16,14 @@ fn main() {
     app.add_plugin(examples_common::SaneDefaultsPlugin)
         .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
         .add_system(examples_common::print_on_change::<ChromaticAberration>)
-        .add_plugin(BevyVfxBagPlugin::default())
+        .add_plugin(BevyVfxBagPlugin)
         .add_startup_system(startup)
         .add_system(update)
         .run();
 }
 
 fn startup(mut commands: Commands) {
-    info!("Press [up/down] to change");
+    info!("Press [up/down] to change, [r] to switch between directional and radial fringes");
 
     commands.spawn((
         Camera3dBundle {
@@ -45,6 +48,13 @@ fn update(
         chromatic_aberration.add_magnitude(-0.001);
     }
 
+    if keyboard_input.just_pressed(KeyCode::R) {
+        *chromatic_aberration = match chromatic_aberration.mode {
+            ChromaticAberrationMode::Directional => ChromaticAberration::radial(0.01),
+            ChromaticAberrationMode::Radial => ChromaticAberration::default(),
+        };
+    }
+
     let t = time.elapsed_seconds();
 
     chromatic_aberration.dir_r = Vec2::from_angle(t);
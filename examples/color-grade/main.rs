@@ -0,0 +1,110 @@
+//! This example shows adjusting colors parametrically,
+//! and baking the grade into a LUT.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{color_grade::ColorGrade, lut::Lut},
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .add_system(examples_common::print_on_change::<ColorGrade>)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [E|D] to change the exposure, [C|X] the contrast, [S|A] the saturation, [H] to shift the hue, [Left|Right] to change the temperature, [Up|Down] the tint, [R] to reset");
+    info!("Press [B] to bake the grade into a LUT, and again to go back to the grade");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        ColorGrade::default(),
+    ));
+}
+
+fn update(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut baked: Local<Option<ColorGrade>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(Entity, Option<&mut ColorGrade>), With<Camera>>,
+) {
+    let (camera, grade) = query.single_mut();
+
+    if keyboard_input.just_pressed(KeyCode::B) {
+        match (baked.take(), grade) {
+            (Some(grade), _) => {
+                info!("Using the grade");
+                commands.entity(camera).remove::<Lut>().insert(grade);
+            }
+            (None, Some(grade)) => {
+                info!("Using the grade baked into a LUT");
+                let lut = images.add(grade.bake_to_lut(32));
+                commands
+                    .entity(camera)
+                    .remove::<ColorGrade>()
+                    .insert(Lut::new(lut));
+                *baked = Some(*grade);
+            }
+            (None, None) => {}
+        }
+        return;
+    }
+
+    let Some(mut grade) = grade else {
+        return;
+    };
+
+    let pressed = |key| keyboard_input.just_pressed(key);
+
+    if pressed(KeyCode::E) {
+        grade.exposure += 0.25;
+    } else if pressed(KeyCode::D) {
+        grade.exposure -= 0.25;
+    }
+
+    if pressed(KeyCode::C) {
+        grade.contrast += 0.1;
+    } else if pressed(KeyCode::X) {
+        grade.contrast = (grade.contrast - 0.1).max(0.1);
+    }
+
+    if pressed(KeyCode::S) {
+        grade.saturation += 0.1;
+    } else if pressed(KeyCode::A) {
+        grade.saturation = (grade.saturation - 0.1).max(0.0);
+    }
+
+    if pressed(KeyCode::H) {
+        grade.hue_shift = (grade.hue_shift + 30.0) % 360.0;
+    }
+
+    if pressed(KeyCode::Right) {
+        grade.temperature = (grade.temperature + 0.1).min(1.0);
+    } else if pressed(KeyCode::Left) {
+        grade.temperature = (grade.temperature - 0.1).max(-1.0);
+    }
+
+    if pressed(KeyCode::Up) {
+        grade.tint = (grade.tint + 0.1).min(1.0);
+    } else if pressed(KeyCode::Down) {
+        grade.tint = (grade.tint - 0.1).max(-1.0);
+    }
+
+    if pressed(KeyCode::R) {
+        *grade = ColorGrade::default();
+    }
+}
@@ -0,0 +1,57 @@
+//! This example shows emulating CRT screens.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{post_processing::crt::Crt, BevyVfxBagPlugin};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .add_system(examples_common::print_on_change::<Crt>)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [1|2|3|4] to switch between a consumer TV, an arcade monitor, a Trinitron, and a PVM, [Up|Down] to change the lines, [Left|Right] to change the curvature");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        Crt::default(),
+    ));
+}
+
+fn update(keyboard_input: Res<Input<KeyCode>>, mut query: Query<&mut Crt, With<Camera>>) {
+    let mut crt = query.single_mut();
+
+    if keyboard_input.just_pressed(KeyCode::Key1) {
+        *crt = Crt::consumer_tv();
+    } else if keyboard_input.just_pressed(KeyCode::Key2) {
+        *crt = Crt::arcade();
+    } else if keyboard_input.just_pressed(KeyCode::Key3) {
+        *crt = Crt::trinitron();
+    } else if keyboard_input.just_pressed(KeyCode::Key4) {
+        *crt = Crt::pvm();
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        crt.lines += 16.0;
+    } else if keyboard_input.just_pressed(KeyCode::Down) {
+        crt.lines = (crt.lines - 16.0).max(16.0);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Right) {
+        crt.curvature += 0.02;
+    } else if keyboard_input.just_pressed(KeyCode::Left) {
+        crt.curvature = (crt.curvature - 0.02).max(0.0);
+    }
+}
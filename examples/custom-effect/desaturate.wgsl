@@ -0,0 +1,24 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_render::globals
+
+@group(0) @binding(0)
+var source: texture_2d<f32>;
+@group(0) @binding(1)
+var source_sampler: sampler;
+@group(0) @binding(2)
+var<uniform> globals: Globals;
+
+struct Desaturate {
+    amount: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> desaturate: Desaturate;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(source, source_sampler, in.uv).rgb;
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+
+    return vec4<f32>(mix(color, vec3<f32>(luminance), desaturate.amount), 1.0);
+}
@@ -0,0 +1,86 @@
+//! This example shows how to make a post processing effect outside of this crate.
+//! The effect desaturates the image, and is applied after a pixelation effect
+//! from this crate.
+#![allow(dead_code)] // The `ShaderType` derive emits per-field checks which newer compilers consider unused.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::{
+    asset::load_internal_asset, prelude::*, reflect::TypeUuid, render::render_resource::ShaderType,
+};
+use bevy_vfx_bag::{
+    post_processing::{
+        pixelate::Pixelate, PostProcessingEffect, PostProcessingEffectPlugin, PostProcessingOrder,
+    },
+    BevyVfxBagPlugin,
+};
+
+const DESATURATE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 17225473431442346437);
+
+/// Desaturation settings.
+#[derive(Debug, Copy, Clone, Component, ShaderType)]
+struct Desaturate {
+    /// How much color to remove. `0.0` leaves the image untouched, `1.0` is grayscale.
+    amount: f32,
+}
+
+impl PostProcessingEffect for Desaturate {
+    type Uniform = Self;
+
+    const LABEL: &'static str = "Desaturate";
+
+    fn shader() -> Handle<Shader> {
+        DESATURATE_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        *self
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_plugin(PostProcessingEffectPlugin::<Desaturate>::default())
+        .add_startup_system(startup)
+        .add_system(update);
+
+    load_internal_asset!(
+        app,
+        DESATURATE_SHADER_HANDLE,
+        "desaturate.wgsl",
+        Shader::from_wgsl
+    );
+
+    app.run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [up/down] to change the amount of desaturation");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        Pixelate::default().order(0.),
+        Desaturate { amount: 0.8 }.order(1.),
+    ));
+}
+
+fn update(keyboard_input: Res<Input<KeyCode>>, mut query: Query<&mut Desaturate>) {
+    let mut desaturate = query.single_mut();
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        desaturate.amount += 0.1;
+    } else if keyboard_input.just_pressed(KeyCode::Down) {
+        desaturate.amount -= 0.1;
+    };
+
+    desaturate.amount = desaturate.amount.clamp(0.0, 1.0);
+}
@@ -0,0 +1,70 @@
+//! This example shows depth of field and depth fog in a 3D scene.
+//! Both read the depth prepass, so the camera needs a `DepthPrepass`.
+
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::{core_pipeline::prepass::DepthPrepass, prelude::*};
+use bevy_vfx_bag::{
+    post_processing::{depth_fog::DepthFog, depth_of_field::DepthOfField},
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_system(examples_common::print_on_change::<DepthOfField>)
+        .add_system(examples_common::print_on_change::<DepthFog>)
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [up|down] to change the focal distance, [left|right] to change the aperture, [F] to toggle fog");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        DepthPrepass,
+        DepthOfField::default(),
+    ));
+}
+
+fn update(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(Entity, &mut DepthOfField, Option<&DepthFog>)>,
+) {
+    let (camera, mut depth_of_field, fog) = query.single_mut();
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        depth_of_field.focal_distance += 1.0;
+    } else if keyboard_input.just_pressed(KeyCode::Down) {
+        depth_of_field.focal_distance = (depth_of_field.focal_distance - 1.0).max(0.5);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Right) {
+        depth_of_field.aperture += 0.25;
+    } else if keyboard_input.just_pressed(KeyCode::Left) {
+        depth_of_field.aperture = (depth_of_field.aperture - 0.25).max(0.);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::F) {
+        if fog.is_some() {
+            commands.entity(camera).remove::<DepthFog>();
+        } else {
+            commands.entity(camera).insert(DepthFog {
+                color: Color::rgba(0.6, 0.65, 0.7, 1.0),
+                start: 5.0,
+                density: 0.08,
+            });
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! This example shows how to limit effects to parts of the view.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        blur::Blur,
+        effect_mask::{EffectMask, EffectMaskShape, Masked, PostProcessingMask},
+        pixelate::Pixelate,
+        EffectStack,
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [arrow keys] to move the focus circle, [PgUp|PgDown] to change its feather, [I] to invert it, [P] to toggle the pixelated rectangle");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        // Blur everything but a focus circle.
+        Blur {
+            amount: 1.0,
+            kernel_radius: 0.01,
+            ..Blur::gaussian()
+        }
+        .masked(
+            EffectMask::circle(Vec2::new(0.5, 0.5), 0.25)
+                .inverted()
+                .with_feather(0.05),
+        ),
+        // Pixelate a rectangle in the lower left corner.
+        EffectStack::new().with_masked(
+            Pixelate::new(12.0),
+            1.,
+            EffectMask::rect(Vec2::new(0.05, 0.6), Vec2::new(0.35, 0.95)),
+        ),
+    ));
+}
+
+fn update(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(&mut Masked<Blur>, &mut EffectStack<Pixelate>)>,
+) {
+    let (mut masked, mut stack) = query.single_mut();
+    let mask = &mut masked.mask;
+
+    if let EffectMaskShape::Circle { center, .. } = &mut mask.shape {
+        let mut direction = Vec2::ZERO;
+
+        if keyboard_input.pressed(KeyCode::Left) {
+            direction.x -= 1.;
+        }
+        if keyboard_input.pressed(KeyCode::Right) {
+            direction.x += 1.;
+        }
+        if keyboard_input.pressed(KeyCode::Up) {
+            direction.y -= 1.;
+        }
+        if keyboard_input.pressed(KeyCode::Down) {
+            direction.y += 1.;
+        }
+
+        *center = (*center + direction * 0.5 * time.delta_seconds()).clamp(Vec2::ZERO, Vec2::ONE);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        mask.feather += 0.01;
+    } else if keyboard_input.just_pressed(KeyCode::PageDown) {
+        mask.feather = (mask.feather - 0.01).max(0.);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::I) {
+        mask.inverted = !mask.inverted;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::P) {
+        let instance = &mut stack.instances[0];
+        instance.enabled = !instance.enabled;
+    }
+}
@@ -0,0 +1,98 @@
+//! This example shows how to apply several instances of the same effect to a camera.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        blur::Blur, pixelate::Pixelate, wave::Wave, EffectStack, PostProcessingOrder,
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [1|2] to toggle the wave instances, [3|4] to toggle the blur instances");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        // One wave along each axis, at different speeds.
+        EffectStack::new()
+            .with(
+                Wave {
+                    waves_x: 4.,
+                    speed_x: 1.,
+                    amplitude_x: 0.01,
+                    ..default()
+                },
+                0.,
+            )
+            .with(
+                Wave {
+                    waves_y: 10.,
+                    speed_y: 5.,
+                    amplitude_y: 0.005,
+                    ..default()
+                },
+                1.,
+            ),
+        // Blur both before and after pixelation.
+        EffectStack::new()
+            .with(
+                Blur {
+                    amount: 1.0,
+                    kernel_radius: 0.005,
+                    ..default()
+                },
+                2.,
+            )
+            .with(
+                Blur {
+                    amount: 0.5,
+                    kernel_radius: 0.002,
+                    ..default()
+                },
+                4.,
+            ),
+        Pixelate::default().order(3.),
+    ));
+}
+
+fn update(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut waves: Query<&mut EffectStack<Wave>>,
+    mut blurs: Query<&mut EffectStack<Blur>>,
+) {
+    let mut waves = waves.single_mut();
+    let mut blurs = blurs.single_mut();
+
+    let wave_keys = [KeyCode::Key1, KeyCode::Key2];
+    for (key, instance) in wave_keys.into_iter().zip(waves.instances.iter_mut()) {
+        if keyboard_input.just_pressed(key) {
+            instance.enabled = !instance.enabled;
+            info!("Wave {key:?} enabled: {}", instance.enabled);
+        }
+    }
+
+    let blur_keys = [KeyCode::Key3, KeyCode::Key4];
+    for (key, instance) in blur_keys.into_iter().zip(blurs.instances.iter_mut()) {
+        if keyboard_input.just_pressed(key) {
+            instance.enabled = !instance.enabled;
+            info!("Blur {key:?} enabled: {}", instance.enabled);
+        }
+    }
+}
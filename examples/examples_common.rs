@@ -59,7 +59,7 @@ pub(crate) struct ShouldAdd3dCameraBundle(bool);
 impl Plugin for ShapesExamplePlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ShouldAdd3dCameraBundle(self.add_3d_camera_bundle))
-            .add_plugin(FrameTimeDiagnosticsPlugin::default())
+            .add_plugin(FrameTimeDiagnosticsPlugin)
             .add_startup_system(shapes::setup)
             .add_startup_system(ui::setup)
             .add_system(shapes::rotate)
@@ -0,0 +1,67 @@
+//! This example shows animated film grain.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{post_processing::film_grain::FilmGrain, BevyVfxBagPlugin};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(examples_common::print_on_change::<FilmGrain>)
+        .add_system(update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [up|down] to change the intensity, [left|right] to change the size, [C] to toggle colored grain, [S|M|H] to emphasize shadows, midtones, or highlights");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        FilmGrain::default(),
+    ));
+}
+
+fn update(keyboard_input: Res<Input<KeyCode>>, mut query: Query<&mut FilmGrain>) {
+    let mut grain = query.single_mut();
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        grain.intensity += 0.05;
+    } else if keyboard_input.just_pressed(KeyCode::Down) {
+        grain.intensity = (grain.intensity - 0.05).max(0.);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Right) {
+        grain.size += 0.5;
+    } else if keyboard_input.just_pressed(KeyCode::Left) {
+        grain.size = (grain.size - 0.5).max(0.5);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::C) {
+        grain.colored = !grain.colored;
+    }
+
+    let response = if keyboard_input.just_pressed(KeyCode::S) {
+        Some((1.0, 0.4, 0.1))
+    } else if keyboard_input.just_pressed(KeyCode::M) {
+        Some((0.6, 1.0, 0.3))
+    } else if keyboard_input.just_pressed(KeyCode::H) {
+        Some((0.1, 0.4, 1.0))
+    } else {
+        None
+    };
+
+    if let Some((shadows, midtones, highlights)) = response {
+        grain.shadows = shadows;
+        grain.midtones = midtones;
+        grain.highlights = highlights;
+    }
+}
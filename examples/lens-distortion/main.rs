@@ -0,0 +1,79 @@
+//! This example shows barrel and pincushion lens distortion,
+//! with color fringes which grow towards the edges.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{post_processing::lens_distortion::LensDistortion, BevyVfxBagPlugin};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .add_system(examples_common::print_on_change::<LensDistortion>)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [up/down] to change k1, [left/right] to change k2, [c|x] to change the chromatic aberration");
+    info!("Press [z] to toggle zooming to fill the view, click to move the optical center, [r] to reset");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        LensDistortion::default(),
+    ));
+}
+
+fn update(
+    keyboard_input: Res<Input<KeyCode>>,
+    mouse_input: Res<Input<MouseButton>>,
+    windows: Query<&Window>,
+    mut query: Query<&mut LensDistortion, With<Camera>>,
+) {
+    let mut distortion = query.single_mut();
+
+    let pressed = |key| keyboard_input.just_pressed(key);
+
+    if pressed(KeyCode::Up) {
+        distortion.k1 += 0.05;
+    } else if pressed(KeyCode::Down) {
+        distortion.k1 -= 0.05;
+    }
+
+    if pressed(KeyCode::Right) {
+        distortion.k2 += 0.01;
+    } else if pressed(KeyCode::Left) {
+        distortion.k2 -= 0.01;
+    }
+
+    if pressed(KeyCode::C) {
+        distortion.chromatic_aberration += 0.005;
+    } else if pressed(KeyCode::X) {
+        distortion.chromatic_aberration -= 0.005;
+    }
+
+    if pressed(KeyCode::Z) {
+        distortion.zoom_to_fill = !distortion.zoom_to_fill;
+    }
+
+    if pressed(KeyCode::R) {
+        *distortion = LensDistortion::default();
+    }
+
+    if mouse_input.just_pressed(MouseButton::Left) {
+        let window = windows.single();
+        if let Some(cursor) = window.cursor_position() {
+            // The cursor position starts at the bottom left, UV coordinates at the top left.
+            let size = Vec2::new(window.width(), window.height());
+            distortion.center = Vec2::new(cursor.x, size.y - cursor.y) / size;
+        }
+    }
+}
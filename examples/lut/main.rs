@@ -2,21 +2,33 @@
 mod examples_common;
 
 use bevy::prelude::*;
-use bevy_vfx_bag::{post_processing::lut::Lut, BevyVfxBagPlugin};
+use bevy_vfx_bag::{
+    post_processing::lut::{Lut, LutSplit},
+    BevyVfxBagPlugin,
+};
 
 fn main() {
     let mut app = App::new();
 
     app.add_plugin(examples_common::SaneDefaultsPlugin)
         .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
-        .add_plugin(BevyVfxBagPlugin::default())
+        .add_plugin(BevyVfxBagPlugin)
         .add_startup_system(startup)
         .add_system(update)
+        .add_system(adjust)
         .run();
 }
 
-fn startup(mut commands: Commands) {
+#[derive(Debug, Resource)]
+struct CubeLut(Handle<Image>);
+
+fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
     info!("Press [left|right] to change which LUT is in use");
+    info!("Press [up|down] to change the intensity, [S] to toggle the split, [Q|E] to rotate the split");
+    info!("Press [C] to toggle cross-fading between the LUT and the arctic LUT");
+
+    // LUTs may also be loaded from `.cube` files.
+    commands.insert_resource(CubeLut(asset_server.load("luts/teal-orange.cube")));
 
     commands
         .spawn(Camera3dBundle {
@@ -27,17 +39,30 @@ fn startup(mut commands: Commands) {
         .insert(Lut::default());
 }
 
-// Cycle through some preset LUTs.
+// Cycle through the preset LUTs, a LUT loaded from a `.cube` file, and no LUT.
 fn update(
     mut choice: Local<usize>,
     mut commands: Commands,
     keyboard_input: Res<Input<KeyCode>>,
+    cube_lut: Res<CubeLut>,
     mut query: Query<Entity, With<Camera>>,
 ) {
+    let luts = [
+        ("Neo", Lut::neo()),
+        ("Arctic", Lut::arctic()),
+        ("Burlesque", Lut::burlesque()),
+        ("Denim", Lut::denim()),
+        ("Neutral", Lut::neutral()),
+        ("Rouge", Lut::rouge()),
+        ("Sauna", Lut::sauna()),
+        ("Slate", Lut::slate()),
+        ("Teal Orange (.cube)", Lut::new(cube_lut.0.clone())),
+    ];
+
     let choice_now = if keyboard_input.just_pressed(KeyCode::Left) {
         choice.saturating_sub(1)
     } else if keyboard_input.just_pressed(KeyCode::Right) {
-        (*choice + 1).min(3)
+        (*choice + 1).min(luts.len())
     } else {
         *choice
     };
@@ -46,24 +71,62 @@ fn update(
         let entity = query.single_mut();
 
         *choice = choice_now;
-        match *choice {
-            0 => {
-                commands.get_or_spawn(entity).insert(Lut::neo());
-                info!("Neo");
+        match luts.into_iter().nth(*choice) {
+            Some((name, lut)) => {
+                commands.get_or_spawn(entity).insert(lut);
+                info!("{name}");
             }
-            1 => {
-                commands.get_or_spawn(entity).insert(Lut::arctic());
-                info!("Arctic");
-            }
-            2 => {
-                commands.get_or_spawn(entity).insert(Lut::slate());
-                info!("Slate");
-            }
-            3 => {
+            None => {
                 commands.get_or_spawn(entity).remove::<Lut>();
                 info!("Disabled (default Bevy colors)");
             }
-            _ => unreachable!(),
         }
     }
 }
+
+// Change the intensity, split, and cross-fade of the LUT in use.
+fn adjust(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut cross_fading: Local<bool>,
+    mut query: Query<&mut Lut, With<Camera>>,
+) {
+    let Ok(mut lut) = query.get_single_mut() else {
+        return;
+    };
+
+    if keyboard_input.just_pressed(KeyCode::Up) {
+        lut.intensity = (lut.intensity + 0.1).min(1.0);
+        info!("Intensity: {:.1}", lut.intensity);
+    } else if keyboard_input.just_pressed(KeyCode::Down) {
+        lut.intensity = (lut.intensity - 0.1).max(0.0);
+        info!("Intensity: {:.1}", lut.intensity);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::S) {
+        lut.split = match lut.split {
+            Some(_) => None,
+            None => Some(LutSplit::default()),
+        };
+    }
+
+    if let Some(split) = &mut lut.split {
+        if keyboard_input.pressed(KeyCode::Q) {
+            split.angle += time.delta_seconds();
+        } else if keyboard_input.pressed(KeyCode::E) {
+            split.angle -= time.delta_seconds();
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::C) {
+        *cross_fading = !*cross_fading;
+
+        let to = Lut::arctic();
+        lut.set_cross_fade(cross_fading.then_some(&to));
+    }
+
+    if *cross_fading {
+        // Like a day/night cycle.
+        lut.blend = (time.elapsed_seconds().sin() + 1.0) / 2.0;
+    }
+}
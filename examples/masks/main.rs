@@ -12,7 +12,7 @@ fn main() {
 
     app.add_plugin(examples_common::SaneDefaultsPlugin)
         .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
-        .add_plugin(BevyVfxBagPlugin::default())
+        .add_plugin(BevyVfxBagPlugin)
         .add_startup_system(startup)
         .add_system(update)
         .add_system(examples_common::print_on_change::<Mask>)
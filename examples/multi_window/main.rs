@@ -20,7 +20,7 @@ fn main() {
 
     app.add_plugin(examples_common::SaneDefaultsPlugin)
         .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
-        .add_plugin(BevyVfxBagPlugin::default())
+        .add_plugin(BevyVfxBagPlugin)
         .add_startup_system(setup);
 
     app.run();
@@ -54,13 +54,14 @@ fn setup(mut commands: Commands) {
         Flip::default().order(5.),
     ));
 
-    // Third window: Camera has other effects
+    // Third window: HDR camera has other effects
     let window_3 = commands.spawn(Window::default()).id();
     commands.spawn((
         Camera3dBundle {
             transform,
             camera: Camera {
                 target: RenderTarget::Window(WindowRef::Entity(window_3)),
+                hdr: true,
                 ..default()
             },
             ..default()
@@ -0,0 +1,90 @@
+//! This example shows mapping the screen to the colors of a palette,
+//! dithering between them, and pixelating along with the dithering.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        palette::{Dither, Palette},
+        pixelate::Pixelate,
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .add_system(examples_common::print_on_change::<Palette>)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [1|2|3] to use the Game Boy, PICO-8, or CGA palette, [D] to change the dithering, [Up|Down] to change its strength, [P] to toggle pixelation");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        Palette::default(),
+    ));
+}
+
+fn update(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(Entity, &mut Palette, Option<&Pixelate>), With<Camera>>,
+) {
+    let (camera, mut palette, pixelate) = query.single_mut();
+
+    let pressed = |key| keyboard_input.just_pressed(key);
+
+    let colors = if pressed(KeyCode::Key1) {
+        Some(Palette::game_boy())
+    } else if pressed(KeyCode::Key2) {
+        Some(Palette::pico8())
+    } else if pressed(KeyCode::Key3) {
+        Some(Palette::cga())
+    } else {
+        None
+    };
+    if let Some(Palette { colors, .. }) = colors {
+        palette.colors = colors;
+    }
+
+    if pressed(KeyCode::D) {
+        palette.dither = match palette.dither {
+            Dither::None => Dither::Bayer2x2,
+            Dither::Bayer2x2 => Dither::Bayer4x4,
+            Dither::Bayer4x4 => Dither::Bayer8x8,
+            Dither::Bayer8x8 => Dither::BlueNoise,
+            Dither::BlueNoise => Dither::None,
+        };
+    }
+
+    if pressed(KeyCode::Up) {
+        palette.dither_strength += 0.05;
+    } else if pressed(KeyCode::Down) {
+        palette.dither_strength = (palette.dither_strength - 0.05).max(0.0);
+    }
+
+    if pressed(KeyCode::P) {
+        // Dither whole blocks, so the dithering pattern lines up with the pixelation.
+        if pixelate.is_some() {
+            commands.entity(camera).remove::<Pixelate>();
+            palette.block_size = Vec2::ONE;
+            palette.anchor = Vec2::ZERO;
+        } else {
+            let pixelate = Pixelate::with_size(6.0, 4.0);
+            *palette = palette.clone().with_blocks_of(&pixelate);
+            commands.entity(camera).insert(pixelate);
+        }
+    }
+}
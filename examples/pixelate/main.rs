@@ -1,26 +1,30 @@
-//! This example shows the pixelation effect as well as
+//! This example shows the pixelation effect with its block shapes as well as
 //! how to toggle a post processing effect at runtime.
 //! All post processing effects may be toggled as such.
 #[path = "../examples_common.rs"]
 mod examples_common;
 
 use bevy::prelude::*;
-use bevy_vfx_bag::{post_processing::pixelate::Pixelate, BevyVfxBagPlugin};
+use bevy_vfx_bag::{
+    post_processing::pixelate::{Pixelate, PixelateAnchor, PixelateSampling, PixelateShape},
+    BevyVfxBagPlugin,
+};
 
 fn main() {
     let mut app = App::new();
 
     app.add_plugin(examples_common::SaneDefaultsPlugin)
         .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
-        .add_plugin(BevyVfxBagPlugin::default())
+        .add_plugin(BevyVfxBagPlugin)
         .add_startup_system(startup)
-        .add_system(examples_common::print_on_change::<Pixelate>)
         .add_system(update)
+        .add_system(pan)
         .run();
 }
 
 fn startup(mut commands: Commands) {
-    info!("Press [t] to toggle, [up/down] to change");
+    info!("Press [t] to toggle, [up/down] to change the block size, [left/right] the aspect ratio");
+    info!("Press [s] to change the shape, [a] to average blocks, [w] to anchor the blocks to the world");
 
     commands.spawn((
         Camera3dBundle {
@@ -34,6 +38,7 @@ fn startup(mut commands: Commands) {
 
 fn update(
     mut saved_settings: Local<Pixelate>,
+    mut anchored: Local<bool>,
     mut commands: Commands,
     keyboard_input: Res<Input<KeyCode>>,
     mut query: Query<(Entity, Option<&mut Pixelate>), With<Camera>>,
@@ -52,11 +57,57 @@ fn update(
         };
     }
 
-    if let (_, Some(mut settings)) = query.single_mut() {
+    if let (entity, Some(mut settings)) = query.single_mut() {
         if keyboard_input.just_pressed(KeyCode::Up) {
             settings.block_size += 1.0;
         } else if keyboard_input.just_pressed(KeyCode::Down) {
             settings.block_size -= 1.0;
         };
+
+        if keyboard_input.just_pressed(KeyCode::Right) {
+            settings.aspect_ratio += 0.25;
+        } else if keyboard_input.just_pressed(KeyCode::Left) {
+            settings.aspect_ratio = (settings.aspect_ratio - 0.25).max(0.25);
+        };
+
+        if keyboard_input.just_pressed(KeyCode::S) {
+            settings.shape = match settings.shape {
+                PixelateShape::Square => PixelateShape::Hexagon,
+                PixelateShape::Hexagon => PixelateShape::Circle,
+                PixelateShape::Circle => PixelateShape::Triangle,
+                PixelateShape::Triangle => PixelateShape::Square,
+            };
+        }
+
+        if keyboard_input.just_pressed(KeyCode::A) {
+            settings.sampling = match settings.sampling {
+                PixelateSampling::Center => PixelateSampling::Average,
+                PixelateSampling::Average => PixelateSampling::Center,
+            };
+        }
+
+        if keyboard_input.just_pressed(KeyCode::W) {
+            if *anchored {
+                info!("Blocks move with the view");
+                commands.entity(entity).remove::<PixelateAnchor>();
+                settings.anchor = Vec2::ZERO;
+            } else {
+                info!("Blocks stay in place in the world");
+                commands.entity(entity).insert(PixelateAnchor::default());
+            }
+            *anchored = !*anchored;
+        }
+
+        // Not printed on every change, since the anchor changes every frame while anchored.
+        if keyboard_input.get_just_pressed().next().is_some() {
+            info!("{}", *settings);
+        }
     }
 }
+
+/// Pans the camera from side to side, which shows the difference anchoring the blocks to the world makes.
+fn pan(time: Res<Time>, mut query: Query<&mut Transform, With<Camera>>) {
+    let mut transform = query.single_mut();
+
+    transform.translation.x = (time.elapsed_seconds() * 0.3).sin() * 2.0;
+}
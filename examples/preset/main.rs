@@ -0,0 +1,70 @@
+//! This example shows how to load a complete set of effects from a `.vfx.ron` preset file.
+//!
+//! The files are watched for changes, so editing them updates the effects while the example runs.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{post_processing::preset::PostProcessingPreset, BevyVfxBagPlugin};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .run();
+}
+
+#[derive(Debug, Resource)]
+struct Presets(Vec<(&'static str, Handle<PostProcessingPreset>)>);
+
+fn startup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    info!("Press [left|right] to change which preset is in use");
+    info!("Edit the files in `assets/presets` to change the effects while running");
+
+    let presets = Presets(vec![
+        ("Dream", asset_server.load("presets/dream.vfx.ron")),
+        ("Retro", asset_server.load("presets/retro.vfx.ron")),
+        (
+            "Found footage",
+            asset_server.load("presets/found-footage.vfx.ron"),
+        ),
+    ]);
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        presets.0[0].1.clone(),
+    ));
+
+    commands.insert_resource(presets);
+}
+
+fn update(
+    mut choice: Local<usize>,
+    keyboard_input: Res<Input<KeyCode>>,
+    presets: Res<Presets>,
+    mut query: Query<&mut Handle<PostProcessingPreset>, With<Camera>>,
+) {
+    let count = presets.0.len();
+
+    let new_choice = if keyboard_input.just_pressed(KeyCode::Left) {
+        (*choice + count - 1) % count
+    } else if keyboard_input.just_pressed(KeyCode::Right) {
+        (*choice + 1) % count
+    } else {
+        return;
+    };
+
+    *choice = new_choice;
+
+    let (name, preset) = &presets.0[new_choice];
+    *query.single_mut() = preset.clone();
+    info!("Preset: {name}");
+}
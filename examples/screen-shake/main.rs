@@ -0,0 +1,78 @@
+//! This example shows how to shake a camera by adding trauma.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        chromatic_aberration::ChromaticAberration,
+        screen_shake::{ScreenShake, ScreenShakeImpulse},
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [1|2|3] to add a small, medium, or large amount of trauma, [C] to toggle chromatic aberration");
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        ScreenShake::default().with_chromatic_aberration(ChromaticAberration {
+            magnitude_r: 0.02,
+            magnitude_g: 0.0,
+            magnitude_b: 0.02,
+            ..default()
+        }),
+    ));
+}
+
+fn update(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut impulses: EventWriter<ScreenShakeImpulse>,
+    mut query: Query<&mut ScreenShake>,
+) {
+    let impulse = [
+        (KeyCode::Key1, 0.2),
+        (KeyCode::Key2, 0.4),
+        (KeyCode::Key3, 0.8),
+    ]
+    .into_iter()
+    .find(|(key, _)| keyboard_input.just_pressed(*key));
+
+    if let Some((_, trauma)) = impulse {
+        impulses.send(ScreenShakeImpulse::all(trauma));
+        info!("Added {trauma} trauma");
+    }
+
+    if keyboard_input.just_pressed(KeyCode::C) {
+        let mut shake = query.single_mut();
+
+        shake.max_chromatic_aberration = match shake.max_chromatic_aberration {
+            Some(_) => None,
+            None => Some(ChromaticAberration {
+                magnitude_r: 0.02,
+                magnitude_g: 0.0,
+                magnitude_b: 0.02,
+                ..default()
+            }),
+        };
+        info!(
+            "Chromatic aberration: {}",
+            shake.max_chromatic_aberration.is_some()
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! This example shows running effects at different stages of the render graph.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{lut::Lut, masks::Mask, Order, PostProcessingStage},
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [M] to move the CRT mask, [L] to move the LUT, between the stages");
+
+    // By default the LUT runs after tonemapping, and the CRT mask after the UI.
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 6., 12.0)
+                .looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+            ..default()
+        },
+        Mask::crt(),
+        Lut::arctic(),
+    ));
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::width(Val::Percent(100.0)),
+                justify_content: JustifyContent::SpaceBetween,
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for corner in ["Health: 100", "Score: 9001"] {
+                parent.spawn(TextBundle::from_section(
+                    corner,
+                    TextStyle {
+                        font_size: 40.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
+
+/// The stage after `stage`, wrapping around.
+fn next(stage: PostProcessingStage) -> PostProcessingStage {
+    let all = PostProcessingStage::ALL;
+    let index = all.iter().position(|s| *s == stage).unwrap_or_default();
+
+    all[(index + 1) % all.len()]
+}
+
+#[allow(clippy::type_complexity)]
+fn update(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    query: Query<(Entity, Option<&Order<Mask>>, Option<&Order<Lut>>), With<Camera>>,
+) {
+    let (camera, mask_order, lut_order) = query.single();
+
+    if keyboard_input.just_pressed(KeyCode::M) {
+        let stage = next(
+            mask_order
+                .and_then(|order| order.stage)
+                .unwrap_or(PostProcessingStage::PostUi),
+        );
+        info!("CRT mask runs in {stage:?}");
+        commands
+            .entity(camera)
+            .insert(Order::<Mask>::new(0.0).in_stage(stage));
+    }
+
+    if keyboard_input.just_pressed(KeyCode::L) {
+        let stage = next(
+            lut_order
+                .and_then(|order| order.stage)
+                .unwrap_or(PostProcessingStage::PostTonemap),
+        );
+        info!("LUT runs in {stage:?}");
+        commands
+            .entity(camera)
+            .insert(Order::<Lut>::new(0.0).in_stage(stage));
+    }
+}
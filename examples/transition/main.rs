@@ -0,0 +1,92 @@
+//! This example shows transitions covering the view, and uncovering it again once they are done.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        transition::{Transition, TransitionFinished},
+        tween::{Easing, EffectTween},
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .add_system(finished)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [1] to fade, [2] to wipe, [3] to close an iris, [4] to dissolve, [5] to pixelate dissolve");
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 6., 12.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+        ..default()
+    });
+}
+
+fn update(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    query: Query<Entity, (With<Camera>, Without<EffectTween<Transition>>)>,
+) {
+    // Wait for the current transition to be done.
+    let Ok(camera) = query.get_single() else {
+        return;
+    };
+
+    let transition = if keyboard_input.just_pressed(KeyCode::Key1) {
+        Transition::fade()
+    } else if keyboard_input.just_pressed(KeyCode::Key2) {
+        Transition::wipe().with_color(Color::MIDNIGHT_BLUE)
+    } else if keyboard_input.just_pressed(KeyCode::Key3) {
+        Transition::iris()
+    } else if keyboard_input.just_pressed(KeyCode::Key4) {
+        Transition::dissolve().with_color(Color::ORANGE_RED)
+    } else if keyboard_input.just_pressed(KeyCode::Key5) {
+        Transition::pixelate_dissolve().with_color(Color::WHITE)
+    } else {
+        return;
+    };
+
+    commands.entity(camera).insert((
+        transition,
+        transition
+            .animate_to(1.0, Duration::from_secs(1))
+            .with_easing(Easing::QuadraticInOut),
+    ));
+}
+
+// Once covered, this is where a scene would be changed.
+// Then the transition goes back, and is removed once the view is uncovered.
+fn finished(
+    mut commands: Commands,
+    mut events: EventReader<TransitionFinished>,
+    transitions: Query<&Transition>,
+) {
+    for event in events.iter() {
+        if event.progress < 1.0 {
+            commands.entity(event.camera).remove::<Transition>();
+            info!("Transition done");
+            continue;
+        }
+
+        let Ok(transition) = transitions.get(event.camera) else {
+            continue;
+        };
+        commands.entity(event.camera).insert(
+            transition
+                .animate_to(0.0, Duration::from_secs(1))
+                .with_easing(Easing::QuadraticInOut),
+        );
+    }
+}
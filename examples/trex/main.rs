@@ -4,14 +4,11 @@ mod examples_common;
 use bevy::prelude::*;
 use bevy_vfx_bag::{post_processing::wave::Wave, BevyVfxBagPlugin};
 
-#[derive(Debug, Resource, Default)]
-struct SlowerTime(Time);
-
 fn main() {
     App::new()
         .add_plugin(examples_common::SaneDefaultsPlugin)
         .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
-        .add_plugin(BevyVfxBagPlugin::default())
+        .add_plugin(BevyVfxBagPlugin)
         .add_startup_system(startup)
         .add_system(update)
         .run();
@@ -0,0 +1,130 @@
+//! This example shows how to animate the parameters of effects via tweens.
+#[path = "../examples_common.rs"]
+mod examples_common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::{
+    post_processing::{
+        chromatic_aberration::ChromaticAberration,
+        pixelate::Pixelate,
+        tween::{Easing, EffectTween, EffectTweenCompleted},
+        wave::Wave,
+    },
+    BevyVfxBagPlugin,
+};
+
+fn main() {
+    let mut app = App::new();
+
+    app.add_plugin(examples_common::SaneDefaultsPlugin)
+        .add_plugin(examples_common::ShapesExamplePlugin::without_3d_camera())
+        .add_plugin(BevyVfxBagPlugin)
+        .add_startup_system(startup)
+        .add_system(update)
+        .add_system(completed)
+        .run();
+}
+
+fn startup(mut commands: Commands) {
+    info!("Press [S] to shake the camera, [D] to flash as if taking damage, [P] to toggle pulsing pixels");
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 6., 12.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+        ..default()
+    });
+}
+
+fn update(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    query: Query<(Entity, Option<&EffectTween<Pixelate>>), With<Camera>>,
+) {
+    let (camera, pulsing) = query.single();
+    let mut camera = commands.entity(camera);
+
+    if keyboard_input.just_pressed(KeyCode::S) {
+        // The amplitudes decay to zero, which stills the waves.
+        let shake = Wave {
+            waves_x: 2.0,
+            waves_y: 3.0,
+            speed_x: 30.,
+            speed_y: 20.,
+            amplitude_x: 0.02,
+            amplitude_y: 0.02,
+        };
+        let still = Wave {
+            amplitude_x: 0.0,
+            amplitude_y: 0.0,
+            ..shake
+        };
+
+        camera.insert((
+            shake,
+            EffectTween::new(shake, still, Duration::from_secs_f32(0.6))
+                .with_easing(Easing::ExponentialOut),
+        ));
+    }
+
+    if keyboard_input.just_pressed(KeyCode::D) {
+        let flash = ChromaticAberration {
+            magnitude_r: 0.03,
+            magnitude_g: 0.0,
+            magnitude_b: 0.03,
+            ..default()
+        };
+        let none = ChromaticAberration {
+            magnitude_r: 0.0,
+            magnitude_b: 0.0,
+            ..flash
+        };
+
+        camera.insert((
+            flash,
+            EffectTween::new(flash, none, Duration::from_secs_f32(0.3))
+                .with_easing(Easing::QuadraticOut),
+        ));
+    }
+
+    if keyboard_input.just_pressed(KeyCode::P) {
+        if pulsing.is_some() {
+            camera.remove::<(Pixelate, EffectTween<Pixelate>)>();
+        } else {
+            camera.insert((
+                Pixelate::default(),
+                EffectTween::new(
+                    Pixelate::new(1.0),
+                    Pixelate::new(16.0),
+                    Duration::from_secs(2),
+                )
+                .with_easing(Easing::SineInOut)
+                .ping_pong(),
+            ));
+        }
+    }
+}
+
+// Once the shake and flash are done, the effects are no longer needed.
+fn completed(
+    mut commands: Commands,
+    mut waves: EventReader<EffectTweenCompleted<Wave>>,
+    mut flashes: EventReader<EffectTweenCompleted<ChromaticAberration>>,
+    mut pulses: EventReader<EffectTweenCompleted<Pixelate>>,
+) {
+    for event in waves.iter() {
+        commands.entity(event.entity).remove::<Wave>();
+        info!("Shake done");
+    }
+
+    for event in flashes.iter() {
+        commands
+            .entity(event.entity)
+            .remove::<ChromaticAberration>();
+        info!("Flash done");
+    }
+
+    for _ in pulses.iter() {
+        info!("Pulse done");
+    }
+}
@@ -0,0 +1,479 @@
+use std::{f32::consts::PI, fmt::Display};
+
+use bevy::{
+    math::{Vec3Swizzles, Vec4Swizzles},
+    prelude::*,
+    render::render_resource::TextureFormat,
+};
+
+use crate::post_processing::{
+    blur::{Blur, BlurKernel, BlurUniform},
+    chromatic_aberration::{
+        ChromaticAberration, ChromaticAberrationMode, ChromaticAberrationUniform,
+    },
+    color_grade::{ColorGrade, ColorGradeUniform},
+    flip::{Flip, FlipUniform},
+    lens_distortion::{LensDistortion, LensDistortionUniform},
+    lut::{Lut, LutUniform},
+    masks::{Mask, MaskUniform, MaskVariant},
+    palette::{Dither, Palette, PaletteUniform},
+    pixelate::{Pixelate, PixelateSampling, PixelateShape, PixelateUniform},
+    wave::Wave,
+};
+
+use texels::{linear_to_srgb, srgb_to_linear, Texels};
+
+mod texels;
+
+/// An effect which can be applied to an [`Image`] on the CPU.
+///
+/// The result matches what the effect's shader renders when the image is the view,
+/// up to small differences in precision.
+/// This is useful for testing, and for baking effects into textures.
+///
+/// Images must be 2D and either `Rgba8Unorm`, `Rgba8UnormSrgb`, `Rgba16Unorm`, `Rgba16Float`, or `Rgba32Float`.
+/// sRGB images are converted to linear colors before the effect is applied, like when sampled on the GPU.
+/// The returned image has the same format as the given one.
+pub trait CpuEffect {
+    /// Applies the effect to the image, returning the result.
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError>;
+}
+
+/// Errors which may happen when applying a [`CpuEffect`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum CpuError {
+    /// The image is not a 2D image with a single layer.
+    Not2d,
+
+    /// The image format is not supported.
+    UnsupportedFormat(TextureFormat),
+
+    /// The image of a [`Lut`] is neither 3D nor a strip of `N` blocks of `N`x`N` texels.
+    InvalidLutSize {
+        /// The width of the image.
+        width: u32,
+        /// The height of the image.
+        height: u32,
+    },
+
+    /// An image used by the effect is not in the assets.
+    MissingImage(Handle<Image>),
+}
+
+impl Display for CpuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CpuError::Not2d => write!(f, "Only 2D images with a single layer are supported"),
+            CpuError::UnsupportedFormat(format) => {
+                write!(f, "The image format {format:?} is not supported")
+            }
+            CpuError::InvalidLutSize { width, height } => write!(
+                f,
+                "The image is {width}x{height}, but a LUT of size N must be N * N wide and N tall"
+            ),
+            CpuError::MissingImage(handle) => write!(f, "The image {handle:?} is not loaded"),
+        }
+    }
+}
+
+impl std::error::Error for CpuError {}
+
+/// Calls `fragment` for the UV coordinates of each texel, like a fullscreen fragment shader.
+fn apply(image: &Image, fragment: impl Fn(&Texels, Vec2) -> Vec4) -> Result<Image, CpuError> {
+    let source = Texels::from_image(image)?;
+
+    Ok(source.map(|uv| fragment(&source, uv)).into_image(image))
+}
+
+impl CpuEffect for Blur {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let blur = BlurUniform::from(*self);
+        let p = |x: f32, y: f32| Vec2::new(x, y) * blur.kernel_radius;
+
+        let taps = self.kernel.taps() as i32;
+
+        let pass = |source: &Texels, blurred: &dyn Fn(&Texels, Vec2) -> Vec3| {
+            source.map(|uv| blurred(source, uv).extend(1.0))
+        };
+
+        let s = |t: &Texels, uv: Vec2| t.sample(uv).xyz();
+
+        let gaussian = |direction: Vec2| {
+            move |t: &Texels, uv: Vec2| {
+                let step = direction * blur.kernel_radius / taps as f32;
+
+                // Distances are relative to the kernel radius, so sigma is too.
+                let two_sigma_squared = 2.0 * (blur.sigma * blur.sigma).max(0.0001);
+
+                let (total, weights) =
+                    (-taps..=taps).fold((Vec3::ZERO, 0.0), |(total, weights), i| {
+                        let x = i as f32 / taps as f32;
+                        let weight = (-(x * x) / two_sigma_squared).exp();
+
+                        (
+                            total + s(t, uv + step * i as f32) * weight,
+                            weights + weight,
+                        )
+                    });
+
+                total / weights
+            }
+        };
+
+        let diagonals = |t: &Texels, uv: Vec2| {
+            s(t, uv + p(1.0, 1.0))
+                + s(t, uv + p(-1.0, 1.0))
+                + s(t, uv + p(-1.0, -1.0))
+                + s(t, uv + p(1.0, -1.0))
+        };
+
+        let source = Texels::from_image(image)?;
+
+        let blurred = match self.kernel {
+            BlurKernel::Box3x3 => pass(&source, &|t, uv| {
+                let neighbours = [
+                    p(1.0, 0.0),
+                    p(1.0, 1.0),
+                    p(0.0, 1.0),
+                    p(-1.0, 1.0),
+                    p(-1.0, 0.0),
+                    p(-1.0, -1.0),
+                    p(0.0, -1.0),
+                    p(1.0, -1.0),
+                ];
+
+                neighbours
+                    .into_iter()
+                    .fold(s(t, uv), |total, offset| total + s(t, uv + offset))
+                    / 9.
+            }),
+            BlurKernel::Gaussian { .. } => {
+                let horizontal = pass(&source, &gaussian(Vec2::X));
+                pass(&horizontal, &gaussian(Vec2::Y))
+            }
+            BlurKernel::Kawase => {
+                let down = pass(&source, &|t, uv| (s(t, uv) * 4.0 + diagonals(t, uv)) / 8.0);
+                pass(&down, &|t, uv| {
+                    let axes = s(t, uv + p(2.0, 0.0))
+                        + s(t, uv + p(-2.0, 0.0))
+                        + s(t, uv + p(0.0, 2.0))
+                        + s(t, uv + p(0.0, -2.0));
+
+                    (axes + diagonals(t, uv) * 2.0) / 12.0
+                })
+            }
+        };
+
+        // The amount is applied once, against the input, after all passes.
+        let output = source.map(|uv| {
+            let original = source.sample(uv).xyz();
+            original
+                .lerp(blurred.sample(uv).xyz(), blur.amount)
+                .extend(1.0)
+        });
+
+        Ok(output.into_image(image))
+    }
+}
+
+impl CpuEffect for ChromaticAberration {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let ca = ChromaticAberrationUniform::from(*self);
+        let radial = self.mode == ChromaticAberrationMode::Radial;
+
+        apply(image, |t, uv| {
+            let [r, g, b] = ca.offsets(uv, radial);
+
+            Vec4::new(
+                t.sample(uv + r).x,
+                t.sample(uv + g).y,
+                t.sample(uv + b).z,
+                1.0,
+            )
+        })
+    }
+}
+
+impl CpuEffect for ColorGrade {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let grade = ColorGradeUniform::from(*self);
+
+        apply(image, |t, uv| grade.grade(t.sample(uv).xyz()).extend(1.0))
+    }
+}
+
+impl CpuEffect for Flip {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let flip = FlipUniform::from(*self);
+
+        apply(image, |t, uv| {
+            t.sample((Vec2::new(flip.x, flip.y) - uv).abs())
+        })
+    }
+}
+
+impl CpuEffect for LensDistortion {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let lens = LensDistortionUniform::from(*self);
+        let inside = |uv: Vec2| uv.cmpge(Vec2::ZERO).all() && uv.cmple(Vec2::ONE).all();
+
+        apply(image, |t, uv| {
+            let uvs = lens.sample_uvs(uv, t.dimensions(), self.zoom_to_fill);
+            let channel = |index: usize| {
+                let uv = uvs[index];
+                if inside(uv) {
+                    t.sample(uv)[index]
+                } else {
+                    0.0
+                }
+            };
+
+            Vec4::new(channel(0), channel(1), channel(2), 1.0)
+        })
+    }
+}
+
+impl CpuEffect for Mask {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let mask = MaskUniform::from(*self);
+
+        let result = |uv: Vec2| match self.variant {
+            MaskVariant::Square => {
+                let uv_big = (uv * mask.strength).clamp(Vec2::ZERO, Vec2::ONE);
+                let uv_big_inv = ((1. - uv) * mask.strength).clamp(Vec2::ZERO, Vec2::ONE);
+                let square = uv_big * uv_big_inv;
+
+                square.x * square.y
+            }
+            MaskVariant::Crt => {
+                let square = uv * (1. - uv);
+                let norm = square.x * square.y;
+
+                (norm * norm * mask.strength).clamp(0., 1.)
+            }
+            MaskVariant::Vignette => {
+                let uv_centered = (uv * 2. - 1.) * mask.strength;
+                let dist = uv_centered.length().clamp(0., 1.).powf(1.5);
+
+                (1. - dist + 0.05).clamp(0., 1.)
+            }
+        };
+
+        apply(image, |t, uv| {
+            (t.sample(uv).xyz() * (result(uv) + mask.fade).clamp(0., 1.)).extend(1.0)
+        })
+    }
+}
+
+/// The index of the block containing the pixel `position`, like `pixelate_grid_block` in `pixelate-functions.wgsl`.
+fn pixelate_grid_block(position: Vec2, block_size: Vec2, anchor: Vec2) -> Vec2 {
+    ((position - anchor) / block_size).floor()
+}
+
+/// Only the `colors` of the palette are used, not its `image`.
+impl CpuEffect for Palette {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let palette = PaletteUniform::from(self);
+
+        apply(image, |t, uv| {
+            let mut color = linear_to_srgb(t.sample(uv).xyz().max(Vec3::ZERO));
+
+            if self.dither != Dither::None {
+                let block =
+                    pixelate_grid_block(uv * t.dimensions(), palette.block_size, palette.anchor);
+                let threshold = palette.threshold(block.x as i32, block.y as i32);
+                color += (threshold - 0.5) * palette.dither_strength;
+            }
+
+            srgb_to_linear(palette.nearest(color)).extend(1.0)
+        })
+    }
+}
+
+/// The center of the cell containing `q`, like `cell_center` in `pixelate.wgsl`.
+fn pixelate_cell_center(shape: PixelateShape, q: Vec2) -> Vec2 {
+    match shape {
+        PixelateShape::Square | PixelateShape::Circle => q.floor() + 0.5,
+        PixelateShape::Hexagon => {
+            let r = Vec2::new(1.0, 3f32.sqrt());
+            let h = r * 0.5;
+            let modulo = |x: Vec2| x - r * (x / r).floor();
+            let a = modulo(q) - h;
+            let b = modulo(q - h) - h;
+
+            if a.length_squared() < b.length_squared() {
+                q - a
+            } else {
+                q - b
+            }
+        }
+        PixelateShape::Triangle => {
+            let block = q.floor();
+            let f = q - block;
+
+            let centroid = if ((block.x + block.y) * 0.5).rem_euclid(1.0) < 0.25 {
+                if f.x > f.y {
+                    Vec2::new(2.0, 1.0)
+                } else {
+                    Vec2::new(1.0, 2.0)
+                }
+            } else if f.x + f.y < 1.0 {
+                Vec2::ONE
+            } else {
+                Vec2::splat(2.0)
+            };
+
+            block + centroid / 3.0
+        }
+    }
+}
+
+impl CpuEffect for Pixelate {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let pixelate = PixelateUniform::from(*self);
+        let block_size = pixelate.block_size;
+
+        apply(image, |t, uv| {
+            let resolution = t.dimensions();
+            let q = (uv * resolution - pixelate.anchor) / block_size;
+            let center_q = pixelate_cell_center(self.shape, q);
+            let center = pixelate.anchor + center_q * block_size;
+
+            let color = match self.sampling {
+                PixelateSampling::Center => t.load_2d(
+                    center
+                        .floor()
+                        .clamp(Vec2::ZERO, resolution - 1.0)
+                        .as_ivec2(),
+                ),
+                PixelateSampling::Average => {
+                    (0..4)
+                        .flat_map(|y| (0..4).map(move |x| Vec2::new(x as f32, y as f32)))
+                        .map(|xy| {
+                            let offset = ((xy + 0.5) / 4.0 - 0.5) * block_size;
+                            t.sample((center + offset) / resolution)
+                        })
+                        .sum::<Vec4>()
+                        / 16.0
+                }
+            };
+
+            match self.shape {
+                PixelateShape::Circle => {
+                    let from_edge =
+                        (1.0 - ((q - center_q) * 2.0).length()) * 0.5 * block_size.min_element();
+                    (color.xyz() * (from_edge + 0.5).clamp(0.0, 1.0)).extend(color.w)
+                }
+                _ => color,
+            }
+        })
+    }
+}
+
+impl Wave {
+    /// Applies the wave to the image on the CPU as it looks `time` seconds after startup.
+    ///
+    /// See [`CpuEffect`].
+    pub fn apply_cpu_at(&self, image: &Image, time: f32) -> Result<Image, CpuError> {
+        apply(image, |t, uv| {
+            let pi_uv = PI * uv;
+            let pi_time = PI * time;
+
+            let offset_x =
+                (pi_uv.y * self.waves_x + pi_time * self.speed_x).sin() * self.amplitude_x;
+            let offset_y =
+                (pi_uv.x * self.waves_y + pi_time * self.speed_y).sin() * self.amplitude_y;
+
+            t.sample(uv + Vec2::new(offset_x, offset_y))
+        })
+    }
+}
+
+/// Applies the wave as it looks at startup.
+/// Use [`Wave::apply_cpu_at`] for other points in time.
+impl CpuEffect for Wave {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        self.apply_cpu_at(image, 0.0)
+    }
+}
+
+/// A [`Lut`] together with its images, which is needed to apply it on the CPU.
+///
+/// The images may be LUT strips or 3D images, like for [`Lut::new`].
+#[derive(Debug, Clone, Copy)]
+pub struct CpuLut<'a> {
+    /// The settings of the LUT.
+    /// Its images are not used, the ones below are.
+    pub lut: &'a Lut,
+
+    /// The image of the LUT.
+    pub texture: &'a Image,
+
+    /// The image of the LUT to cross-fade to, if any.
+    pub cross_fade_texture: Option<&'a Image>,
+}
+
+impl<'a> CpuLut<'a> {
+    /// Looks up the images of the LUT in the assets.
+    pub fn from_assets(lut: &'a Lut, images: &'a Assets<Image>) -> Result<Self, CpuError> {
+        let get = |handle: &Handle<Image>| {
+            images
+                .get(handle)
+                .ok_or_else(|| CpuError::MissingImage(handle.clone_weak()))
+        };
+
+        Ok(Self {
+            lut,
+            texture: get(&lut.texture)?,
+            cross_fade_texture: lut.cross_fade_texture.as_ref().map(get).transpose()?,
+        })
+    }
+}
+
+impl CpuEffect for CpuLut<'_> {
+    fn apply_cpu(&self, image: &Image) -> Result<Image, CpuError> {
+        let mut settings = LutUniform::from(self.lut);
+        // The images given here decide whether there is a cross-fade, not the handles of the LUT.
+        settings.blend = if self.cross_fade_texture.is_some() {
+            self.lut.blend
+        } else {
+            0.0
+        };
+
+        let lut = Texels::from_lut_image(self.texture)?;
+        let cross_fade = self
+            .cross_fade_texture
+            .map(Texels::from_lut_image)
+            .transpose()?;
+
+        // The colors zero and one should map to the centers of the first and last texels,
+        // not their outer edges, so the color is scaled and offset by half a texel.
+        let sample_lut = |lut: &Texels, color: Vec3| {
+            let size = lut.dimensions().x;
+            let scale = (size - 1.0) / size;
+            let half_texel = 0.5 / size;
+
+            lut.sample_3d(color * scale + half_texel).xyz()
+        };
+
+        apply(image, |t, uv| {
+            let original = t.sample(uv).xyz();
+
+            // Green and blue are swapped in the layout of the LUT texture.
+            let raw_color = original.xzy();
+
+            let graded = sample_lut(&lut, raw_color);
+            let graded = match &cross_fade {
+                Some(cross_fade) => graded.lerp(sample_lut(cross_fade, raw_color), settings.blend),
+                None => graded,
+            };
+
+            // Without a split the normal is zero, so everything is graded.
+            if (uv - settings.split_point).dot(settings.split_normal) < 0.0 {
+                return original.extend(1.0);
+            }
+
+            original.lerp(graded, settings.intensity).extend(1.0)
+        })
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::{
+    math::{Vec3Swizzles, Vec4Swizzles},
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+use super::CpuError;
+
+/// The texels of an image as floats, sampled the way the effect shaders sample their textures:
+/// with linear filtering, clamped to the edges.
+#[derive(Debug, Clone)]
+pub(crate) struct Texels {
+    size: UVec3,
+    texels: Vec<Vec4>,
+}
+
+impl Texels {
+    /// Decodes a 2D image.
+    /// sRGB images are converted to linear colors, like the GPU does when sampling them.
+    pub(crate) fn from_image(image: &Image) -> Result<Self, CpuError> {
+        let Extent3d {
+            width,
+            height,
+            depth_or_array_layers,
+        } = image.texture_descriptor.size;
+
+        if image.texture_descriptor.dimension != TextureDimension::D2 || depth_or_array_layers != 1
+        {
+            return Err(CpuError::Not2d);
+        }
+
+        Ok(Self {
+            size: UVec3::new(width, height, 1),
+            texels: decode(image, true)?,
+        })
+    }
+
+    /// Decodes the image of a [`Lut`](crate::post_processing::lut::Lut) as a 3D texture.
+    ///
+    /// Both strips and images already made 3D are accepted.
+    /// The values are not colors, so sRGB is ignored.
+    pub(crate) fn from_lut_image(image: &Image) -> Result<Self, CpuError> {
+        let Extent3d { width, height, .. } = image.texture_descriptor.size;
+
+        // A strip of `N` blocks of `N`x`N` texels has the same layout in memory as a 3D texture.
+        let size = match image.texture_descriptor.dimension {
+            TextureDimension::D3 => width,
+            _ if height >= 2 && width == height * height => height,
+            _ => return Err(CpuError::InvalidLutSize { width, height }),
+        };
+
+        Ok(Self {
+            size: UVec3::splat(size),
+            texels: decode(image, false)?,
+        })
+    }
+
+    /// The width and height, like `textureDimensions` in WGSL.
+    pub(crate) fn dimensions(&self) -> Vec2 {
+        self.size.xy().as_vec2()
+    }
+
+    /// Calls `f` with the UV coordinates of the center of each texel of a 2D image,
+    /// and returns the results as a new image of the same size.
+    pub(crate) fn map(&self, f: impl Fn(Vec2) -> Vec4) -> Self {
+        let dimensions = self.dimensions();
+
+        let texels = (0..self.size.y)
+            .flat_map(|y| (0..self.size.x).map(move |x| UVec2::new(x, y)))
+            .map(|texel| f((texel.as_vec2() + 0.5) / dimensions))
+            .collect();
+
+        Self {
+            size: self.size,
+            texels,
+        }
+    }
+
+    fn load(&self, texel: IVec3) -> Vec4 {
+        let texel = texel
+            .clamp(IVec3::ZERO, self.size.as_ivec3() - 1)
+            .as_uvec3();
+
+        self.texels
+            [(texel.x + texel.y * self.size.x + texel.z * self.size.x * self.size.y) as usize]
+    }
+
+    /// Loads a texel of a 2D image, like `textureLoad` in WGSL, clamped to the edges.
+    pub(crate) fn load_2d(&self, texel: IVec2) -> Vec4 {
+        self.load(texel.extend(0))
+    }
+
+    /// Samples a 2D image, like `textureSample` in WGSL.
+    pub(crate) fn sample(&self, uv: Vec2) -> Vec4 {
+        self.sample_3d(uv.extend(0.5))
+    }
+
+    /// Samples a 3D image, like `textureSample` in WGSL.
+    pub(crate) fn sample_3d(&self, uvw: Vec3) -> Vec4 {
+        // Texel centers are at half texel offsets.
+        let position = uvw * self.size.as_vec3() - 0.5;
+        let base = position.floor();
+        let fraction = position - base;
+        let base = base.as_ivec3();
+
+        let lerp_x = |y: i32, z: i32| {
+            self.load(base + IVec3::new(0, y, z))
+                .lerp(self.load(base + IVec3::new(1, y, z)), fraction.x)
+        };
+        let lerp_y = |z: i32| lerp_x(0, z).lerp(lerp_x(1, z), fraction.y);
+
+        lerp_y(0).lerp(lerp_y(1), fraction.z)
+    }
+
+    /// Encodes the texels in the format of `like`, and returns a copy of `like` with them.
+    pub(crate) fn into_image(self, like: &Image) -> Image {
+        let srgb = like.texture_descriptor.format.describe().srgb;
+
+        let data = match like.texture_descriptor.format {
+            TextureFormat::Rgba16Unorm => self
+                .texels
+                .iter()
+                .flat_map(|texel| texel.to_array())
+                .map(|value| (value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16)
+                .flat_map(|value| value.to_le_bytes())
+                .collect(),
+            TextureFormat::Rgba16Float => self
+                .texels
+                .iter()
+                .flat_map(|texel| texel.to_array())
+                .flat_map(|value| half::f16::from_f32(value).to_le_bytes())
+                .collect(),
+            TextureFormat::Rgba32Float => self
+                .texels
+                .iter()
+                .flat_map(|texel| texel.to_array())
+                .flat_map(|value| value.to_le_bytes())
+                .collect(),
+            // Only 8-bit formats are left, as others are rejected when decoding.
+            _ => self
+                .texels
+                .iter()
+                .map(|texel| {
+                    if srgb {
+                        linear_to_srgb(texel.xyz()).extend(texel.w)
+                    } else {
+                        *texel
+                    }
+                })
+                .flat_map(|texel| texel.to_array())
+                .map(|value| (value.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8)
+                .collect(),
+        };
+
+        let mut image = like.clone();
+        image.data = data;
+        image
+    }
+}
+
+fn decode(image: &Image, convert_srgb: bool) -> Result<Vec<Vec4>, CpuError> {
+    let format = image.texture_descriptor.format;
+
+    let values: Vec<f32> = match format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => image
+            .data
+            .iter()
+            .map(|&value| value as f32 / u8::MAX as f32)
+            .collect(),
+        TextureFormat::Rgba16Unorm => image
+            .data
+            .chunks_exact(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32)
+            .collect(),
+        TextureFormat::Rgba16Float => image
+            .data
+            .chunks_exact(2)
+            .map(|bytes| half::f16::from_le_bytes([bytes[0], bytes[1]]).to_f32())
+            .collect(),
+        TextureFormat::Rgba32Float => image
+            .data
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect(),
+        other => return Err(CpuError::UnsupportedFormat(other)),
+    };
+
+    let srgb = convert_srgb && format.describe().srgb;
+
+    Ok(values
+        .chunks_exact(4)
+        .map(Vec4::from_slice)
+        .map(|texel| {
+            if srgb {
+                srgb_to_linear(texel.xyz()).extend(texel.w)
+            } else {
+                texel
+            }
+        })
+        .collect())
+}
+
+pub(crate) fn srgb_to_linear(color: Vec3) -> Vec3 {
+    Vec3::from_array(color.to_array().map(|c| {
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    }))
+}
+
+pub(crate) fn linear_to_srgb(color: Vec3) -> Vec3 {
+    Vec3::from_array(color.to_array().map(|c| {
+        if c <= 0.0031308 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        }
+    }))
+}
@@ -1,4 +1,5 @@
 #![allow(clippy::too_many_arguments)] // Bevy fns tend to have many args.
+#![allow(dead_code)] // The `ShaderType` derive emits per-field checks which newer compilers consider unused.
 #![deny(clippy::unwrap_used)] // Let's try to explain invariants when we unwrap (so use expect).
 #![deny(missing_docs)] // Let's try to have good habits.
 #![doc = include_str!("../README.md")]
@@ -6,6 +7,10 @@
 /// Post processing effects.
 pub mod post_processing;
 
+/// Applying effects to images on the CPU.
+#[cfg(feature = "cpu")]
+pub mod cpu;
+
 mod plugin;
 
 pub use plugin::BevyVfxBagPlugin;
@@ -3,6 +3,9 @@ use bevy::prelude::*;
 use crate::post_processing;
 
 /// The main plugin needed to use any effects.
+///
+/// Add it after the `DefaultPlugins`, such that effects can run after tonemapping and the UI.
+/// See [`PostProcessingStage`](crate::post_processing::PostProcessingStage).
 #[derive(Debug, Default)]
 pub struct BevyVfxBagPlugin;
 
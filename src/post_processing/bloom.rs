@@ -0,0 +1,559 @@
+use std::fmt::Display;
+
+use bevy::{
+    asset::load_internal_asset,
+    ecs::{
+        query::{QueryItem, ROQueryItem},
+        system::{lifetimeless::Read, SystemParamItem},
+    },
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        camera::ExtractedCamera,
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+            RenderPhase, SetItemPipeline, TrackedRenderPass,
+        },
+        render_resource::{
+            AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+            BindGroupLayoutEntry, BindingResource, BindingType, BlendComponent, BlendFactor,
+            BlendOperation, BlendState, BufferBindingType, Extent3d, FilterMode, PipelineCache,
+            RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor, ShaderDefVal,
+            ShaderStages, ShaderType, SpecializedRenderPipeline, SpecializedRenderPipelines,
+            TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType, TextureUsages,
+            TextureView, TextureViewDimension,
+        },
+        renderer::RenderDevice,
+        texture::{CachedTexture, TextureCache},
+        view::ExtractedView,
+        RenderSet,
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    effect_mask::{EffectMasking, Masked},
+    DrawPostProcessing, Order, PostProcessingPhaseItem, PostProcessingPipelineKey,
+    PostProcessingSharedLayout, PostProcessingStage, PostProcessingTarget,
+    SetTextureSamplerGlobals,
+};
+
+pub(crate) const BLOOM_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 7405372187314059721);
+
+/// The most mip levels the bloom texture will have.
+/// Each level halves the resolution, so this decides how far the glow can spread.
+const MAX_MIP_LEVELS: u32 = 6;
+
+/// Intermediate textures hold values above one, so they need a floating point format
+/// regardless of the format of the view target.
+const BLOOM_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rg11b10Float;
+
+type DrawBloom = (
+    // The pipeline must be set in order to use the correct bind group,
+    // access the correct shaders, and so on.
+    SetItemPipeline,
+    // Common to post processing items is that they all use the same
+    // first bind group, which has the input texture (the scene) and
+    // the sampler for that.
+    SetTextureSamplerGlobals<0>,
+    // Here we set the bind group for the bloom pass.
+    // This has the mip level read by the pass as well as the uniform.
+    SetBloomPassBindGroup<1>,
+    // Lastly we draw vertices.
+    // This is simple for a post processing effect, since we just draw
+    // a full screen triangle.
+    DrawPostProcessing,
+);
+
+/// A single pass of the bloom effect.
+///
+/// Bloom needs several passes per view, each reading a different mip level,
+/// so each pass gets its own entity in the render world.
+#[derive(Debug, Component)]
+struct BloomPass {
+    bind_group: BindGroup,
+    uniform_offset: u32,
+}
+
+struct SetBloomPassBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetBloomPassBindGroup<I> {
+    type Param = ();
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<BloomPass>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        bloom_pass: ROQueryItem<'w, Self::ItemWorldQuery>,
+        _param: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        pass.set_bind_group(I, &bloom_pass.bind_group, &[bloom_pass.uniform_offset]);
+        RenderCommandResult::Success
+    }
+}
+
+/// The passes of the bloom effect, in the order they run.
+/// Each pass is a shader definition of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BloomStage {
+    /// Reads the scene, applies the threshold, and writes the first mip level.
+    FirstDownsample,
+
+    /// Reads a mip level and writes the next, smaller one.
+    Downsample,
+
+    /// Reads a mip level and adds it to the next, larger one.
+    Upsample,
+
+    /// Reads the scene and the first mip level, and writes the result to the view target.
+    Composite,
+}
+
+impl From<BloomStage> for ShaderDefVal {
+    fn from(stage: BloomStage) -> Self {
+        match stage {
+            BloomStage::FirstDownsample => "FIRST_DOWNSAMPLE",
+            BloomStage::Downsample => "DOWNSAMPLE",
+            BloomStage::Upsample => "UPSAMPLE",
+            BloomStage::Composite => "COMPOSITE",
+        }
+        .into()
+    }
+}
+
+impl BloomStage {
+    fn key(self, view: &ExtractedView, mip_count: u32) -> PostProcessingPipelineKey {
+        let mut key = PostProcessingPipelineKey::for_view(view, vec![self.into()]);
+
+        match self {
+            // The composite writes to the view target,
+            // and needs to know how many levels were summed.
+            BloomStage::Composite => {
+                key.shader_defs
+                    .push(ShaderDefVal::UInt("MIP_LEVELS".into(), mip_count));
+                key
+            }
+            _ => PostProcessingPipelineKey {
+                target_format: BLOOM_TEXTURE_FORMAT,
+                ..key
+            },
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct BloomData {
+    shared_layout: BindGroupLayout,
+    layout: BindGroupLayout,
+    sampler: Sampler,
+}
+
+impl FromWorld for BloomData {
+    fn from_world(world: &mut World) -> Self {
+        let layout = super::create_layout(
+            world,
+            "Bloom",
+            &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(BloomUniform::min_size()),
+                    },
+                    count: None,
+                },
+            ],
+        );
+
+        let sampler = world
+            .resource::<RenderDevice>()
+            .create_sampler(&SamplerDescriptor {
+                label: Some("Bloom Sampler"),
+                address_mode_u: AddressMode::ClampToEdge,
+                address_mode_v: AddressMode::ClampToEdge,
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                ..default()
+            });
+
+        let shared_layout = world
+            .resource::<PostProcessingSharedLayout>()
+            .shared_layout
+            .clone();
+
+        BloomData {
+            shared_layout,
+            layout,
+            sampler,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for BloomData {
+    type Key = PostProcessingPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let additive = key.shader_defs.contains(&BloomStage::Upsample.into());
+
+        let mut descriptor = super::render_pipeline_descriptor(
+            "Bloom",
+            &self.shared_layout,
+            &self.layout,
+            BLOOM_SHADER_HANDLE.typed(),
+            key,
+        );
+
+        // Upsampling adds to the contents of the mip level being written to.
+        if additive {
+            let fragment = descriptor
+                .fragment
+                .as_mut()
+                .expect("Post processing pipelines should have a fragment state");
+
+            for target in fragment.targets.iter_mut().flatten() {
+                target.blend = Some(BlendState {
+                    color: BlendComponent {
+                        src_factor: BlendFactor::One,
+                        dst_factor: BlendFactor::One,
+                        operation: BlendOperation::Add,
+                    },
+                    alpha: BlendComponent::REPLACE,
+                });
+            }
+        }
+
+        descriptor
+    }
+}
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            BLOOM_SHADER_HANDLE,
+            concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/", "bloom.wgsl"),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<Bloom>().register_type::<Order<Bloom>>();
+
+        // This puts the uniform into the render world.
+        app.add_plugin(ExtractComponentPlugin::<Bloom>::default())
+            .add_plugin(UniformComponentPlugin::<BloomUniform>::default());
+
+        super::render_app(app)
+            .add_system(
+                super::extract_post_processing_camera_phases::<Bloom>.in_schedule(ExtractSchedule),
+            )
+            .init_resource::<BloomData>()
+            .init_resource::<SpecializedRenderPipelines<BloomData>>()
+            .add_system(prepare.in_set(RenderSet::Prepare))
+            .add_system(queue.in_set(RenderSet::Queue))
+            .add_render_command::<PostProcessingPhaseItem, DrawBloom>();
+    }
+}
+
+/// The mip chain the bloom effect downsamples into and upsamples from.
+/// The first level is half the size of the view target.
+///
+/// Each level is a separate texture, since not all backends can sample a single mip level of a texture.
+#[derive(Component)]
+struct BloomTexture {
+    mips: Vec<CachedTexture>,
+}
+
+impl BloomTexture {
+    fn mip_count(&self) -> u32 {
+        self.mips.len() as u32
+    }
+
+    fn mip_view(&self, mip_level: u32) -> &TextureView {
+        &self.mips[mip_level as usize].default_view
+    }
+}
+
+/// How many mip levels a bloom texture with the given size of its first level gets.
+fn mip_count(size: UVec2) -> u32 {
+    let smallest_side = size.min_element().max(1);
+
+    (smallest_side.ilog2() + 1).min(MAX_MIP_LEVELS)
+}
+
+fn prepare(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ExtractedCamera), With<BloomUniform>>,
+) {
+    for (entity, camera) in views.iter() {
+        let Some(target_size) = camera.physical_target_size else {
+            continue;
+        };
+
+        let size = (target_size / 2).max(UVec2::ONE);
+        let mip_count = mip_count(size);
+
+        // Bloom needs at least one level to downsample into and one to upsample from.
+        if mip_count < 2 {
+            continue;
+        }
+
+        let mips = (0..mip_count)
+            .map(|mip_level| {
+                let mip_size = (size >> mip_level).max(UVec2::ONE);
+
+                texture_cache.get(
+                    &render_device,
+                    TextureDescriptor {
+                        label: Some("Bloom Texture"),
+                        size: Extent3d {
+                            width: mip_size.x,
+                            height: mip_size.y,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: TextureDimension::D2,
+                        format: BLOOM_TEXTURE_FORMAT,
+                        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                        view_formats: &[],
+                    },
+                )
+            })
+            .collect();
+
+        commands.get_or_spawn(entity).insert(BloomTexture { mips });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn queue(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    data: Res<BloomData>,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<BloomData>>,
+    uniforms: Res<ComponentUniforms<BloomUniform>>,
+    mut masking: EffectMasking,
+    mut views: Query<(
+        Entity,
+        &mut RenderPhase<PostProcessingPhaseItem>,
+        &ExtractedView,
+        &Order<Bloom>,
+        Option<&Masked<Bloom>>,
+        &BloomTexture,
+        &DynamicUniformIndex<BloomUniform>,
+    )>,
+    draw_functions: Res<DrawFunctions<PostProcessingPhaseItem>>,
+) {
+    let Some(uniforms) = uniforms.binding() else {
+        return;
+    };
+
+    let draw_function = draw_functions.read().id::<DrawBloom>();
+
+    for (view_entity, mut phase, view, order, maybe_masked, bloom_texture, uniform_index) in
+        views.iter_mut()
+    {
+        let mips: Vec<_> = (0..bloom_texture.mip_count())
+            .map(|mip_level| bloom_texture.mip_view(mip_level).clone())
+            .collect();
+        let last = mips.len() - 1;
+
+        // Each pass is the stage to run, the texture to read via the bind group, and the target.
+        //
+        // The first downsample reads the scene via the shared bind group,
+        // but a texture is still needed for the bind group.
+        // Any mip level but the one being written to will do.
+        let mut passes = vec![(
+            BloomStage::FirstDownsample,
+            &mips[last],
+            PostProcessingTarget::Intermediate(mips[0].clone()),
+        )];
+
+        for mip_level in 1..=last {
+            passes.push((
+                BloomStage::Downsample,
+                &mips[mip_level - 1],
+                PostProcessingTarget::Intermediate(mips[mip_level].clone()),
+            ));
+        }
+
+        for mip_level in (0..last).rev() {
+            passes.push((
+                BloomStage::Upsample,
+                &mips[mip_level + 1],
+                PostProcessingTarget::Intermediate(mips[mip_level].clone()),
+            ));
+        }
+
+        // The composite reads the first level, which all other levels have been summed into.
+        passes.push((
+            BloomStage::Composite,
+            &mips[0],
+            PostProcessingTarget::ViewTarget,
+        ));
+
+        // The passes share a sort key, see the phase item sorting.
+        let sort_key = order.sort_key(PostProcessingStage::PreTonemap);
+        let mut items = vec![];
+
+        for (stage, input, target) in passes {
+            let pipeline_id = pipelines.specialize(
+                &pipeline_cache,
+                &data,
+                stage.key(view, bloom_texture.mip_count()),
+            );
+
+            let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+                label: Some("Bloom Uniform Bind Group"),
+                layout: &data.layout,
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(input),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&data.sampler),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: uniforms.clone(),
+                    },
+                ],
+            });
+
+            let entity = commands
+                .spawn(BloomPass {
+                    bind_group,
+                    uniform_offset: uniform_index.index(),
+                })
+                .id();
+
+            items.push(PostProcessingPhaseItem {
+                entity,
+                sort_key,
+                draw_function,
+                pipeline_id,
+                target,
+            });
+        }
+
+        masking.add_items(
+            &mut phase,
+            view_entity,
+            sort_key,
+            maybe_masked.map(|masked| &masked.mask),
+            items,
+        );
+    }
+}
+
+/// Bloom settings.
+///
+/// Makes bright parts of the scene glow by blurring them and adding the result back on top.
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bloom {
+    /// Parts of the scene with a brightness above this contribute to the glow.
+    ///
+    /// Without HDR colors are at most one, so the threshold should be below one to have any effect.
+    pub threshold: f32,
+
+    /// How softly the threshold kicks in, as a fraction of the threshold.
+    /// Zero is a hard cut-off, one starts ramping in the glow from zero brightness.
+    pub knee: f32,
+
+    /// How strongly the glow is added back to the scene.
+    pub intensity: f32,
+
+    /// The glow is multiplied by this color.
+    pub tint: Color,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self {
+            threshold: 0.8,
+            knee: 0.5,
+            intensity: 1.0,
+            tint: Color::WHITE,
+        }
+    }
+}
+
+impl Display for Bloom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Bloom threshold: {:.2}, knee: {:.2}, intensity: {:.2}, tint: {:?}",
+            self.threshold, self.knee, self.intensity, self.tint
+        )
+    }
+}
+
+/// The uniform representation of [`Bloom`].
+#[doc(hidden)]
+#[derive(Debug, Copy, Clone, Component, ShaderType)]
+pub struct BloomUniform {
+    threshold: f32,
+    knee: f32,
+    intensity: f32,
+    tint: Vec3,
+}
+
+impl From<Bloom> for BloomUniform {
+    fn from(bloom: Bloom) -> Self {
+        let tint = bloom.tint.as_rgba_linear();
+
+        Self {
+            threshold: bloom.threshold,
+            knee: bloom.threshold * bloom.knee.clamp(0.0, 1.0),
+            intensity: bloom.intensity,
+            tint: Vec3::new(tint.r(), tint.g(), tint.b()),
+        }
+    }
+}
+
+impl ExtractComponent for Bloom {
+    type Query = (&'static Self, &'static Camera);
+    type Filter = ();
+    type Out = BloomUniform;
+
+    fn extract_component((bloom, camera): QueryItem<'_, Self::Query>) -> Option<Self::Out> {
+        if !camera.is_active {
+            return None;
+        }
+
+        Some((*bloom).into())
+    }
+}
@@ -2,61 +2,18 @@ use std::fmt::Display;
 
 use bevy::{
     asset::load_internal_asset,
-    ecs::query::QueryItem,
     prelude::*,
     reflect::TypeUuid,
-    render::{
-        extract_component::{
-            ComponentUniforms, ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin,
-        },
-        render_phase::{AddRenderCommand, DrawFunctions, RenderPhase},
-        render_resource::{
-            BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry,
-            BindingType, BufferBindingType, CachedRenderPipelineId, ShaderStages, ShaderType,
-        },
-        renderer::RenderDevice,
-        RenderSet,
-    },
+    render::render_resource::{ShaderDefVal, ShaderType},
 };
 
-use crate::post_processing::{DrawPostProcessingEffect, UniformBindGroup};
+use serde::{Deserialize, Serialize};
 
-use super::{Order, PostProcessingPhaseItem};
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin};
 
 pub(crate) const BLUR_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 11044253213698850613);
 
-#[derive(Resource)]
-pub(crate) struct BlurData {
-    pub pipeline_id: CachedRenderPipelineId,
-    pub uniform_layout: BindGroupLayout,
-}
-
-impl FromWorld for BlurData {
-    fn from_world(world: &mut World) -> Self {
-        let (uniform_layout, pipeline_id) = super::create_layout_and_pipeline(
-            world,
-            "Blur",
-            &[BindGroupLayoutEntry {
-                binding: 0,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: true,
-                    min_binding_size: Some(Blur::min_size()),
-                },
-                visibility: ShaderStages::FRAGMENT,
-                count: None,
-            }],
-            BLUR_SHADER_HANDLE.typed(),
-        );
-
-        BlurData {
-            pipeline_id,
-            uniform_layout,
-        }
-    }
-}
-
 pub(crate) struct Plugin;
 impl bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
@@ -67,78 +24,110 @@ impl bevy::prelude::Plugin for Plugin {
             Shader::from_wgsl
         );
 
-        // This puts the uniform into the render world.
-        app.add_plugin(ExtractComponentPlugin::<Blur>::default())
-            .add_plugin(UniformComponentPlugin::<Blur>::default());
-
-        super::render_app(app)
-            .add_system(
-                super::extract_post_processing_camera_phases::<Blur>.in_schedule(ExtractSchedule),
-            )
-            .init_resource::<BlurData>()
-            .init_resource::<UniformBindGroup<Blur>>()
-            .add_system(prepare.in_set(RenderSet::Prepare))
-            .add_system(queue.in_set(RenderSet::Queue))
-            .add_render_command::<PostProcessingPhaseItem, DrawPostProcessingEffect<Blur>>();
+        app.register_type::<Blur>()
+            .register_type::<Order<Blur>>()
+            .register_type::<BlurKernel>()
+            .add_plugin(PostProcessingEffectPlugin::<Blur>::default());
     }
 }
 
-fn prepare(
-    data: Res<BlurData>,
-    mut views: Query<(
-        Entity,
-        &mut RenderPhase<PostProcessingPhaseItem>,
-        &Order<Blur>,
-    )>,
-    draw_functions: Res<DrawFunctions<PostProcessingPhaseItem>>,
-) {
-    for (entity, mut phase, order) in views.iter_mut() {
-        let draw_function = draw_functions.read().id::<DrawPostProcessingEffect<Blur>>();
-
-        phase.add(PostProcessingPhaseItem {
-            entity,
-            sort_key: (*order).into(),
-            draw_function,
-            pipeline_id: data.pipeline_id,
-        });
+/// Which kernel to blur with.
+#[derive(Debug, Default, PartialEq, Copy, Clone, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum BlurKernel {
+    /// Averages the origin texel and the eight texels surrounding it.
+    ///
+    /// Runs in a single pass.
+    /// Looks blocky at large [`Blur::kernel_radius`] values.
+    #[default]
+    Box3x3,
+
+    /// A gaussian blur.
+    ///
+    /// Runs as two separable passes, first horizontally and then vertically.
+    Gaussian {
+        /// The standard deviation of the gaussian, relative to the kernel radius.
+        /// At `0.5` the kernel radius covers two standard deviations.
+        sigma: f32,
+
+        /// How many texels are sampled on each side of the origin texel per pass.
+        /// More taps give a smoother result at larger radii, at a higher cost.
+        ///
+        /// Clamped to `1..=64`.
+        taps: u32,
+    },
+
+    /// A dual filter (Kawase style) blur.
+    ///
+    /// Runs as two passes: The first pass samples the diagonal neighbours,
+    /// and the second pass samples a wider ring of neighbours.
+    /// Cheaper than [`BlurKernel::Gaussian`] for large radii.
+    Kawase,
+}
+
+impl BlurKernel {
+    /// The taps of a gaussian kernel, clamped such that a pass stays reasonably cheap.
+    /// Zero for other kernels.
+    pub(crate) fn taps(&self) -> u32 {
+        match *self {
+            BlurKernel::Gaussian { taps, .. } => taps.clamp(1, 64),
+            BlurKernel::Box3x3 | BlurKernel::Kawase => 0,
+        }
     }
 }
 
-fn queue(
-    render_device: Res<RenderDevice>,
-    data: Res<BlurData>,
-    mut bind_group: ResMut<UniformBindGroup<Blur>>,
-    uniforms: Res<ComponentUniforms<Blur>>,
-    views: Query<Entity, With<Blur>>,
-) {
-    bind_group.inner = None;
-
-    if let Some(uniforms) = uniforms.binding() {
-        if !views.is_empty() {
-            bind_group.inner = Some(render_device.create_bind_group(&BindGroupDescriptor {
-                label: Some("Blur Uniform Bind Group"),
-                layout: &data.uniform_layout,
-                entries: &[BindGroupEntry {
-                    binding: 0,
-                    resource: uniforms.clone(),
-                }],
-            }));
+impl From<BlurKernel> for ShaderDefVal {
+    fn from(kernel: BlurKernel) -> Self {
+        match kernel {
+            BlurKernel::Box3x3 => "BOX",
+            BlurKernel::Gaussian { .. } => "GAUSSIAN",
+            BlurKernel::Kawase => "KAWASE",
         }
+        .into()
     }
 }
 
 /// Blur settings.
-#[derive(Debug, Copy, Clone, Component, ShaderType)]
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Blur {
     /// How blurry the output image should be.
     /// If `0.0`, no blur is applied.
     /// `1.0` is "fully blurred", but higher values will produce interesting results.
+    ///
+    /// Kernels running in several passes mix the fully blurred image with the input after the last pass.
     pub amount: f32,
 
     /// How far away the blur should sample points away from the origin point
     /// when blurring.
     /// This is in UV coordinates, so small (positive) values are expected (`0.01` is a good start).
     pub kernel_radius: f32,
+
+    /// Which [`BlurKernel`] to use.
+    pub kernel: BlurKernel,
+}
+
+impl Blur {
+    /// Create a new gaussian blur with reasonable parameters.
+    pub fn gaussian() -> Self {
+        Self {
+            kernel: BlurKernel::Gaussian {
+                sigma: 0.5,
+                taps: 8,
+            },
+            ..default()
+        }
+    }
+
+    /// Create a new dual filter (Kawase style) blur with reasonable parameters.
+    pub fn kawase() -> Self {
+        Self {
+            amount: 1.0,
+            kernel_radius: 0.004,
+            kernel: BlurKernel::Kawase,
+        }
+    }
 }
 
 impl Default for Blur {
@@ -146,6 +135,7 @@ impl Default for Blur {
         Self {
             amount: 0.5,
             kernel_radius: 0.01,
+            kernel: BlurKernel::default(),
         }
     }
 }
@@ -154,22 +144,71 @@ impl Display for Blur {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Blur amount: {}, radius: {}",
-            self.amount, self.kernel_radius
+            "Blur {:?}, amount: {}, radius: {}",
+            self.kernel, self.amount, self.kernel_radius
         )
     }
 }
 
-impl ExtractComponent for Blur {
-    type Query = (&'static Self, &'static Camera);
-    type Filter = ();
-    type Out = Self;
+#[doc(hidden)]
+/// [`Blur`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct BlurUniform {
+    pub(crate) amount: f32,
+    pub(crate) kernel_radius: f32,
+    pub(crate) sigma: f32,
+}
+
+impl From<Blur> for BlurUniform {
+    fn from(blur: Blur) -> Self {
+        let sigma = match blur.kernel {
+            BlurKernel::Gaussian { sigma, .. } => sigma,
+            BlurKernel::Box3x3 | BlurKernel::Kawase => 0.0,
+        };
+
+        Self {
+            amount: blur.amount,
+            kernel_radius: blur.kernel_radius,
+            sigma,
+        }
+    }
+}
+
+impl PostProcessingEffect for Blur {
+    type Uniform = BlurUniform;
+
+    const LABEL: &'static str = "Blur";
 
-    fn extract_component((settings, camera): QueryItem<'_, Self::Query>) -> Option<Self::Out> {
-        if !camera.is_active {
-            return None;
+    fn shader() -> Handle<Shader> {
+        BLUR_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        let mut shader_defs = vec![self.kernel.into()];
+
+        if let BlurKernel::Gaussian { .. } = self.kernel {
+            shader_defs.push(ShaderDefVal::UInt("TAPS".into(), self.kernel.taps()));
         }
 
-        Some(*settings)
+        shader_defs
+    }
+
+    fn passes(&self) -> u32 {
+        match self.kernel {
+            BlurKernel::Box3x3 => 1,
+            BlurKernel::Gaussian { .. } | BlurKernel::Kawase => 2,
+        }
+    }
+
+    fn amount(&self) -> f32 {
+        match self.kernel {
+            // The single pass mixes with the input itself.
+            BlurKernel::Box3x3 => 1.0,
+            BlurKernel::Gaussian { .. } | BlurKernel::Kawase => self.amount,
+        }
     }
 }
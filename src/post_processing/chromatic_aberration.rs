@@ -1,62 +1,19 @@
 use std::{f32::consts::PI, fmt::Display};
 
-use bevy::render::{render_phase::AddRenderCommand, RenderSet};
-pub(crate) use bevy::{
+use bevy::{
     asset::load_internal_asset,
-    ecs::query::QueryItem,
     prelude::*,
     reflect::TypeUuid,
-    render::{
-        extract_component::{
-            ComponentUniforms, ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin,
-        },
-        render_phase::{DrawFunctions, RenderPhase},
-        render_resource::{
-            BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry,
-            BindingType, BufferBindingType, CachedRenderPipelineId, ShaderStages, ShaderType,
-        },
-        renderer::RenderDevice,
-    },
+    render::render_resource::{ShaderDefVal, ShaderType},
 };
 
-use crate::post_processing::{DrawPostProcessingEffect, UniformBindGroup};
+use serde::{Deserialize, Serialize};
 
-use super::{Order, PostProcessingPhaseItem};
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin};
 
 pub(crate) const CHROMATIC_ABERRATION_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 4357337502039082134);
 
-#[derive(Resource)]
-pub(crate) struct ChromaticAberrationData {
-    pub pipeline_id: CachedRenderPipelineId,
-    pub uniform_layout: BindGroupLayout,
-}
-
-impl FromWorld for ChromaticAberrationData {
-    fn from_world(world: &mut World) -> Self {
-        let (uniform_layout, pipeline_id) = super::create_layout_and_pipeline(
-            world,
-            "ChromaticAberration",
-            &[BindGroupLayoutEntry {
-                binding: 0,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: true,
-                    min_binding_size: Some(ChromaticAberration::min_size()),
-                },
-                visibility: ShaderStages::FRAGMENT,
-                count: None,
-            }],
-            CHROMATIC_ABERRATION_SHADER_HANDLE.typed(),
-        );
-
-        ChromaticAberrationData {
-            pipeline_id,
-            uniform_layout,
-        }
-    }
-}
-
 pub(crate) struct Plugin;
 impl bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
@@ -71,71 +28,33 @@ impl bevy::prelude::Plugin for Plugin {
             Shader::from_wgsl
         );
 
-        // This puts the uniform into the render world.
-        app.add_plugin(ExtractComponentPlugin::<ChromaticAberration>::default())
-            .add_plugin(UniformComponentPlugin::<ChromaticAberration>::default());
-
-        super::render_app(app)
-            .add_system(
-                super::extract_post_processing_camera_phases::<ChromaticAberration>.in_schedule(ExtractSchedule),
-            )
-            .init_resource::<ChromaticAberrationData>()
-            .init_resource::<UniformBindGroup<ChromaticAberration>>()
-            .add_system(prepare.in_set(RenderSet::Prepare))
-            .add_system(queue.in_set(RenderSet::Queue))
-            .add_render_command::<PostProcessingPhaseItem, DrawPostProcessingEffect<ChromaticAberration>>(
-            );
+        app.register_type::<ChromaticAberration>()
+            .register_type::<Order<ChromaticAberration>>()
+            .register_type::<ChromaticAberrationMode>()
+            .add_plugin(PostProcessingEffectPlugin::<ChromaticAberration>::default());
     }
 }
 
-fn prepare(
-    data: Res<ChromaticAberrationData>,
-    mut views: Query<(
-        Entity,
-        &mut RenderPhase<PostProcessingPhaseItem>,
-        &Order<ChromaticAberration>,
-    )>,
-    draw_functions: Res<DrawFunctions<PostProcessingPhaseItem>>,
-) {
-    for (entity, mut phase, order) in views.iter_mut() {
-        let draw_function = draw_functions
-            .read()
-            .id::<DrawPostProcessingEffect<ChromaticAberration>>();
-
-        phase.add(PostProcessingPhaseItem {
-            entity,
-            sort_key: (*order).into(),
-            draw_function,
-            pipeline_id: data.pipeline_id,
-        });
-    }
-}
-
-fn queue(
-    render_device: Res<RenderDevice>,
-    data: Res<ChromaticAberrationData>,
-    mut bind_group: ResMut<UniformBindGroup<ChromaticAberration>>,
-    uniforms: Res<ComponentUniforms<ChromaticAberration>>,
-    views: Query<Entity, With<ChromaticAberration>>,
-) {
-    bind_group.inner = None;
-
-    if let Some(uniforms) = uniforms.binding() {
-        if !views.is_empty() {
-            bind_group.inner = Some(render_device.create_bind_group(&BindGroupDescriptor {
-                label: Some("ChromaticAberration Uniform Bind Group"),
-                layout: &data.uniform_layout,
-                entries: &[BindGroupEntry {
-                    binding: 0,
-                    resource: uniforms.clone(),
-                }],
-            }));
-        }
-    }
+/// How a [`ChromaticAberration`] offsets the color channels.
+#[derive(
+    Debug, Default, Eq, PartialEq, Hash, Copy, Clone, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum ChromaticAberrationMode {
+    /// Each channel is offset in its direction, by the same amount across the whole view.
+    #[default]
+    Directional,
+
+    /// Each channel is offset away from the `center`, more the further from it, like the fringes of a real lens.
+    /// The directions are not used, and the magnitudes are the offsets at the edges of the view.
+    /// Negative magnitudes offset towards the center.
+    Radial,
 }
 
 /// Chromatic Aberration settings.
-#[derive(Debug, Copy, Clone, Component, ShaderType)]
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ChromaticAberration {
     /// The direction (in UV space) the red channel is offset in.
     /// Will be normalized.
@@ -157,6 +76,12 @@ pub struct ChromaticAberration {
 
     /// How far (in UV space) the blue channel should be displaced.
     pub magnitude_b: f32,
+
+    /// Which [`ChromaticAberrationMode`] to use.
+    pub mode: ChromaticAberrationMode,
+
+    /// The center (in UV space) channels are offset away from in [`ChromaticAberrationMode::Radial`].
+    pub center: Vec2,
 }
 
 impl ChromaticAberration {
@@ -166,6 +91,17 @@ impl ChromaticAberration {
         self.magnitude_g += diff;
         self.magnitude_b += diff;
     }
+
+    /// Fringes which grow away from the center of the view, with red outwards and blue inwards by the given magnitude.
+    pub fn radial(magnitude: f32) -> Self {
+        Self {
+            magnitude_r: magnitude,
+            magnitude_g: 0.0,
+            magnitude_b: -magnitude,
+            mode: ChromaticAberrationMode::Radial,
+            ..default()
+        }
+    }
 }
 
 impl Default for ChromaticAberration {
@@ -179,6 +115,8 @@ impl Default for ChromaticAberration {
             magnitude_g: 0.01,
             dir_b: Vec2::from_angle(2. * one_third),
             magnitude_b: 0.01,
+            mode: ChromaticAberrationMode::Directional,
+            center: Vec2::splat(0.5),
         }
     }
 }
@@ -188,6 +126,14 @@ impl Display for ChromaticAberration {
         let base_angle = Vec2::new(1., 0.);
         let angle = |color_dir| base_angle.angle_between(color_dir) * 180. / PI + 180.;
 
+        if self.mode == ChromaticAberrationMode::Radial {
+            return write!(
+                f,
+                "Radial Chromatic Aberration [magnitude]: R: [{:.3}] G: [{:.3}] B: [{:.3}], center: {}",
+                self.magnitude_r, self.magnitude_g, self.magnitude_b, self.center
+            );
+        }
+
         write!(
             f,
             "Chromatic Aberration [magnitude, angle]:  R: [{:.3}, {:4.0}°] G: [{:.3}, {:4.0}°] B: [{:.3}, {:4.0}°]",
@@ -201,16 +147,72 @@ impl Display for ChromaticAberration {
     }
 }
 
-impl ExtractComponent for ChromaticAberration {
-    type Query = (&'static Self, &'static Camera);
-    type Filter = ();
-    type Out = Self;
+#[doc(hidden)]
+/// [`ChromaticAberration`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct ChromaticAberrationUniform {
+    pub(crate) dir_r: Vec2,
+    pub(crate) magnitude_r: f32,
+    pub(crate) dir_g: Vec2,
+    pub(crate) magnitude_g: f32,
+    pub(crate) dir_b: Vec2,
+    pub(crate) magnitude_b: f32,
+    pub(crate) center: Vec2,
+}
 
-    fn extract_component((settings, camera): QueryItem<'_, Self::Query>) -> Option<Self::Out> {
-        if !camera.is_active {
-            return None;
+#[cfg(feature = "cpu")]
+impl ChromaticAberrationUniform {
+    /// The offsets of the red, green, and blue channels at `uv`.
+    pub(crate) fn offsets(&self, uv: Vec2, radial: bool) -> [Vec2; 3] {
+        if radial {
+            // Twice the distance from the center, so the offset at the edges is the magnitude.
+            let from_center = (uv - self.center) * 2.0;
+            [
+                from_center * self.magnitude_r,
+                from_center * self.magnitude_g,
+                from_center * self.magnitude_b,
+            ]
+        } else {
+            [
+                self.dir_r * self.magnitude_r,
+                self.dir_g * self.magnitude_g,
+                self.dir_b * self.magnitude_b,
+            ]
         }
+    }
+}
 
-        Some(*settings)
+impl From<ChromaticAberration> for ChromaticAberrationUniform {
+    fn from(ca: ChromaticAberration) -> Self {
+        Self {
+            dir_r: ca.dir_r,
+            magnitude_r: ca.magnitude_r,
+            dir_g: ca.dir_g,
+            magnitude_g: ca.magnitude_g,
+            dir_b: ca.dir_b,
+            magnitude_b: ca.magnitude_b,
+            center: ca.center,
+        }
+    }
+}
+
+impl PostProcessingEffect for ChromaticAberration {
+    type Uniform = ChromaticAberrationUniform;
+
+    const LABEL: &'static str = "ChromaticAberration";
+
+    fn shader() -> Handle<Shader> {
+        CHROMATIC_ABERRATION_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        match self.mode {
+            ChromaticAberrationMode::Directional => vec![],
+            ChromaticAberrationMode::Radial => vec!["RADIAL".into()],
+        }
     }
 }
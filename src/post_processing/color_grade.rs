@@ -0,0 +1,274 @@
+use std::fmt::Display;
+
+use bevy::{
+    asset::load_internal_asset, prelude::*, reflect::TypeUuid, render::render_resource::ShaderType,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{lut, Order, PostProcessingEffect, PostProcessingEffectPlugin, PostProcessingStage};
+
+const COLOR_GRADE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3735882461552015967);
+
+/// Rec. 709 luminance weights of linear red, green, and blue.
+const LUMINANCE: Vec3 = Vec3::new(0.2126, 0.7152, 0.0722);
+
+/// Converts linear sRGB colors to the LMS color space, in which white balance is done.
+const LINEAR_TO_LMS: Mat3 = Mat3::from_cols_array(&[
+    0.390_405,
+    0.070_841_6,
+    0.023_108_2, //
+    0.549_941,
+    0.963_172,
+    0.128_021, //
+    0.008_926_32,
+    0.001_357_75,
+    0.936_245,
+]);
+
+/// Converts LMS colors back to linear sRGB.
+const LMS_TO_LINEAR: Mat3 = Mat3::from_cols_array(&[
+    2.858_47,
+    -0.210_182,
+    -0.041_812, //
+    -1.628_79,
+    1.158_2,
+    -0.118_169, //
+    -0.024_891,
+    0.000_324_281,
+    1.068_67,
+]);
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            COLOR_GRADE_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "color-grade.wgsl"
+            ),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<ColorGrade>()
+            .register_type::<Order<ColorGrade>>()
+            .add_plugin(PostProcessingEffectPlugin::<ColorGrade>::default());
+    }
+}
+
+/// Adjusts colors parametrically, as an alternative to a [`Lut`](super::lut::Lut).
+///
+/// The adjustments are applied in this order: white balance and exposure, contrast, lift, gamma, and gain,
+/// hue shift, and saturation and vibrance.
+/// The default changes nothing.
+///
+/// Runs after tonemapping by default, like a [`Lut`](super::lut::Lut).
+/// A grade tuned at runtime can be frozen into a cheaper LUT via [`ColorGrade::bake_to_lut`].
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColorGrade {
+    /// Brightens or darkens the colors, in stops.
+    /// Each stop doubles the brightness.
+    pub exposure: f32,
+
+    /// Pushes colors away from the `pivot`, or pulls them towards it below one.
+    pub contrast: f32,
+
+    /// The brightness contrast is relative to, which is unchanged by it.
+    pub pivot: f32,
+
+    /// Multiplies how colorful colors are. Zero is grayscale.
+    pub saturation: f32,
+
+    /// Like `saturation`, but changes dull colors more than colorful ones.
+    pub vibrance: f32,
+
+    /// Rotates the hue of colors, in degrees.
+    /// Positive values turn red towards green, like increasing the hue of a [`Color::Hsla`].
+    pub hue_shift: f32,
+
+    /// Makes colors warmer, or cooler when negative, from minus one to one.
+    pub temperature: f32,
+
+    /// Makes colors more magenta, or greener when negative, from minus one to one.
+    pub tint: f32,
+
+    /// Raises the dark colors per channel, while keeping white as it is.
+    pub lift: Vec3,
+
+    /// Brightens the midtones per channel above one, or darkens them below one.
+    pub gamma: Vec3,
+
+    /// Multiplies the colors per channel.
+    pub gain: Vec3,
+}
+
+impl Default for ColorGrade {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            contrast: 1.0,
+            pivot: 0.18,
+            saturation: 1.0,
+            vibrance: 0.0,
+            hue_shift: 0.0,
+            temperature: 0.0,
+            tint: 0.0,
+            lift: Vec3::ZERO,
+            gamma: Vec3::ONE,
+            gain: Vec3::ONE,
+        }
+    }
+}
+
+impl ColorGrade {
+    /// Grades a single linear color, the same way the shader does.
+    pub fn grade(&self, color: Vec3) -> Vec3 {
+        ColorGradeUniform::from(*self).grade(color)
+    }
+
+    /// Bakes the grade into a `size`x`size`x`size` 3D LUT image, which can be used with [`Lut::new`](super::lut::Lut::new).
+    ///
+    /// Colors between the entries of the LUT are interpolated, so a size of 32 or 64 is usually enough.
+    /// Colors outside of zero to one are clamped by the LUT, which they are not by the grade.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is less than 2.
+    pub fn bake_to_lut(&self, size: u32) -> Image {
+        assert!(size >= 2, "A LUT has at least two entries per channel");
+
+        let uniform = ColorGradeUniform::from(*self);
+        let max_index = (size - 1) as f32;
+
+        lut::lut_image(size, |index| uniform.grade(index.as_vec3() / max_index))
+    }
+}
+
+impl Display for ColorGrade {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ColorGrade exposure: {}, contrast: {} (pivot {}), saturation: {}, vibrance: {}, hue shift: {}, \
+            temperature: {}, tint: {}, lift: {}, gamma: {}, gain: {}",
+            self.exposure,
+            self.contrast,
+            self.pivot,
+            self.saturation,
+            self.vibrance,
+            self.hue_shift,
+            self.temperature,
+            self.tint,
+            self.lift,
+            self.gamma,
+            self.gain
+        )
+    }
+}
+
+/// The LMS color of white under the illuminant of the given temperature and tint.
+///
+/// See <https://en.wikipedia.org/wiki/Standard_illuminant#Illuminant_series_D>.
+fn white_point(temperature: f32, tint: f32) -> Vec3 {
+    let t1 = temperature * 10.0 / 6.0;
+    let t2 = tint * 10.0 / 6.0;
+
+    // The CIE xy chromaticity, along the daylight locus and shifted by the tint.
+    let x = 0.31271 - t1 * if t1 < 0.0 { 0.1 } else { 0.05 };
+    let y = 2.87 * x - 3.0 * x * x - 0.275_095_07 + t2 * 0.05;
+
+    let xyz = Vec3::new(x / y, 1.0, (1.0 - x - y) / y);
+
+    Mat3::from_cols_array(&[
+        0.7328, -0.7036, 0.0030, //
+        0.4296, 1.6975, 0.0136, //
+        -0.1624, 0.0061, 0.9834,
+    ]) * xyz
+}
+
+#[doc(hidden)]
+/// [`ColorGrade`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct ColorGradeUniform {
+    /// White balance and exposure.
+    pub(crate) balance: Mat3,
+    pub(crate) hue: Mat3,
+    pub(crate) lift: Vec3,
+    pub(crate) contrast: f32,
+    pub(crate) gain: Vec3,
+    pub(crate) pivot: f32,
+    pub(crate) inverse_gamma: Vec3,
+    pub(crate) saturation: f32,
+    pub(crate) vibrance: f32,
+}
+
+impl ColorGradeUniform {
+    /// Mirrors `grade` in `color-grade.wgsl`.
+    pub(crate) fn grade(&self, color: Vec3) -> Vec3 {
+        let pow = |color: Vec3, exponent: Vec3| {
+            Vec3::new(
+                color.x.powf(exponent.x),
+                color.y.powf(exponent.y),
+                color.z.powf(exponent.z),
+            )
+        };
+
+        let color = self.balance * color;
+        let color = pow(
+            color.max(Vec3::ZERO) / self.pivot,
+            Vec3::splat(self.contrast),
+        ) * self.pivot;
+        let color = self.gain * (color + self.lift * (1.0 - color));
+        let color = pow(color.max(Vec3::ZERO), self.inverse_gamma);
+        let color = self.hue * color;
+
+        let luminance = color.dot(LUMINANCE);
+        let chroma = (color.max_element() - color.min_element()).clamp(0.0, 1.0);
+        let saturation = self.saturation * (1.0 + self.vibrance * (1.0 - chroma));
+
+        (luminance + (color - luminance) * saturation).max(Vec3::ZERO)
+    }
+}
+
+impl From<ColorGrade> for ColorGradeUniform {
+    fn from(grade: ColorGrade) -> Self {
+        let white_balance =
+            Mat3::from_diagonal(white_point(0.0, 0.0) / white_point(grade.temperature, grade.tint));
+
+        Self {
+            balance: LMS_TO_LINEAR * white_balance * LINEAR_TO_LMS * 2f32.powf(grade.exposure),
+            hue: Mat3::from_axis_angle(Vec3::ONE.normalize(), grade.hue_shift.to_radians()),
+            lift: grade.lift,
+            contrast: grade.contrast.max(0.0001),
+            gain: grade.gain,
+            pivot: grade.pivot.max(0.0001),
+            inverse_gamma: 1.0 / grade.gamma.max(Vec3::splat(0.0001)),
+            saturation: grade.saturation,
+            vibrance: grade.vibrance,
+        }
+    }
+}
+
+impl PostProcessingEffect for ColorGrade {
+    type Uniform = ColorGradeUniform;
+
+    const LABEL: &'static str = "ColorGrade";
+
+    fn shader() -> Handle<Shader> {
+        COLOR_GRADE_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    /// Colors are graded as they are displayed, like by a LUT.
+    fn stage(&self) -> PostProcessingStage {
+        PostProcessingStage::PostTonemap
+    }
+}
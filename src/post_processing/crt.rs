@@ -0,0 +1,243 @@
+use std::fmt::Display;
+
+use bevy::{
+    asset::load_internal_asset,
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{ShaderDefVal, ShaderType},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin, PostProcessingStage};
+
+const CRT_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 8690929331363755033);
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            CRT_SHADER_HANDLE,
+            concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/", "crt.wgsl"),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<Crt>()
+            .register_type::<Order<Crt>>()
+            .register_type::<PhosphorMask>()
+            .add_plugin(PostProcessingEffectPlugin::<Crt>::default());
+    }
+}
+
+/// The pattern of the red, green, and blue phosphors of a [`Crt`].
+#[derive(
+    Debug, Default, Eq, PartialEq, Hash, Copy, Clone, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum PhosphorMask {
+    /// Vertical stripes, as in Trinitron TVs and professional monitors.
+    ApertureGrille,
+
+    /// Stripes broken into slots, as in most consumer TVs.
+    #[default]
+    SlotMask,
+
+    /// Dots in triangles, as in arcade monitors and computer monitors.
+    ShadowMask,
+
+    /// No visible phosphors.
+    None,
+}
+
+impl PhosphorMask {
+    fn shader_def(self) -> Option<ShaderDefVal> {
+        match self {
+            PhosphorMask::ApertureGrille => Some("APERTURE_GRILLE".into()),
+            PhosphorMask::SlotMask => Some("SLOT_MASK".into()),
+            PhosphorMask::ShadowMask => Some("SHADOW_MASK".into()),
+            PhosphorMask::None => None,
+        }
+    }
+}
+
+/// Emulates a CRT screen, with curved glass, scanlines, phosphors, and phosphor bleed.
+///
+/// The view is drawn as `lines` lines, as if by a console or arcade board with that vertical resolution.
+/// Use one of the constructors for the look of a common kind of screen, and adjust from there.
+///
+/// Runs after the UI by default, since the whole screen is a CRT.
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Crt {
+    /// How much the glass bulges outwards. Zero is a flat screen.
+    pub curvature: f32,
+
+    /// The vertical resolution of the virtual screen, which is how many scanlines there are.
+    pub lines: f32,
+
+    /// How dark the gaps between scanlines are, from zero to one.
+    pub scanlines: f32,
+
+    /// Which [`PhosphorMask`] to use.
+    pub phosphor_mask: PhosphorMask,
+
+    /// How much the phosphors not lit at a pixel darken it, from zero to one.
+    pub mask_intensity: f32,
+
+    /// The width of a single phosphor in pixels.
+    pub mask_size: f32,
+
+    /// How far colors bleed horizontally, in pixels of the virtual screen.
+    pub bleed: f32,
+
+    /// The strength of the dark edges, see [`MaskVariant::Crt`](super::masks::MaskVariant::Crt).
+    /// Zero disables them.
+    pub edge: f32,
+
+    /// Brightens the result, making up for the darkening of the scanlines and the phosphors.
+    pub brightness: f32,
+}
+
+impl Crt {
+    /// A consumer TV from the nineties, with a slot mask and soft scanlines.
+    pub fn consumer_tv() -> Self {
+        Self {
+            curvature: 0.08,
+            lines: 240.0,
+            scanlines: 0.4,
+            phosphor_mask: PhosphorMask::SlotMask,
+            mask_intensity: 0.3,
+            mask_size: 1.0,
+            bleed: 1.5,
+            edge: 30000.0,
+            brightness: 1.3,
+        }
+    }
+
+    /// An arcade monitor, with a shadow mask and strong scanlines at a low resolution.
+    pub fn arcade() -> Self {
+        Self {
+            curvature: 0.1,
+            lines: 224.0,
+            scanlines: 0.7,
+            phosphor_mask: PhosphorMask::ShadowMask,
+            mask_intensity: 0.35,
+            mask_size: 1.0,
+            bleed: 0.8,
+            edge: 50000.0,
+            brightness: 1.5,
+        }
+    }
+
+    /// A Trinitron TV, with an aperture grille and a screen which is almost flat.
+    pub fn trinitron() -> Self {
+        Self {
+            curvature: 0.03,
+            lines: 240.0,
+            scanlines: 0.5,
+            phosphor_mask: PhosphorMask::ApertureGrille,
+            mask_intensity: 0.25,
+            mask_size: 1.0,
+            bleed: 0.7,
+            edge: 150000.0,
+            brightness: 1.3,
+        }
+    }
+
+    /// A professional video monitor, with sharp, well defined scanlines and little bleed.
+    pub fn pvm() -> Self {
+        Self {
+            curvature: 0.02,
+            lines: 240.0,
+            scanlines: 0.8,
+            phosphor_mask: PhosphorMask::ApertureGrille,
+            mask_intensity: 0.15,
+            mask_size: 1.0,
+            bleed: 0.3,
+            edge: 0.0,
+            brightness: 1.5,
+        }
+    }
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self::consumer_tv()
+    }
+}
+
+impl Display for Crt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Crt {:?}, curvature: {}, lines: {}, scanlines: {}, mask intensity: {}, mask size: {}, bleed: {}, edge: {}, brightness: {}",
+            self.phosphor_mask,
+            self.curvature,
+            self.lines,
+            self.scanlines,
+            self.mask_intensity,
+            self.mask_size,
+            self.bleed,
+            self.edge,
+            self.brightness
+        )
+    }
+}
+
+#[doc(hidden)]
+/// [`Crt`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct CrtUniform {
+    pub(crate) curvature: f32,
+    pub(crate) lines: f32,
+    pub(crate) scanlines: f32,
+    pub(crate) mask_intensity: f32,
+    pub(crate) mask_size: f32,
+    pub(crate) bleed: f32,
+    pub(crate) edge: f32,
+    pub(crate) brightness: f32,
+}
+
+impl From<Crt> for CrtUniform {
+    fn from(crt: Crt) -> Self {
+        Self {
+            curvature: crt.curvature,
+            lines: crt.lines.max(1.0),
+            scanlines: crt.scanlines,
+            mask_intensity: crt.mask_intensity,
+            mask_size: crt.mask_size,
+            bleed: crt.bleed,
+            edge: crt.edge,
+            brightness: crt.brightness,
+        }
+    }
+}
+
+impl PostProcessingEffect for Crt {
+    type Uniform = CrtUniform;
+
+    const LABEL: &'static str = "Crt";
+
+    fn shader() -> Handle<Shader> {
+        CRT_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        match self.phosphor_mask.shader_def() {
+            Some(pattern) => vec!["PHOSPHOR_MASK".into(), pattern],
+            None => vec![],
+        }
+    }
+
+    /// CRTs cover the UI as well.
+    fn stage(&self) -> PostProcessingStage {
+        PostProcessingStage::PostUi
+    }
+}
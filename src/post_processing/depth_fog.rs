@@ -0,0 +1,109 @@
+use std::fmt::Display;
+
+use bevy::{
+    asset::load_internal_asset, prelude::*, reflect::TypeUuid, render::render_resource::ShaderType,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin};
+
+pub(crate) const DEPTH_FOG_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 16143797999914035748);
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            DEPTH_FOG_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "depth-fog.wgsl"
+            ),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<DepthFog>()
+            .register_type::<Order<DepthFog>>()
+            .add_plugin(PostProcessingEffectPlugin::<DepthFog>::default());
+    }
+}
+
+/// Depth fog settings.
+///
+/// Fades what is far away from the camera into the fog color, exponentially with the distance.
+/// Where nothing is drawn, such as the sky, is fully fogged.
+///
+/// Needs a [`DepthPrepass`](bevy::core_pipeline::prepass::DepthPrepass) on the camera, and is skipped without one.
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DepthFog {
+    /// The color of the fog.
+    /// The alpha is how much the fog covers at most.
+    pub color: Color,
+
+    /// The distance from the camera where the fog starts.
+    pub start: f32,
+
+    /// How thick the fog is.
+    /// The fog covers about two thirds at a distance of one over the density past the start.
+    pub density: f32,
+}
+
+impl Default for DepthFog {
+    fn default() -> Self {
+        Self {
+            color: Color::rgb(0.6, 0.65, 0.7),
+            start: 0.0,
+            density: 0.05,
+        }
+    }
+}
+
+impl Display for DepthFog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Depth fog, start: {}, density: {}",
+            self.start, self.density
+        )
+    }
+}
+
+#[doc(hidden)]
+/// [`DepthFog`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct DepthFogUniform {
+    pub(crate) color: Vec4,
+    pub(crate) start: f32,
+    pub(crate) density: f32,
+}
+
+impl From<DepthFog> for DepthFogUniform {
+    fn from(fog: DepthFog) -> Self {
+        Self {
+            color: Vec4::from(fog.color.as_linear_rgba_f32()),
+            start: fog.start,
+            density: fog.density,
+        }
+    }
+}
+
+impl PostProcessingEffect for DepthFog {
+    type Uniform = DepthFogUniform;
+
+    const LABEL: &'static str = "DepthFog";
+
+    const DEPTH: bool = true;
+
+    fn shader() -> Handle<Shader> {
+        DEPTH_FOG_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+}
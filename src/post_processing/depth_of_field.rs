@@ -0,0 +1,94 @@
+use std::fmt::Display;
+
+use bevy::{
+    asset::load_internal_asset, prelude::*, reflect::TypeUuid, render::render_resource::ShaderType,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin};
+
+pub(crate) const DEPTH_OF_FIELD_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3307519107345738608);
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            DEPTH_OF_FIELD_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "depth-of-field.wgsl"
+            ),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<DepthOfField>()
+            .register_type::<Order<DepthOfField>>()
+            .add_plugin(PostProcessingEffectPlugin::<DepthOfField>::default());
+    }
+}
+
+/// Depth of field settings.
+///
+/// Blurs what is nearer or further away than the focal distance,
+/// by gathering samples from a disk the size of the circle of confusion (CoC) around each pixel.
+///
+/// Needs a [`DepthPrepass`](bevy::core_pipeline::prepass::DepthPrepass) on the camera, and is skipped without one.
+#[derive(
+    Debug, Copy, Clone, Component, ShaderType, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DepthOfField {
+    /// The distance from the camera, along the view direction, which is in focus.
+    pub focal_distance: f32,
+
+    /// How wide the aperture of the lens is.
+    /// Wider apertures blur more quickly away from the focal distance, giving a shallower depth of field.
+    ///
+    /// At `1.0`, something twice as far away as the focal distance is blurred by half the max CoC.
+    pub aperture: f32,
+
+    /// The largest radius of the circle of confusion, in pixels.
+    /// Larger values blur more, at the cost of more visible sampling patterns.
+    pub max_coc: f32,
+}
+
+impl Default for DepthOfField {
+    fn default() -> Self {
+        Self {
+            focal_distance: 10.0,
+            aperture: 1.0,
+            max_coc: 12.0,
+        }
+    }
+}
+
+impl Display for DepthOfField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Depth of field, focal distance: {}, aperture: {}, max CoC: {}",
+            self.focal_distance, self.aperture, self.max_coc
+        )
+    }
+}
+
+impl PostProcessingEffect for DepthOfField {
+    type Uniform = Self;
+
+    const LABEL: &'static str = "DepthOfField";
+
+    const DEPTH: bool = true;
+
+    fn shader() -> Handle<Shader> {
+        DEPTH_OF_FIELD_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        *self
+    }
+}
@@ -0,0 +1,623 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::load_internal_asset,
+    ecs::{
+        query::ROQueryItem,
+        system::{lifetimeless::Read, SystemParam, SystemParamItem},
+    },
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        camera::ExtractedCamera,
+        render_asset::RenderAssets,
+        render_phase::{
+            AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+            RenderPhase, SetItemPipeline, TrackedRenderPass,
+        },
+        render_resource::{
+            AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+            BindGroupLayoutEntry, BindingResource, BindingType, BufferBindingType,
+            DynamicUniformBuffer, Extent3d, FilterMode, PipelineCache, RenderPipelineDescriptor,
+            Sampler, SamplerBindingType, SamplerDescriptor, ShaderStages, ShaderType,
+            SpecializedRenderPipeline, SpecializedRenderPipelines, TextureDescriptor,
+            TextureDimension, TextureSampleType, TextureUsages, TextureView, TextureViewDimension,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        texture::TextureCache,
+        view::ExtractedView,
+        RenderSet,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    DrawPostProcessing, PostProcessingPhaseItem, PostProcessingPipelineKey,
+    PostProcessingSharedLayout, PostProcessingSortKey, PostProcessingTarget,
+    SetTextureSamplerGlobals,
+};
+
+pub(crate) const EFFECT_MASK_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 12834010384502913711);
+
+/// The shape of an [`EffectMask`].
+///
+/// Positions and sizes are in UV space, where `(0, 0)` is the top left and `(1, 1)` is the bottom right of the view.
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum EffectMaskShape {
+    /// A rectangle.
+    Rect {
+        /// The top left corner.
+        min: Vec2,
+        /// The bottom right corner.
+        max: Vec2,
+    },
+
+    /// An ellipse.
+    /// Since UV space is stretched to the aspect ratio of the view, equal radii do not make a circle.
+    Ellipse {
+        /// The center of the ellipse.
+        center: Vec2,
+        /// The horizontal and vertical radii.
+        radii: Vec2,
+    },
+
+    /// A circle, which stays round regardless of the aspect ratio of the view.
+    Circle {
+        /// The center of the circle.
+        center: Vec2,
+        /// The radius, relative to the height of the view.
+        radius: f32,
+    },
+
+    /// A grayscale image stretched over the view.
+    /// The red channel is used, where one means the effect is fully applied.
+    ///
+    /// Image masks can not be serialized.
+    #[serde(skip)]
+    Image(Handle<Image>),
+}
+
+/// Limits where an effect is applied.
+///
+/// The output of the effect is blended with its input through the mask,
+/// so for example a [`Blur`](super::blur::Blur) may be applied only outside of a focus circle,
+/// or a [`Pixelate`](super::pixelate::Pixelate) only within a rectangle.
+///
+/// Add a mask to a camera via [`Masked`], or to an [`EffectInstance`](super::EffectInstance) of an effect stack.
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct EffectMask {
+    /// Where the effect is applied.
+    pub shape: EffectMaskShape,
+
+    /// If set, the effect is applied outside of the shape instead of within it.
+    #[serde(default)]
+    pub inverted: bool,
+
+    /// The width of the soft edge of the shape, in UV space.
+    /// Zero gives a hard edge.
+    /// Has no effect on image masks.
+    #[serde(default)]
+    pub feather: f32,
+}
+
+impl EffectMask {
+    /// Creates a mask from a shape.
+    pub fn new(shape: EffectMaskShape) -> Self {
+        Self {
+            shape,
+            inverted: false,
+            feather: 0.0,
+        }
+    }
+
+    /// A rectangular mask. See [`EffectMaskShape::Rect`].
+    pub fn rect(min: Vec2, max: Vec2) -> Self {
+        Self::new(EffectMaskShape::Rect { min, max })
+    }
+
+    /// An elliptical mask. See [`EffectMaskShape::Ellipse`].
+    pub fn ellipse(center: Vec2, radii: Vec2) -> Self {
+        Self::new(EffectMaskShape::Ellipse { center, radii })
+    }
+
+    /// A circular mask. See [`EffectMaskShape::Circle`].
+    pub fn circle(center: Vec2, radius: f32) -> Self {
+        Self::new(EffectMaskShape::Circle { center, radius })
+    }
+
+    /// A grayscale image mask. See [`EffectMaskShape::Image`].
+    pub fn image(image: Handle<Image>) -> Self {
+        Self::new(EffectMaskShape::Image(image))
+    }
+
+    /// Applies the effect outside of the shape instead of within it.
+    pub fn inverted(mut self) -> Self {
+        self.inverted = !self.inverted;
+        self
+    }
+
+    /// Sets the width of the soft edge of the shape.
+    pub fn with_feather(mut self, feather: f32) -> Self {
+        self.feather = feather;
+        self
+    }
+}
+
+/// Limits the effect `C` on a camera to an [`EffectMask`].
+///
+/// It's easier to use this via the [`PostProcessingMask`] trait
+/// which adds a helper method for using this.
+#[derive(Debug, Component)]
+pub struct Masked<C> {
+    /// The mask.
+    pub mask: EffectMask,
+    marker: PhantomData<C>,
+}
+
+impl<C> Clone for Masked<C> {
+    fn clone(&self) -> Self {
+        Self::new(self.mask.clone())
+    }
+}
+
+impl<C> Masked<C> {
+    /// Create a new mask for the effect `C`.
+    pub fn new(mask: EffectMask) -> Self {
+        Self {
+            mask,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Adds a `.masked` helper method to a component.
+/// When used on a post processing effect, it limits where the effect is applied.
+///
+/// See [`EffectMask`] for more information.
+pub trait PostProcessingMask: Sized {
+    /// Sets the mask on a component, and returns the component
+    /// as well as the mask as a bundle.
+    fn masked(self, mask: EffectMask) -> (Self, Masked<Self>);
+}
+
+impl<C> PostProcessingMask for C
+where
+    C: Component,
+{
+    fn masked(self, mask: EffectMask) -> (Self, Masked<Self>) {
+        (self, Masked::new(mask))
+    }
+}
+
+type DrawEffectMaskSave = (
+    SetItemPipeline,
+    SetTextureSamplerGlobals<0>,
+    DrawPostProcessing,
+);
+
+type DrawEffectMask = (
+    SetItemPipeline,
+    // The output of the effect.
+    SetTextureSamplerGlobals<0>,
+    // The input of the effect and the mask.
+    SetEffectMaskBindGroup<1>,
+    DrawPostProcessing,
+);
+
+/// A masked effect in the render world.
+///
+/// Before the effect runs, its input is saved to a texture.
+/// Afterwards, the saved input and the output are blended through the mask.
+#[derive(Debug, Component)]
+struct EffectMaskPass {
+    mask: Option<EffectMask>,
+    amount: f32,
+    saved: TextureView,
+}
+
+#[derive(Debug, Component)]
+struct EffectMaskBindGroup {
+    bind_group: BindGroup,
+    uniform_offset: u32,
+}
+
+struct SetEffectMaskBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetEffectMaskBindGroup<I> {
+    type Param = ();
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<EffectMaskBindGroup>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        mask_bind_group: ROQueryItem<'w, Self::ItemWorldQuery>,
+        _param: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        pass.set_bind_group(
+            I,
+            &mask_bind_group.bind_group,
+            &[mask_bind_group.uniform_offset],
+        );
+        RenderCommandResult::Success
+    }
+}
+
+#[derive(Debug, Clone, Copy, ShaderType)]
+struct EffectMaskUniform {
+    /// Zero means no mask, see the shader for the other values.
+    shape: u32,
+    inverted: u32,
+    feather: f32,
+    center: Vec2,
+    /// Half the size for rectangles, the radii for ellipses, and the radius in `x` for circles.
+    size: Vec2,
+    /// How much of the output of the effect is used where the mask applies it.
+    amount: f32,
+}
+
+impl EffectMaskUniform {
+    fn new(mask: Option<&EffectMask>, image_loaded: bool, amount: f32) -> Self {
+        let Some(mask) = mask else {
+            return Self {
+                shape: 0,
+                inverted: 0,
+                feather: 0.0,
+                center: Vec2::ZERO,
+                size: Vec2::ZERO,
+                amount,
+            };
+        };
+
+        let (shape, center, size) = match mask.shape {
+            EffectMaskShape::Rect { min, max } => (1, (min + max) / 2., (max - min).abs() / 2.),
+            EffectMaskShape::Ellipse { center, radii } => (2, center, radii),
+            EffectMaskShape::Circle { center, radius } => (3, center, Vec2::splat(radius)),
+            // Until the image is loaded, the effect is applied as if there was no mask.
+            EffectMaskShape::Image(_) if !image_loaded => (0, Vec2::ZERO, Vec2::ZERO),
+            EffectMaskShape::Image(_) => (4, Vec2::ZERO, Vec2::ZERO),
+        };
+
+        Self {
+            shape,
+            inverted: mask.inverted.into(),
+            feather: mask.feather,
+            center,
+            size,
+            amount,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct EffectMaskUniforms {
+    buffer: DynamicUniformBuffer<EffectMaskUniform>,
+}
+
+#[derive(Resource)]
+pub(crate) struct EffectMaskData {
+    shared_layout: BindGroupLayout,
+    layout: BindGroupLayout,
+    sampler: Sampler,
+}
+
+impl FromWorld for EffectMaskData {
+    fn from_world(world: &mut World) -> Self {
+        let texture_entry = |binding| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let layout = super::create_layout(
+            world,
+            "Effect Mask",
+            &[
+                // The saved input of the effect.
+                texture_entry(0),
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                // The mask image.
+                texture_entry(2),
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(EffectMaskUniform::min_size()),
+                    },
+                    count: None,
+                },
+            ],
+        );
+
+        let sampler = world
+            .resource::<RenderDevice>()
+            .create_sampler(&SamplerDescriptor {
+                label: Some("Effect Mask Sampler"),
+                address_mode_u: AddressMode::ClampToEdge,
+                address_mode_v: AddressMode::ClampToEdge,
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                ..default()
+            });
+
+        let shared_layout = world
+            .resource::<PostProcessingSharedLayout>()
+            .shared_layout
+            .clone();
+
+        Self {
+            shared_layout,
+            layout,
+            sampler,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for EffectMaskData {
+    type Key = PostProcessingPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let save = key.shader_defs.contains(&"SAVE".into());
+
+        let mut descriptor = super::render_pipeline_descriptor(
+            "Effect Mask",
+            &self.shared_layout,
+            &self.layout,
+            EFFECT_MASK_SHADER_HANDLE.typed(),
+            key,
+        );
+
+        // Saving writes to the texture which is later bound in the mask bind group,
+        // so only the shared bind group is used.
+        if save {
+            descriptor.layout.truncate(1);
+        }
+
+        descriptor
+    }
+}
+
+/// Adds the phase items of effects, limited by their [`EffectMask`] if they have one.
+#[derive(SystemParam)]
+pub(super) struct EffectMasking<'w, 's> {
+    commands: Commands<'w, 's>,
+    texture_cache: ResMut<'w, TextureCache>,
+    render_device: Res<'w, RenderDevice>,
+    data: Res<'w, EffectMaskData>,
+    pipeline_cache: Res<'w, PipelineCache>,
+    pipelines: ResMut<'w, SpecializedRenderPipelines<EffectMaskData>>,
+    draw_functions: Res<'w, DrawFunctions<PostProcessingPhaseItem>>,
+    views: Query<'w, 's, (&'static ExtractedView, &'static ExtractedCamera)>,
+}
+
+impl<'w, 's> EffectMasking<'w, 's> {
+    /// Adds the items making up one effect to the phase of a view.
+    ///
+    /// With a mask, the items are surrounded by items saving the input of the effect
+    /// and blending it with the output through the mask.
+    /// All items must share the given sort key.
+    pub(super) fn add_items(
+        &mut self,
+        phase: &mut RenderPhase<PostProcessingPhaseItem>,
+        view_entity: Entity,
+        sort_key: PostProcessingSortKey,
+        mask: Option<&EffectMask>,
+        items: impl IntoIterator<Item = PostProcessingPhaseItem>,
+    ) {
+        self.add_mixed_items(phase, view_entity, sort_key, mask, 1.0, items);
+    }
+
+    /// Like [`EffectMasking::add_items`], but only the given amount of the output of the effect is used,
+    /// mixed with its input.
+    ///
+    /// Effects with several passes use this, since their later passes can not read the input.
+    pub(super) fn add_mixed_items(
+        &mut self,
+        phase: &mut RenderPhase<PostProcessingPhaseItem>,
+        view_entity: Entity,
+        sort_key: PostProcessingSortKey,
+        mask: Option<&EffectMask>,
+        amount: f32,
+        items: impl IntoIterator<Item = PostProcessingPhaseItem>,
+    ) {
+        let saved = if mask.is_some() || amount != 1.0 {
+            self.saved_texture(view_entity)
+        } else {
+            None
+        };
+
+        let Some(saved) = saved else {
+            for item in items {
+                phase.add(item);
+            }
+            return;
+        };
+
+        let (save_key, composite_key) = {
+            let (view, _) = self
+                .views
+                .get(view_entity)
+                .expect("Saved texture needs a view");
+
+            (
+                PostProcessingPipelineKey::for_view(view, vec!["SAVE".into()]),
+                PostProcessingPipelineKey::for_view(view, vec![]),
+            )
+        };
+
+        let entity = self
+            .commands
+            .spawn(EffectMaskPass {
+                mask: mask.cloned(),
+                amount,
+                saved: saved.clone(),
+            })
+            .id();
+
+        let draw_functions = self.draw_functions.read();
+
+        phase.add(PostProcessingPhaseItem {
+            entity,
+            sort_key,
+            draw_function: draw_functions.id::<DrawEffectMaskSave>(),
+            pipeline_id: self
+                .pipelines
+                .specialize(&self.pipeline_cache, &self.data, save_key),
+            target: PostProcessingTarget::ViewSized(saved),
+        });
+
+        for item in items {
+            phase.add(item);
+        }
+
+        phase.add(PostProcessingPhaseItem {
+            entity,
+            sort_key,
+            draw_function: draw_functions.id::<DrawEffectMask>(),
+            pipeline_id: self
+                .pipelines
+                .specialize(&self.pipeline_cache, &self.data, composite_key),
+            target: PostProcessingTarget::ViewTarget,
+        });
+    }
+
+    /// A texture the size and format of the main texture of the view.
+    fn saved_texture(&mut self, view_entity: Entity) -> Option<TextureView> {
+        let (view, camera) = self.views.get(view_entity).ok()?;
+        let size = camera.physical_target_size?;
+
+        let texture = self.texture_cache.get(
+            &self.render_device,
+            TextureDescriptor {
+                label: Some("Effect Mask Texture"),
+                size: Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: PostProcessingPipelineKey::for_view(view, vec![]).target_format,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        Some(texture.default_view)
+    }
+}
+
+/// Creates the bind groups of masked effects.
+///
+/// Effects add their items in both the prepare and queue sets,
+/// so this runs afterwards.
+fn queue_effect_masks(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    data: Res<EffectMaskData>,
+    images: Res<RenderAssets<Image>>,
+    mut uniforms: ResMut<EffectMaskUniforms>,
+    passes: Query<(Entity, &EffectMaskPass)>,
+) {
+    uniforms.buffer.clear();
+
+    let passes: Vec<_> = passes
+        .iter()
+        .map(|(entity, pass)| {
+            let mask_image = match pass.mask.as_ref().map(|mask| &mask.shape) {
+                Some(EffectMaskShape::Image(handle)) => images.get(handle),
+                _ => None,
+            };
+
+            let uniform =
+                EffectMaskUniform::new(pass.mask.as_ref(), mask_image.is_some(), pass.amount);
+            let uniform_offset = uniforms.buffer.push(uniform);
+
+            // The mask image binding is not used without an image, but something has to be bound.
+            let mask_view = mask_image.map_or(&pass.saved, |image| &image.texture_view);
+
+            (entity, &pass.saved, mask_view, uniform_offset)
+        })
+        .collect();
+
+    uniforms.buffer.write_buffer(&render_device, &render_queue);
+
+    let Some(binding) = uniforms.buffer.binding() else {
+        return;
+    };
+
+    for (entity, saved, mask_view, uniform_offset) in passes {
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Effect Mask Uniform Bind Group"),
+            layout: &data.layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(saved),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&data.sampler),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(mask_view),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: binding.clone(),
+                },
+            ],
+        });
+
+        commands.entity(entity).insert(EffectMaskBindGroup {
+            bind_group,
+            uniform_offset,
+        });
+    }
+}
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            EFFECT_MASK_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "effect-mask.wgsl"
+            ),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<EffectMask>()
+            .register_type::<EffectMaskShape>();
+
+        super::render_app(app)
+            .init_resource::<EffectMaskData>()
+            .init_resource::<SpecializedRenderPipelines<EffectMaskData>>()
+            .init_resource::<EffectMaskUniforms>()
+            .add_system(queue_effect_masks.in_set(RenderSet::PhaseSort))
+            .add_render_command::<PostProcessingPhaseItem, DrawEffectMaskSave>()
+            .add_render_command::<PostProcessingPhaseItem, DrawEffectMask>();
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt::Display;
+
+use bevy::{
+    asset::load_internal_asset,
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{ShaderDefVal, ShaderType},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin, PostProcessingStage};
+
+const FILM_GRAIN_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 12710433698417598356);
+
+/// The height of the view [`FilmGrain::size`] is relative to.
+const REFERENCE_HEIGHT: f32 = 1080.0;
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            FILM_GRAIN_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "film-grain.wgsl"
+            ),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<FilmGrain>()
+            .register_type::<Order<FilmGrain>>()
+            .add_plugin(PostProcessingEffectPlugin::<FilmGrain>::default());
+    }
+}
+
+/// Animated grain, like that of film or a noisy camera sensor.
+///
+/// The grain covers the same part of the view at any resolution and aspect ratio.
+/// How strong it is depends on the brightness of the view underneath,
+/// via the `shadows`, `midtones`, and `highlights` response.
+///
+/// Runs after tonemapping by default, such that the grain is added to the colors as they are displayed.
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilmGrain {
+    /// How much the grain changes the colors.
+    pub intensity: f32,
+
+    /// The size of a grain, in pixels of a view 1080 pixels tall.
+    pub size: f32,
+
+    /// Whether each color channel has its own grain.
+    /// If not, the grain is monochrome.
+    pub colored: bool,
+
+    /// How many times per second the grain changes.
+    /// At zero the grain stays still.
+    pub frame_rate: f32,
+
+    /// How strong the grain is in dark parts of the view.
+    pub shadows: f32,
+
+    /// How strong the grain is in parts of the view of medium brightness.
+    pub midtones: f32,
+
+    /// How strong the grain is in bright parts of the view.
+    pub highlights: f32,
+}
+
+impl Default for FilmGrain {
+    fn default() -> Self {
+        Self {
+            intensity: 0.1,
+            size: 1.5,
+            colored: false,
+            frame_rate: 24.0,
+            shadows: 0.6,
+            midtones: 1.0,
+            highlights: 0.3,
+        }
+    }
+}
+
+impl Display for FilmGrain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "FilmGrain intensity: {}, size: {}, colored: {}, frame rate: {}, response: ({}, {}, {})",
+            self.intensity,
+            self.size,
+            self.colored,
+            self.frame_rate,
+            self.shadows,
+            self.midtones,
+            self.highlights
+        )
+    }
+}
+
+#[doc(hidden)]
+/// [`FilmGrain`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct FilmGrainUniform {
+    pub(crate) intensity: f32,
+    pub(crate) grains_per_height: f32,
+    pub(crate) frame_rate: f32,
+    pub(crate) shadows: f32,
+    pub(crate) midtones: f32,
+    pub(crate) highlights: f32,
+}
+
+impl From<FilmGrain> for FilmGrainUniform {
+    fn from(grain: FilmGrain) -> Self {
+        Self {
+            intensity: grain.intensity,
+            grains_per_height: REFERENCE_HEIGHT / grain.size.max(f32::EPSILON),
+            frame_rate: grain.frame_rate,
+            shadows: grain.shadows,
+            midtones: grain.midtones,
+            highlights: grain.highlights,
+        }
+    }
+}
+
+impl PostProcessingEffect for FilmGrain {
+    type Uniform = FilmGrainUniform;
+
+    const LABEL: &'static str = "FilmGrain";
+
+    fn shader() -> Handle<Shader> {
+        FILM_GRAIN_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        if self.colored {
+            vec!["COLORED".into()]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Grain is added to the tonemapped colors.
+    fn stage(&self) -> PostProcessingStage {
+        PostProcessingStage::PostTonemap
+    }
+}
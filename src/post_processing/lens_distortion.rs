@@ -0,0 +1,181 @@
+use std::fmt::Display;
+
+use bevy::{
+    asset::load_internal_asset,
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{ShaderDefVal, ShaderType},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin};
+
+const LENS_DISTORTION_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 15522298499183788450);
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            LENS_DISTORTION_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "lens-distortion.wgsl"
+            ),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<LensDistortion>()
+            .register_type::<Order<LensDistortion>>()
+            .add_plugin(PostProcessingEffectPlugin::<LensDistortion>::default());
+    }
+}
+
+/// Distorts the view like a lens, with the radial terms of the Brown-Conrady model,
+/// and fringes of color which grow with the distance from the optical center.
+///
+/// Distances from the optical center are measured in half the height of the view,
+/// so the top and bottom edges are at a distance of one from the center of the view.
+/// The view at distance `r` shows what was at distance `r * (1 + k1 * r^2 + k2 * r^4)` before the distortion.
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LensDistortion {
+    /// The first radial coefficient.
+    /// Positive values bulge the view outwards like a wide angle lens (barrel distortion),
+    /// negative values pinch it like a telephoto lens (pincushion distortion).
+    pub k1: f32,
+
+    /// The second radial coefficient, which mostly affects the edges and corners.
+    pub k2: f32,
+
+    /// The optical center in UV space, which stays in place.
+    pub center: Vec2,
+
+    /// Zooms in just enough that nothing beyond the edges of the view is shown.
+    /// Otherwise what is beyond the edges is black.
+    pub zoom_to_fill: bool,
+
+    /// How much further the red channel and how much less far the blue channel are distorted at the farthest corner,
+    /// as a fraction of the distance from the optical center.
+    /// The fringes grow with the square of the distance from the center, and negative values swap red and blue.
+    pub chromatic_aberration: f32,
+}
+
+impl LensDistortion {
+    /// Barrel distortion of the given strength, zoomed to fill the view.
+    pub fn barrel(strength: f32) -> Self {
+        Self {
+            k1: strength,
+            ..default()
+        }
+    }
+
+    /// Pincushion distortion of the given strength.
+    pub fn pincushion(strength: f32) -> Self {
+        Self {
+            k1: -strength,
+            ..default()
+        }
+    }
+}
+
+impl Default for LensDistortion {
+    fn default() -> Self {
+        Self {
+            k1: 0.1,
+            k2: 0.0,
+            center: Vec2::splat(0.5),
+            zoom_to_fill: true,
+            chromatic_aberration: 0.01,
+        }
+    }
+}
+
+impl Display for LensDistortion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "LensDistortion k1: {}, k2: {}, center: {}, zoom to fill: {}, chromatic aberration: {}",
+            self.k1, self.k2, self.center, self.zoom_to_fill, self.chromatic_aberration
+        )
+    }
+}
+
+#[doc(hidden)]
+/// [`LensDistortion`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct LensDistortionUniform {
+    pub(crate) center: Vec2,
+    pub(crate) k1: f32,
+    pub(crate) k2: f32,
+    pub(crate) chromatic_aberration: f32,
+}
+
+impl From<LensDistortion> for LensDistortionUniform {
+    fn from(distortion: LensDistortion) -> Self {
+        Self {
+            center: distortion.center,
+            k1: distortion.k1,
+            k2: distortion.k2,
+            chromatic_aberration: distortion.chromatic_aberration,
+        }
+    }
+}
+
+#[cfg(feature = "cpu")]
+impl LensDistortionUniform {
+    /// Where the red, green, and blue channels are sampled for `uv`, in a view of the given resolution.
+    pub(crate) fn sample_uvs(&self, uv: Vec2, resolution: Vec2, zoom_to_fill: bool) -> [Vec2; 3] {
+        let aspect = Vec2::new(resolution.x / resolution.y, 1.0);
+        let factor = |r2: f32| 1.0 + self.k1 * r2 + self.k2 * r2 * r2;
+
+        let from_center = uv - self.center;
+        let r2 = (from_center * aspect * 2.0).length_squared();
+
+        let corner = self.center.max(1.0 - self.center) * aspect * 2.0;
+        let corner_r2 = corner.length_squared();
+
+        let zoom = if zoom_to_fill {
+            // The largest factor within the view, which is at the center, the farthest corner, or the vertex in between.
+            let vertex = -self.k1 / (2.0 * self.k2);
+            let mut largest = factor(0.0).max(factor(corner_r2));
+            if self.k2 < 0.0 && vertex > 0.0 && vertex < corner_r2 {
+                largest = largest.max(factor(vertex));
+            }
+            largest * (1.0 + self.chromatic_aberration.abs())
+        } else {
+            1.0
+        };
+
+        let distorted = from_center * factor(r2) / zoom;
+        let fringe = self.chromatic_aberration * r2 / corner_r2;
+
+        [1.0 + fringe, 1.0, 1.0 - fringe].map(|scale| self.center + distorted * scale)
+    }
+}
+
+impl PostProcessingEffect for LensDistortion {
+    type Uniform = LensDistortionUniform;
+
+    const LABEL: &'static str = "LensDistortion";
+
+    fn shader() -> Handle<Shader> {
+        LENS_DISTORTION_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        if self.zoom_to_fill {
+            vec!["ZOOM_TO_FILL".into()]
+        } else {
+            vec![]
+        }
+    }
+}
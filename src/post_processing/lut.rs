@@ -1,5 +1,5 @@
 use bevy::{
-    asset::load_internal_asset,
+    asset::{load_internal_asset, AssetPath, LoadContext},
     ecs::{
         query::{QueryItem, ROQueryItem},
         system::{lifetimeless::Read, SystemParamItem},
@@ -7,7 +7,10 @@ use bevy::{
     prelude::*,
     reflect::TypeUuid,
     render::{
-        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
         render_asset::RenderAssets,
         render_phase::{
             AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
@@ -15,28 +18,50 @@ use bevy::{
         },
         render_resource::{
             BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry,
-            BindingResource, BindingType, CachedRenderPipelineId, Extent3d, SamplerBindingType,
-            ShaderStages, TextureDimension, TextureFormat, TextureSampleType,
-            TextureViewDescriptor, TextureViewDimension,
+            BindingResource, BindingType, BufferBindingType, Extent3d, PipelineCache,
+            RenderPipelineDescriptor, SamplerBindingType, ShaderStages, ShaderType,
+            SpecializedRenderPipeline, SpecializedRenderPipelines, TextureDimension, TextureFormat,
+            TextureSampleType, TextureViewDescriptor, TextureViewDimension,
         },
         renderer::RenderDevice,
-        texture::{CompressedImageFormats, ImageType},
+        texture::{CompressedImageFormats, GpuImage, ImageSampler, ImageType},
+        view::ExtractedView,
         RenderSet,
     },
 };
 
-use super::{DrawPostProcessing, Order, PostProcessingPhaseItem, SetTextureSamplerGlobals};
+use serde::{ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    effect_mask::{EffectMasking, Masked},
+    DrawPostProcessing, Order, PostProcessingPhaseItem, PostProcessingPipelineKey,
+    PostProcessingSharedLayout, PostProcessingStage, PostProcessingTarget,
+    SetTextureSamplerGlobals,
+};
 
 pub(crate) const LUT_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3719875149378986812);
 
 const LUT_ARCTIC_IMAGE_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Image::TYPE_UUID, 11514769687270273032);
+const LUT_BURLESQUE_IMAGE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Image::TYPE_UUID, 1706247539587234661);
+const LUT_DENIM_IMAGE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Image::TYPE_UUID, 13374616640129871549);
 const LUT_NEO_IMAGE_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Image::TYPE_UUID, 18411885151390434307);
+const LUT_NEUTRAL_IMAGE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Image::TYPE_UUID, 5590164719473532930);
+const LUT_ROUGE_IMAGE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Image::TYPE_UUID, 9137452866617437086);
+const LUT_SAUNA_IMAGE_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Image::TYPE_UUID, 16028806497104530245);
 const LUT_SLATE_IMAGE_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Image::TYPE_UUID, 8809687374954616573);
 
+/// Loading `.cube` files.
+pub mod cube;
+
 type DrawLut = (
     // The pipeline must be set in order to use the correct bind group,
     // access the correct shaders, and so on.
@@ -62,30 +87,38 @@ struct SetLutImage<const I: usize>;
 impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetLutImage<I> {
     type Param = ();
     type ViewWorldQuery = ();
-    type ItemWorldQuery = Read<LutBindGroup>;
+    type ItemWorldQuery = (
+        Option<Read<LutBindGroup>>,
+        Read<DynamicUniformIndex<LutUniform>>,
+    );
 
     #[inline]
     fn render<'w>(
         _item: &P,
         _view: (),
-        lut_bind_group: ROQueryItem<'w, Self::ItemWorldQuery>,
+        (lut_bind_group, uniform_index): ROQueryItem<'w, Self::ItemWorldQuery>,
         _param: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
-        pass.set_bind_group(I, &lut_bind_group.bind_group, &[]);
+        // The bind group is missing if the images were not ready when queueing.
+        let Some(lut_bind_group) = lut_bind_group else {
+            return RenderCommandResult::Failure;
+        };
+
+        pass.set_bind_group(I, &lut_bind_group.bind_group, &[uniform_index.index()]);
         RenderCommandResult::Success
     }
 }
 
 #[derive(Resource)]
 pub(crate) struct LutData {
-    pub pipeline_id: CachedRenderPipelineId,
+    pub shared_layout: BindGroupLayout,
     pub layout: BindGroupLayout,
 }
 
 impl FromWorld for LutData {
     fn from_world(world: &mut World) -> Self {
-        let (layout, pipeline_id) = super::create_layout_and_pipeline(
+        let layout = super::create_layout(
             world,
             "LUT",
             &[
@@ -105,17 +138,56 @@ impl FromWorld for LutData {
                     ty: BindingType::Sampler(SamplerBindingType::Filtering),
                     count: None,
                 },
+                // The LUT to cross-fade to.
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D3,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(LutUniform::min_size()),
+                    },
+                    count: None,
+                },
             ],
-            LUT_SHADER_HANDLE.typed(),
         );
 
+        let shared_layout = world
+            .resource::<PostProcessingSharedLayout>()
+            .shared_layout
+            .clone();
+
         LutData {
-            pipeline_id,
+            shared_layout,
             layout,
         }
     }
 }
 
+impl SpecializedRenderPipeline for LutData {
+    type Key = PostProcessingPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        super::render_pipeline_descriptor(
+            "LUT",
+            &self.shared_layout,
+            &self.layout,
+            LUT_SHADER_HANDLE.typed(),
+            key,
+        )
+    }
+}
+
 pub(crate) struct Plugin;
 impl bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
@@ -128,47 +200,37 @@ impl bevy::prelude::Plugin for Plugin {
 
         let mut assets = app.world.resource_mut::<Assets<_>>();
 
-        let image = Image::from_buffer(
-            include_bytes!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/assets/luts/",
-                "neo.png"
-            )),
-            ImageType::Extension("png"),
-            CompressedImageFormats::NONE,
-            false,
-        )
-        .expect("Should load LUT successfully");
-        assets.set_untracked(LUT_NEO_IMAGE_HANDLE, image);
-
-        let image = Image::from_buffer(
-            include_bytes!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/assets/luts/",
-                "slate.png"
-            )),
-            ImageType::Extension("png"),
-            CompressedImageFormats::NONE,
-            false,
-        )
-        .expect("Should load LUT successfully");
-        assets.set_untracked(LUT_SLATE_IMAGE_HANDLE, image);
-
-        let image = Image::from_buffer(
-            include_bytes!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/assets/luts/",
-                "arctic.png"
-            )),
-            ImageType::Extension("png"),
-            CompressedImageFormats::NONE,
-            false,
-        )
-        .expect("Should load LUT successfully");
-        assets.set_untracked(LUT_ARCTIC_IMAGE_HANDLE, image);
+        macro_rules! add_lut {
+            ($handle:expr, $name:literal) => {
+                let image = Image::from_buffer(
+                    include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/luts/", $name)),
+                    ImageType::Extension("png"),
+                    CompressedImageFormats::NONE,
+                    false,
+                )
+                .expect("Should load LUT successfully");
+                assets.set_untracked($handle, image);
+            };
+        }
+
+        add_lut!(LUT_ARCTIC_IMAGE_HANDLE, "arctic.png");
+        add_lut!(LUT_BURLESQUE_IMAGE_HANDLE, "burlesque.png");
+        add_lut!(LUT_DENIM_IMAGE_HANDLE, "denim.png");
+        add_lut!(LUT_NEO_IMAGE_HANDLE, "neo.png");
+        add_lut!(LUT_NEUTRAL_IMAGE_HANDLE, "neutral.png");
+        add_lut!(LUT_ROUGE_IMAGE_HANDLE, "rouge.png");
+        add_lut!(LUT_SAUNA_IMAGE_HANDLE, "sauna.png");
+        add_lut!(LUT_SLATE_IMAGE_HANDLE, "slate.png");
+
+        app.add_asset_loader(cube::CubeLutLoader)
+            .register_type::<Lut>()
+            .register_type::<Order<Lut>>()
+            .register_type::<LutImage>()
+            .register_type::<LutSplit>();
 
         // This puts the uniform into the render world.
         app.add_plugin(ExtractComponentPlugin::<Lut>::default())
+            .add_plugin(UniformComponentPlugin::<LutUniform>::default())
             .add_system(adapt_image_for_lut_use.in_base_set(CoreSet::PostUpdate));
 
         super::render_app(app)
@@ -176,6 +238,7 @@ impl bevy::prelude::Plugin for Plugin {
                 super::extract_post_processing_camera_phases::<Lut>.in_schedule(ExtractSchedule),
             )
             .init_resource::<LutData>()
+            .init_resource::<SpecializedRenderPipelines<LutData>>()
             .add_system(prepare.in_set(RenderSet::Prepare))
             .add_system(queue.in_set(RenderSet::Queue))
             .add_render_command::<PostProcessingPhaseItem, DrawLut>();
@@ -184,60 +247,237 @@ impl bevy::prelude::Plugin for Plugin {
 
 fn adapt_image_for_lut_use(
     mut assets: ResMut<Assets<Image>>,
-    mut luts: Query<&mut Lut, Changed<Lut>>,
+    mut events: EventReader<AssetEvent<Image>>,
+    mut luts: Query<&mut Lut>,
 ) {
+    // A reloaded strip is a 2D image again, so it must be adapted anew.
+    // This also gives images which could not be used another chance.
+    // Adapting an image modifies it too, in which case it is found to be 3D already below.
+    for event in events.iter() {
+        let AssetEvent::Modified { handle } = event else {
+            continue;
+        };
+
+        for mut lut in luts.iter_mut() {
+            if &lut.texture == handle || lut.cross_fade_texture.as_ref() == Some(handle) {
+                lut.state = LutState::Pending;
+            }
+        }
+    }
+
     for mut lut in luts.iter_mut() {
-        if lut.prepared {
+        if lut.state != LutState::Pending {
             continue;
         }
 
-        let image = assets
-            .get_mut(&lut.texture)
-            .expect("Handle should point to asset");
+        let textures = std::iter::once(&lut.texture).chain(lut.cross_fade_texture.as_ref());
+
+        let mut state = LutState::Prepared;
+        for texture in textures {
+            match adapt_image(&mut assets, texture) {
+                Ok(true) => {}
+                // Not loaded yet, try again when it is.
+                Ok(false) => state = LutState::Pending,
+                Err(e) => {
+                    error!("Could not use image {texture:?} as a LUT: {e}");
+                    state = LutState::Invalid;
+                    break;
+                }
+            }
+        }
 
-        // The LUT is a 3d texture. It has 64 layers, each of which is a 64x64 image.
-        image.texture_descriptor.size = Extent3d {
-            width: 64,
-            height: 64,
-            depth_or_array_layers: 64,
-        };
-        image.texture_descriptor.dimension = TextureDimension::D3;
-        image.texture_descriptor.format = TextureFormat::Rgba8Unorm;
-
-        image.texture_view_descriptor = Some(TextureViewDescriptor {
-            label: Some("LUT Texture View"),
-            format: Some(TextureFormat::Rgba8Unorm),
-            dimension: Some(TextureViewDimension::D3),
-            ..default()
-        });
-
-        debug!("LUT prepared for handle {:?}", lut.texture);
-        lut.prepared = true;
+        lut.state = state;
+    }
+}
+
+/// Makes the image a 3D texture if it is not one already.
+///
+/// Returns whether the image is ready for use, which it is not if it is not loaded yet.
+fn adapt_image(assets: &mut Assets<Image>, texture: &Handle<Image>) -> Result<bool, String> {
+    let Some(image) = assets.get(texture) else {
+        return Ok(false);
+    };
+
+    // Images which are already 3D, such as those loaded from `.cube` files, are used as is.
+    // This is also the case for strips already adapted for another `Lut` using the same image.
+    if image.texture_descriptor.dimension == TextureDimension::D3 {
+        return Ok(true);
+    }
+
+    let image = assets
+        .get_mut(texture)
+        .expect("Handle should point to asset");
+
+    let size = adapt_lut_strip(image)?;
+    debug!("LUT of size {size} prepared for handle {texture:?}");
+
+    Ok(true)
+}
+
+/// Turns a strip of `N` blocks, each of which is `N`x`N`, into a `N`x`N`x`N` 3D texture.
+/// The strip is therefore `N * N` wide and `N` tall.
+///
+/// 8-bit images are used as is, while 16-bit and float images are converted to 16-bit float.
+///
+/// Returns `N`.
+fn adapt_lut_strip(image: &mut Image) -> Result<u32, String> {
+    let Extent3d { width, height, .. } = image.texture_descriptor.size;
+
+    let size = height;
+    if size < 2 || width != size * size {
+        return Err(format!(
+            "The image is {width}x{height}, but a LUT of size N must be N * N wide and N tall, \
+            for example 1024x32 for a LUT of size 32"
+        ));
+    }
+
+    let to_f16_bytes = |value: f32| half::f16::from_f32(value).to_le_bytes();
+
+    let format = match image.texture_descriptor.format {
+        // The values in a LUT are not colors to be converted, so sRGB is ignored.
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => TextureFormat::Rgba8Unorm,
+        TextureFormat::Rgba16Float => TextureFormat::Rgba16Float,
+        TextureFormat::Rgba16Unorm => {
+            image.data = image
+                .data
+                .chunks_exact(2)
+                .flat_map(|bytes| {
+                    to_f16_bytes(u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32)
+                })
+                .collect();
+            TextureFormat::Rgba16Float
+        }
+        // 32-bit float textures are not filterable on all platforms.
+        TextureFormat::Rgba32Float => {
+            image.data = image
+                .data
+                .chunks_exact(4)
+                .flat_map(|bytes| {
+                    to_f16_bytes(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                })
+                .collect();
+            TextureFormat::Rgba16Float
+        }
+        other => {
+            return Err(format!(
+                "The image format {other:?} is not supported, \
+                use an 8-bit, 16-bit, or float RGBA image"
+            ))
+        }
+    };
+
+    // The rows of the strip become the layers of the 3D texture.
+    image.texture_descriptor.size = Extent3d {
+        width: size,
+        height: size,
+        depth_or_array_layers: size,
+    };
+    image.texture_descriptor.dimension = TextureDimension::D3;
+    image.texture_descriptor.format = format;
+
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        label: Some("LUT Texture View"),
+        format: Some(format),
+        dimension: Some(TextureViewDimension::D3),
+        ..default()
+    });
+
+    // Colors between the entries of the LUT must be interpolated,
+    // even if the app defaults to nearest sampling.
+    image.sampler_descriptor = ImageSampler::linear();
+
+    Ok(size)
+}
+
+/// Makes a `size`x`size`x`size` 3D LUT image, with the same layout as an adapted LUT strip:
+/// Red along x, blue along y, and green along z.
+///
+/// `entry` gives the color which the color at the given red, green, and blue indices maps to.
+pub(crate) fn lut_image(size: u32, entry: impl Fn(UVec3) -> Vec3) -> Image {
+    let mut data = Vec::with_capacity((size * size * size) as usize * 8);
+
+    for green in 0..size {
+        for blue in 0..size {
+            for red in 0..size {
+                let color = entry(UVec3::new(red, green, blue));
+
+                for value in color.extend(1.0).to_array() {
+                    data.extend_from_slice(&half::f16::from_f32(value).to_le_bytes());
+                }
+            }
+        }
     }
+
+    let mut image = Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: size,
+        },
+        TextureDimension::D3,
+        data,
+        TextureFormat::Rgba16Float,
+    );
+
+    // The LUT is small, so colors between its entries must be interpolated.
+    image.sampler_descriptor = ImageSampler::linear();
+
+    image
+}
+
+/// The GPU images of the LUT and the LUT to cross-fade to, if both are ready.
+fn lut_images<'a>(
+    lut: &Lut,
+    images: &'a RenderAssets<Image>,
+) -> Option<(&'a GpuImage, &'a GpuImage)> {
+    // Without a LUT to cross-fade to, the blend factor is zero, so any LUT will do.
+    let cross_fade_texture = lut.cross_fade_texture.as_ref().unwrap_or(&lut.texture);
+
+    Some((images.get(&lut.texture)?, images.get(cross_fade_texture)?))
 }
 
 #[allow(clippy::type_complexity)]
 fn prepare(
     data: Res<LutData>,
-    mut views: Query<
-        (
-            Entity,
-            &mut RenderPhase<PostProcessingPhaseItem>,
-            &Order<Lut>,
-        ),
-        With<Lut>,
-    >,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<LutData>>,
+    mut masking: EffectMasking,
+    mut views: Query<(
+        Entity,
+        &mut RenderPhase<PostProcessingPhaseItem>,
+        &ExtractedView,
+        &Lut,
+        &Order<Lut>,
+        Option<&Masked<Lut>>,
+    )>,
+    images: Res<RenderAssets<Image>>,
     draw_functions: Res<DrawFunctions<PostProcessingPhaseItem>>,
 ) {
-    for (entity, mut phase, order) in views.iter_mut() {
+    for (entity, mut phase, view, lut, order, maybe_masked) in views.iter_mut() {
+        // Drawing without the bind group made when queueing is not possible.
+        if lut_images(lut, &images).is_none() {
+            continue;
+        }
+
         let draw_function = draw_functions.read().id::<DrawLut>();
 
-        phase.add(PostProcessingPhaseItem {
+        let key = PostProcessingPipelineKey::for_view(view, vec![]);
+        let pipeline_id = pipelines.specialize(&pipeline_cache, &data, key);
+
+        let sort_key = order.sort_key(PostProcessingStage::PostTonemap);
+        masking.add_items(
+            &mut phase,
             entity,
-            sort_key: order.clone().into(),
-            draw_function,
-            pipeline_id: data.pipeline_id,
-        });
+            sort_key,
+            maybe_masked.map(|masked| &masked.mask),
+            [PostProcessingPhaseItem {
+                entity,
+                sort_key,
+                draw_function,
+                pipeline_id,
+                target: PostProcessingTarget::ViewTarget,
+            }],
+        );
     }
 }
 
@@ -246,10 +486,15 @@ fn queue(
     render_device: Res<RenderDevice>,
     data: Res<LutData>,
     images: Res<RenderAssets<Image>>,
+    uniforms: Res<ComponentUniforms<LutUniform>>,
     luts: Query<(Entity, &Lut)>,
 ) {
+    let Some(uniforms) = uniforms.binding() else {
+        return;
+    };
+
     for (entity, lut) in luts.iter() {
-        if let Some(lut_image) = images.get(&lut.texture) {
+        if let Some((lut_image, cross_fade_image)) = lut_images(lut, &images) {
             let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
                 label: Some("LUT Uniform Bind Group"),
                 layout: &data.layout,
@@ -262,6 +507,14 @@ fn queue(
                         binding: 1,
                         resource: BindingResource::Sampler(&lut_image.sampler),
                     },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: BindingResource::TextureView(&cross_fade_image.texture_view),
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: uniforms.clone(),
+                    },
                 ],
             });
 
@@ -273,57 +526,348 @@ fn queue(
 }
 
 /// A look-up texture. Maps colors to colors. Useful for colorschemes.
-#[derive(Debug, Component, Clone)]
+///
+/// The graded image may be mixed with the original image via [`Lut::intensity`],
+/// cross-faded to the grade of another LUT via [`Lut::cross_fade`],
+/// and limited to one side of a [`LutSplit`] in order to compare the graded and original image.
+///
+/// LUTs made from a [`LutImage`] can be serialized, which LUTs made from a handle via [`Lut::new`] can not.
+///
+/// LUTs run after tonemapping, since they map the colors which are displayed.
+/// See [`PostProcessingStage`].
+#[derive(Debug, Component, Clone, Reflect, FromReflect)]
+#[reflect(Component, Default, Serialize, Deserialize)]
 pub struct Lut {
     /// The 3D look-up texture
-    texture: Handle<Image>,
+    pub(crate) texture: Handle<Image>,
+
+    /// The 3D look-up texture to cross-fade to.
+    pub(crate) cross_fade_texture: Option<Handle<Image>>,
+
+    /// Where the texture comes from, if known.
+    pub(crate) image: Option<LutImage>,
+
+    /// Where the texture to cross-fade to comes from, if known.
+    pub(crate) cross_fade_image: Option<LutImage>,
+
+    /// How much of the graded image is used.
+    /// Zero shows the original image, one shows the fully graded image.
+    pub intensity: f32,
+
+    /// How far along the cross-fade to the other LUT the grade is.
+    /// Zero uses only this LUT, one uses only the other LUT.
+    /// Has no effect without a LUT to cross-fade to.
+    pub blend: f32,
+
+    /// If set, only one side of the split line is graded.
+    pub split: Option<LutSplit>,
+
+    #[reflect(ignore)]
+    state: LutState,
+}
+
+/// Where the image of a [`Lut`] comes from.
+///
+/// This is how the image of a [`Lut`] is described when serialized.
+#[derive(Debug, Clone, PartialEq, Eq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum LutImage {
+    /// See [`Lut::arctic`].
+    Arctic,
+    /// See [`Lut::burlesque`].
+    Burlesque,
+    /// See [`Lut::denim`].
+    Denim,
+    /// See [`Lut::neo`].
+    Neo,
+    /// See [`Lut::neutral`].
+    Neutral,
+    /// See [`Lut::rouge`].
+    Rouge,
+    /// See [`Lut::sauna`].
+    Sauna,
+    /// See [`Lut::slate`].
+    Slate,
+    /// An image asset, such as a LUT strip or a `.cube` file, by its asset path.
+    ///
+    /// Presets load the image, otherwise it must be loaded via the [`AssetServer`].
+    Path(String),
+}
+
+impl LutImage {
+    /// The handle of the image.
+    ///
+    /// The handle is weak, so images given by path must be loaded elsewhere to be used.
+    pub fn handle(&self) -> Handle<Image> {
+        match self {
+            LutImage::Arctic => LUT_ARCTIC_IMAGE_HANDLE.typed_weak(),
+            LutImage::Burlesque => LUT_BURLESQUE_IMAGE_HANDLE.typed_weak(),
+            LutImage::Denim => LUT_DENIM_IMAGE_HANDLE.typed_weak(),
+            LutImage::Neo => LUT_NEO_IMAGE_HANDLE.typed_weak(),
+            LutImage::Neutral => LUT_NEUTRAL_IMAGE_HANDLE.typed_weak(),
+            LutImage::Rouge => LUT_ROUGE_IMAGE_HANDLE.typed_weak(),
+            LutImage::Sauna => LUT_SAUNA_IMAGE_HANDLE.typed_weak(),
+            LutImage::Slate => LUT_SLATE_IMAGE_HANDLE.typed_weak(),
+            LutImage::Path(path) => Handle::weak(AssetPath::from(path.as_str()).into()),
+        }
+    }
+}
+
+/// A line dividing the screen in a graded and an ungraded part.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LutSplit {
+    /// Where the line crosses the horizontal center of the screen, from zero (left) to one (right).
+    pub position: f32,
+
+    /// The angle of the line in radians, where zero is a vertical line.
+    /// The graded part is to the right of a vertical line, and rotates with the line.
+    pub angle: f32,
+}
+
+impl Default for LutSplit {
+    fn default() -> Self {
+        Self {
+            position: 0.5,
+            angle: 0.0,
+        }
+    }
+}
+
+/// Whether the image of a [`Lut`] is ready for use as a 3D texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum LutState {
+    /// The image is not loaded or not adapted yet.
+    #[default]
+    Pending,
 
-    prepared: bool,
+    /// The image is a 3D texture.
+    Prepared,
+
+    /// The image could not be used as a LUT, see the logged error.
+    Invalid,
 }
 
 impl Lut {
     /// Creates a new LUT component.
-    /// The image should be a 64x64x64 3D texture.
-    /// See the `make-neutral-lut` example.
+    /// The image should either be a strip of `N` blocks of `N`x`N` texels, such as the 4096x64
+    /// strip made by the `make-neutral-lut` example or a 1024x32 strip,
+    /// or a 3D image such as one loaded from a `.cube` file via [`cube::CubeLutLoader`].
+    ///
+    /// Strips may be 8-bit, 16-bit, or float images.
     pub fn new(texture: Handle<Image>) -> Self {
         Self {
             texture,
-            prepared: false,
+            cross_fade_texture: None,
+            image: None,
+            cross_fade_image: None,
+            intensity: 1.0,
+            blend: 0.0,
+            split: None,
+            state: LutState::Pending,
+        }
+    }
+
+    /// Creates a new LUT component from a bundled LUT or an image asset.
+    pub fn from_image(image: LutImage) -> Self {
+        Self {
+            image: Some(image.clone()),
+            ..Self::new(image.handle())
         }
     }
 
+    /// Cross-fades to the grade of another LUT, by the given blend factor.
+    /// See [`Lut::blend`].
+    pub fn cross_fade(mut self, to: &Lut, blend: f32) -> Self {
+        self.set_cross_fade(Some(to));
+        self.blend = blend;
+        self
+    }
+
+    /// Sets or clears the LUT to cross-fade to.
+    /// Only the texture of the given LUT is used, not its settings.
+    pub fn set_cross_fade(&mut self, to: Option<&Lut>) {
+        self.cross_fade_texture = to.map(|lut| lut.texture.clone());
+        self.cross_fade_image = to.and_then(|lut| lut.image.clone());
+
+        // The new texture might need adapting.
+        self.state = LutState::Pending;
+    }
+
     /// The arctic color scheme LUT.
     pub fn arctic() -> Self {
-        Self::new(LUT_ARCTIC_IMAGE_HANDLE.typed_weak())
+        Self::from_image(LutImage::Arctic)
+    }
+
+    /// The burlesque color scheme LUT.
+    pub fn burlesque() -> Self {
+        Self::from_image(LutImage::Burlesque)
+    }
+
+    /// The denim color scheme LUT.
+    pub fn denim() -> Self {
+        Self::from_image(LutImage::Denim)
     }
 
     /// The neo color scheme LUT.
     pub fn neo() -> Self {
-        Self::default()
+        Self::from_image(LutImage::Neo)
+    }
+
+    /// The neutral LUT, which leaves colors as they are.
+    pub fn neutral() -> Self {
+        Self::from_image(LutImage::Neutral)
+    }
+
+    /// The rouge color scheme LUT.
+    pub fn rouge() -> Self {
+        Self::from_image(LutImage::Rouge)
+    }
+
+    /// The sauna color scheme LUT.
+    pub fn sauna() -> Self {
+        Self::from_image(LutImage::Sauna)
     }
 
     /// The slate color scheme LUT.
     pub fn slate() -> Self {
-        Self::new(LUT_SLATE_IMAGE_HANDLE.typed_weak())
+        Self::from_image(LutImage::Slate)
+    }
+
+    /// Replaces the weak handles of images given by path with strong handles from the load context,
+    /// and returns the paths such that they are loaded as dependencies.
+    pub(crate) fn load_images(&mut self, load_context: &LoadContext) -> Vec<AssetPath<'static>> {
+        let images = [
+            (Some(&mut self.texture), &self.image),
+            (self.cross_fade_texture.as_mut(), &self.cross_fade_image),
+        ];
+
+        let mut paths = vec![];
+        for (texture, image) in images {
+            if let (Some(texture), Some(LutImage::Path(path))) = (texture, image) {
+                let path = AssetPath::from(path.as_str()).to_owned();
+                *texture = load_context.get_handle(path.clone());
+                paths.push(path);
+            }
+        }
+
+        paths
     }
 }
 
 impl Default for Lut {
     fn default() -> Self {
-        Self::new(LUT_NEO_IMAGE_HANDLE.typed_weak())
+        Self::neo()
+    }
+}
+
+/// How a [`Lut`] is serialized.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Lut")]
+struct LutDescriptor {
+    image: LutImage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cross_fade: Option<LutImage>,
+    #[serde(default = "full_intensity")]
+    intensity: f32,
+    #[serde(default)]
+    blend: f32,
+    #[serde(default)]
+    split: Option<LutSplit>,
+}
+
+fn full_intensity() -> f32 {
+    1.0
+}
+
+impl Serialize for Lut {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let not_serializable =
+            || S::Error::custom("LUTs made from a handle via `Lut::new` can not be serialized");
+
+        let image = self.image.clone().ok_or_else(not_serializable)?;
+        let cross_fade = match (&self.cross_fade_texture, &self.cross_fade_image) {
+            (None, _) => None,
+            (Some(_), Some(image)) => Some(image.clone()),
+            (Some(_), None) => return Err(not_serializable()),
+        };
+
+        LutDescriptor {
+            image,
+            cross_fade,
+            intensity: self.intensity,
+            blend: self.blend,
+            split: self.split,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Lut {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let descriptor = LutDescriptor::deserialize(deserializer)?;
+
+        let mut lut = Lut::from_image(descriptor.image);
+        if let Some(image) = descriptor.cross_fade {
+            lut.set_cross_fade(Some(&Lut::from_image(image)));
+        }
+        lut.intensity = descriptor.intensity;
+        lut.blend = descriptor.blend;
+        lut.split = descriptor.split;
+
+        Ok(lut)
+    }
+}
+
+/// The settings of a [`Lut`] as a uniform.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, Component, ShaderType)]
+pub struct LutUniform {
+    pub(crate) intensity: f32,
+    pub(crate) blend: f32,
+
+    /// A point on the split line, in UV space.
+    pub(crate) split_point: Vec2,
+
+    /// Points towards the graded side of the split line.
+    /// Zero when there is no split, which grades everything.
+    pub(crate) split_normal: Vec2,
+}
+
+impl From<&Lut> for LutUniform {
+    fn from(lut: &Lut) -> Self {
+        let (split_point, split_normal) = match lut.split {
+            Some(split) => (
+                Vec2::new(split.position, 0.5),
+                Vec2::from_angle(split.angle),
+            ),
+            None => (Vec2::ZERO, Vec2::ZERO),
+        };
+
+        Self {
+            intensity: lut.intensity,
+            blend: if lut.cross_fade_texture.is_some() {
+                lut.blend
+            } else {
+                0.0
+            },
+            split_point,
+            split_normal,
+        }
     }
 }
 
 impl ExtractComponent for Lut {
     type Query = (&'static Self, &'static Camera);
     type Filter = ();
-    type Out = Self;
+    type Out = (Self, LutUniform);
 
     fn extract_component((lut, camera): QueryItem<'_, Self::Query>) -> Option<Self::Out> {
-        if !camera.is_active || !lut.prepared {
+        if !camera.is_active || lut.state != LutState::Prepared {
             return None;
         }
 
-        Some(lut.clone())
+        Some((lut.clone(), lut.into()))
     }
 }
@@ -0,0 +1,185 @@
+use std::fmt::Display;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    utils::BoxedFuture,
+};
+
+/// The largest `LUT_3D_SIZE` accepted, as defined by the `.cube` specification.
+const MAX_SIZE: u32 = 256;
+
+/// Loads `.cube` 3D LUT files, as exported by for example Adobe tools and DaVinci Resolve, as 3D [`Image`]s.
+///
+/// Any `LUT_3D_SIZE` is supported, for example 17, 33, or 65.
+/// The resulting image can be used directly with [`Lut::new`](super::Lut::new).
+///
+/// This loader is added by the [`BevyVfxBagPlugin`](crate::BevyVfxBagPlugin).
+#[derive(Debug, Default)]
+pub struct CubeLutLoader;
+
+impl AssetLoader for CubeLutLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let text = std::str::from_utf8(bytes).map_err(|_| CubeLutError::NotUtf8)?;
+            let image = parse_cube(text)?;
+
+            load_context.set_default_asset(LoadedAsset::new(image));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["cube"]
+    }
+}
+
+/// Errors which may happen when parsing a `.cube` file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CubeLutError {
+    /// The file is not valid UTF-8.
+    NotUtf8,
+
+    /// The file describes a 1D LUT, only 3D LUTs are supported.
+    Unsupported1d,
+
+    /// The file has no `LUT_3D_SIZE` line.
+    MissingSize,
+
+    /// The `LUT_3D_SIZE` is outside of `2..=256`.
+    InvalidSize(u32),
+
+    /// The domain is not `0.0` to `1.0`.
+    UnsupportedDomain,
+
+    /// A line could not be parsed.
+    InvalidLine {
+        /// The line number, starting at one.
+        line: usize,
+        /// The contents of the line.
+        contents: String,
+    },
+
+    /// The number of table entries does not match `LUT_3D_SIZE` cubed.
+    WrongEntryCount {
+        /// The amount of entries given by `LUT_3D_SIZE`.
+        expected: usize,
+        /// The amount of entries in the file.
+        found: usize,
+    },
+}
+
+impl Display for CubeLutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CubeLutError::NotUtf8 => write!(f, "The .cube file is not valid UTF-8"),
+            CubeLutError::Unsupported1d => write!(f, "1D .cube LUTs are not supported"),
+            CubeLutError::MissingSize => write!(f, "The .cube file has no LUT_3D_SIZE"),
+            CubeLutError::InvalidSize(size) => {
+                write!(f, "LUT_3D_SIZE {size} is not within 2..={MAX_SIZE}")
+            }
+            CubeLutError::UnsupportedDomain => {
+                write!(f, "Only a domain from 0.0 to 1.0 is supported")
+            }
+            CubeLutError::InvalidLine { line, contents } => {
+                write!(f, "Could not parse line {line}: {contents:?}")
+            }
+            CubeLutError::WrongEntryCount { expected, found } => {
+                write!(f, "Expected {expected} LUT entries, found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CubeLutError {}
+
+/// Parses the contents of a `.cube` file into a 3D [`Image`].
+///
+/// The image is laid out the same way as a 64x64x64 LUT strip reinterpreted as a 3D texture,
+/// which means the green and blue axes are swapped compared to the `.cube` table.
+pub fn parse_cube(text: &str) -> Result<Image, CubeLutError> {
+    let mut size = None;
+    let mut entries: Vec<[f32; 3]> = vec![];
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let invalid_line = || CubeLutError::InvalidLine {
+            line: index + 1,
+            contents: line.to_string(),
+        };
+
+        let mut words = line.split_whitespace();
+        let keyword = words.next().expect("Line should not be empty");
+
+        match keyword {
+            "TITLE" => {}
+            "LUT_1D_SIZE" => return Err(CubeLutError::Unsupported1d),
+            "LUT_3D_SIZE" => {
+                let value: u32 = words
+                    .next()
+                    .and_then(|word| word.parse().ok())
+                    .ok_or_else(invalid_line)?;
+
+                if !(2..=MAX_SIZE).contains(&value) {
+                    return Err(CubeLutError::InvalidSize(value));
+                }
+                size = Some(value);
+            }
+            "DOMAIN_MIN" | "DOMAIN_MAX" | "LUT_3D_INPUT_RANGE" => {
+                let expected = if keyword == "DOMAIN_MAX" { 1.0 } else { 0.0 };
+                let values = words
+                    .map(|word| word.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| invalid_line())?;
+
+                let supported = if keyword == "LUT_3D_INPUT_RANGE" {
+                    values == [0.0, 1.0]
+                } else {
+                    values == [expected; 3]
+                };
+
+                if !supported {
+                    return Err(CubeLutError::UnsupportedDomain);
+                }
+            }
+            _ if keyword.parse::<f32>().is_ok() => {
+                let values = line
+                    .split_whitespace()
+                    .map(|word| word.parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| invalid_line())?;
+
+                match values[..] {
+                    [r, g, b] => entries.push([r, g, b]),
+                    _ => return Err(invalid_line()),
+                }
+            }
+            // Other keywords may appear before the table, such as `LUT_IN_VIDEO_RANGE`.
+            _ if entries.is_empty() => {}
+            _ => return Err(invalid_line()),
+        }
+    }
+
+    let size = size.ok_or(CubeLutError::MissingSize)?;
+    let n = size as usize;
+
+    if entries.len() != n * n * n {
+        return Err(CubeLutError::WrongEntryCount {
+            expected: n * n * n,
+            found: entries.len(),
+        });
+    }
+
+    // In the table red changes fastest, then green, then blue.
+    Ok(super::lut_image(size, |index| {
+        Vec3::from(entries[index.x as usize + index.y as usize * n + index.z as usize * n * n])
+    }))
+}
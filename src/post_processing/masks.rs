@@ -1,64 +1,18 @@
 use bevy::{
     asset::load_internal_asset,
-    ecs::query::QueryItem,
     prelude::*,
     reflect::TypeUuid,
-    render::{
-        extract_component::{
-            ComponentUniforms, ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin,
-        },
-        render_phase::{AddRenderCommand, DrawFunctions, RenderPhase},
-        render_resource::{
-            BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry,
-            BindingType, BufferBindingType, PipelineCache, RenderPipelineDescriptor, ShaderDefVal,
-            ShaderStages, ShaderType, SpecializedRenderPipeline, SpecializedRenderPipelines,
-        },
-        renderer::RenderDevice,
-        RenderSet,
-    },
+    render::render_resource::{ShaderDefVal, ShaderType},
 };
 use std::fmt::Display;
 
-use crate::post_processing::DrawPostProcessingEffect;
+use serde::{Deserialize, Serialize};
+
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin, PostProcessingStage};
 
-use super::{Order, PostProcessingPhaseItem, UniformBindGroup};
 pub(crate) const MASK_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1059400090272595510);
 
-#[derive(Resource)]
-pub(crate) struct MaskData {
-    pub uniform_layout: BindGroupLayout,
-    pub shared_layout: BindGroupLayout,
-}
-
-impl FromWorld for MaskData {
-    fn from_world(world: &mut World) -> Self {
-        let uniform_layout = super::create_layout(
-            world,
-            "Mask",
-            &[BindGroupLayoutEntry {
-                binding: 0,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: true,
-                    min_binding_size: Some(MaskUniform::min_size()),
-                },
-                visibility: ShaderStages::FRAGMENT,
-                count: None,
-            }],
-        );
-
-        let shared_layout = world
-            .resource::<super::PostProcessingSharedLayout>()
-            .shared_layout
-            .clone();
-        MaskData {
-            uniform_layout,
-            shared_layout,
-        }
-    }
-}
-
 pub(crate) struct Plugin;
 impl bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
@@ -69,90 +23,18 @@ impl bevy::prelude::Plugin for Plugin {
             Shader::from_wgsl
         );
 
-        // This puts the uniform into the render world.
-        app.add_plugin(ExtractComponentPlugin::<Mask>::default())
-            .add_plugin(UniformComponentPlugin::<MaskUniform>::default());
-
-        super::render_app(app)
-            .add_system(
-                super::extract_post_processing_camera_phases::<Mask>.in_schedule(ExtractSchedule),
-            )
-            .init_resource::<MaskData>()
-            .init_resource::<UniformBindGroup<MaskUniform>>()
-            .init_resource::<SpecializedRenderPipelines<MaskData>>()
-            .add_system(prepare.in_set(RenderSet::Prepare))
-            .add_system(queue.in_set(RenderSet::Queue))
-            .add_render_command::<PostProcessingPhaseItem, DrawPostProcessingEffect<MaskUniform>>();
-    }
-}
-
-impl SpecializedRenderPipeline for MaskData {
-    type Key = MaskVariant;
-
-    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
-        super::render_pipeline_descriptor(
-            "Masks",
-            &self.shared_layout,
-            &self.uniform_layout,
-            MASK_SHADER_HANDLE.typed(),
-            vec![key.into()],
-        )
-    }
-}
-
-fn prepare(
-    data: Res<MaskData>,
-    pipeline_cache: Res<PipelineCache>,
-    mut pipelines: ResMut<SpecializedRenderPipelines<MaskData>>,
-    mut views: Query<(
-        Entity,
-        &mut RenderPhase<PostProcessingPhaseItem>,
-        &Order<Mask>,
-        &MaskVariant,
-    )>,
-    draw_functions: Res<DrawFunctions<PostProcessingPhaseItem>>,
-) {
-    for (entity, mut phase, order, key) in views.iter_mut() {
-        let draw_function = draw_functions
-            .read()
-            .id::<DrawPostProcessingEffect<MaskUniform>>();
-
-        let pipeline_id = pipelines.specialize(&pipeline_cache, &data, *key);
-
-        phase.add(PostProcessingPhaseItem {
-            entity,
-            sort_key: (*order).into(),
-            draw_function,
-            pipeline_id,
-        });
-    }
-}
-
-fn queue(
-    render_device: Res<RenderDevice>,
-    data: Res<MaskData>,
-    mut bind_group: ResMut<UniformBindGroup<MaskUniform>>,
-    uniforms: Res<ComponentUniforms<MaskUniform>>,
-    views: Query<Entity, With<MaskUniform>>,
-) {
-    bind_group.inner = None;
-
-    if let Some(uniforms) = uniforms.binding() {
-        if !views.is_empty() {
-            bind_group.inner = Some(render_device.create_bind_group(&BindGroupDescriptor {
-                label: Some("Mask Uniform Bind Group"),
-                layout: &data.uniform_layout,
-                entries: &[BindGroupEntry {
-                    binding: 0,
-                    resource: uniforms.clone(),
-                }],
-            }));
-        }
+        app.register_type::<Mask>()
+            .register_type::<Order<Mask>>()
+            .register_type::<MaskVariant>()
+            .add_plugin(PostProcessingEffectPlugin::<Mask>::default());
     }
 }
 
 /// This controls the parameters of the effect.
-#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, Component)]
+#[derive(
+    Debug, Eq, PartialEq, Hash, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Serialize, Deserialize)]
 pub enum MaskVariant {
     /// Rounded square type mask.
     ///
@@ -201,7 +83,9 @@ impl From<MaskVariant> for ShaderDefVal {
 }
 
 /// A darkening mask on the outer edges of the image.
-#[derive(Debug, Copy, Clone, Component)]
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Mask {
     /// The strength parameter of the mask in use.
     ///
@@ -280,16 +164,28 @@ impl From<Mask> for MaskUniform {
     }
 }
 
-impl ExtractComponent for Mask {
-    type Query = (&'static Self, &'static Camera);
-    type Filter = ();
-    type Out = (MaskUniform, MaskVariant);
+impl PostProcessingEffect for Mask {
+    type Uniform = MaskUniform;
 
-    fn extract_component((settings, camera): QueryItem<'_, Self::Query>) -> Option<Self::Out> {
-        if !camera.is_active {
-            return None;
-        }
+    const LABEL: &'static str = "Masks";
+
+    fn shader() -> Handle<Shader> {
+        MASK_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        vec![self.variant.into()]
+    }
 
-        Some(((*settings).into(), settings.variant))
+    /// CRT masks cover the UI as well, like the screen of a television would.
+    fn stage(&self) -> PostProcessingStage {
+        match self.variant {
+            MaskVariant::Crt => PostProcessingStage::PostUi,
+            MaskVariant::Square | MaskVariant::Vignette => PostProcessingStage::PreTonemap,
+        }
     }
 }
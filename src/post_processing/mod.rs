@@ -1,7 +1,13 @@
 use std::{marker::PhantomData, sync::Mutex};
 
+use effect_mask::{EffectMask, EffectMasking, Masked};
+
 use bevy::{
-    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    asset::load_internal_asset,
+    core_pipeline::{
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+        prepass::{DepthPrepass, ViewPrepassTextures},
+    },
     ecs::{
         query::ROQueryItem,
         system::{
@@ -10,32 +16,38 @@ use bevy::{
         },
     },
     prelude::*,
+    reflect::TypeUuid,
     render::{
         camera::ExtractedCamera,
-        extract_component::DynamicUniformIndex,
+        extract_component::{ComponentUniforms, DynamicUniformIndex, UniformComponentPlugin},
         globals::{GlobalsBuffer, GlobalsUniform},
         render_graph::{Node, NodeRunError, RenderGraphContext, SlotInfo, SlotType},
         render_phase::{
-            sort_phase_system, CachedRenderPipelinePhaseItem, DrawFunctionId, DrawFunctions,
-            PhaseItem, RenderCommand, RenderCommandResult, RenderPhase, SetItemPipeline,
-            TrackedRenderPass,
+            sort_phase_system, AddRenderCommand, CachedRenderPipelinePhaseItem, DrawFunctionId,
+            DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult, RenderPhase,
+            SetItemPipeline, TrackedRenderPass,
         },
         render_resource::{
-            BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
-            BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType,
-            BufferBindingType, CachedRenderPipelineId, FilterMode, FragmentState, MultisampleState,
-            Operations, PipelineCache, PrimitiveState, RenderPassColorAttachment,
-            RenderPassDescriptor, RenderPipelineDescriptor, SamplerBindingType, SamplerDescriptor,
-            ShaderDefVal, ShaderStages, ShaderType, TextureFormat, TextureSampleType,
-            TextureViewDimension, TextureViewId,
+            encase::internal::WriteInto, BindGroup, BindGroupDescriptor, BindGroupEntry,
+            BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource,
+            BindingType, BufferBindingType, CachedRenderPipelineId, FilterMode, FragmentState,
+            LoadOp, MultisampleState, Operations, PipelineCache, PrimitiveState,
+            RenderPassColorAttachment, RenderPassDescriptor, RenderPipelineDescriptor, Sampler,
+            SamplerBindingType, SamplerDescriptor, ShaderDefVal, ShaderStages, ShaderType,
+            SpecializedRenderPipeline, SpecializedRenderPipelines, TextureFormat,
+            TextureSampleType, TextureView, TextureViewDimension, TextureViewId,
         },
         renderer::{RenderContext, RenderDevice},
         texture::BevyDefault,
-        view::{ExtractedView, ViewTarget},
+        view::{ExtractedView, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
         Extract, RenderApp, RenderSet,
     },
-    utils::{FloatOrd, HashMap},
+    utils::{FloatOrd, HashMap, HashSet},
 };
+use serde::{Deserialize, Serialize};
+
+/// Bloom
+pub mod bloom;
 
 /// Blur
 pub mod blur;
@@ -43,24 +55,64 @@ pub mod blur;
 /// Chromatic Aberration
 pub mod chromatic_aberration;
 
+/// Color grading
+pub mod color_grade;
+
+/// CRT
+pub mod crt;
+
+/// Depth fog
+pub mod depth_fog;
+
+/// Depth of field
+pub mod depth_of_field;
+
+/// Limiting effects to parts of the view
+pub mod effect_mask;
+
+/// Film grain
+pub mod film_grain;
+
 /// Flip
 pub mod flip;
 
+/// Lens distortion
+pub mod lens_distortion;
+
 /// LUT
 pub mod lut;
 
 /// Masks
 pub mod masks;
 
+/// Palette quantization and dithering
+pub mod palette;
+
 /// Pixelate
 pub mod pixelate;
 
+/// Presets of effects, loaded from files
+pub mod preset;
+
 /// Raindrops
 pub mod raindrops;
 
+/// Shaking cameras
+pub mod screen_shake;
+
+/// Transitions
+pub mod transition;
+
+/// Animating the parameters of effects
+pub mod tween;
+
 /// Wave
 pub mod wave;
 
+/// Shader functions for reading the depth prepass, importable as `bevy_vfx_bag::depth`.
+const DEPTH_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 2866134072996053486);
+
 #[derive(Resource)]
 pub(crate) struct UniformBindGroup<U: ShaderType> {
     pub inner: Option<BindGroup>,
@@ -90,6 +142,9 @@ pub trait PostProcessingOrder: Sized {
     /// as well as the order as a bundle.
     /// This is therefore meant as a helper when inserting the effect component.
     fn order(self, order: f32) -> (Self, Order<Self>);
+
+    /// Like [`PostProcessingOrder::order`], but also moves the effect to the given stage.
+    fn order_in(self, stage: PostProcessingStage, order: f32) -> (Self, Order<Self>);
 }
 
 impl<U> PostProcessingOrder for U
@@ -99,6 +154,10 @@ where
     fn order(self, order: f32) -> (Self, Order<Self>) {
         (self, Order::new(order))
     }
+
+    fn order_in(self, stage: PostProcessingStage, order: f32) -> (Self, Order<Self>) {
+        (self, Order::new(order).in_stage(stage))
+    }
 }
 
 struct SetEffectBindGroup<U: Component + ShaderType, const I: usize>(PhantomData<U>);
@@ -144,6 +203,14 @@ type DrawPostProcessingEffect<U> = (
     DrawPostProcessing,
 );
 
+/// Like [`DrawPostProcessingEffect`], but the shared bind group also has the depth prepass and the view.
+type DrawDepthPostProcessingEffect<U> = (
+    SetItemPipeline,
+    SetTextureSamplerGlobalsDepth<0>,
+    SetEffectBindGroup<U, 1>,
+    DrawPostProcessing,
+);
+
 pub(crate) fn create_layout(
     world: &mut World,
     label: &str,
@@ -162,7 +229,7 @@ pub(crate) fn render_pipeline_descriptor(
     shared_layout: &BindGroupLayout,
     uniform_layout: &BindGroupLayout,
     shader: Handle<Shader>,
-    shader_defs: Vec<ShaderDefVal>,
+    key: PostProcessingPipelineKey,
 ) -> RenderPipelineDescriptor {
     RenderPipelineDescriptor {
         label: Some(format!("{label} Render Pipeline").into()),
@@ -173,55 +240,410 @@ pub(crate) fn render_pipeline_descriptor(
         multisample: MultisampleState::default(),
         fragment: Some(FragmentState {
             shader,
-            shader_defs,
+            shader_defs: key.shader_defs,
             entry_point: "fragment".into(),
-
-            #[cfg(feature = "sdr")]
-            targets: vec![Some(TextureFormat::bevy_default().into())],
-
-            #[cfg(feature = "hdr")]
-            targets: vec![Some(ViewTarget::TEXTURE_FORMAT_HDR.into())],
+            targets: vec![Some(key.target_format.into())],
         }),
         push_constant_ranges: vec![],
     }
 }
 
-pub(crate) fn create_pipeline(
-    world: &mut World,
-    label: &str,
-    uniform_layout: &BindGroupLayout,
-    shader: Handle<Shader>,
-    shader_definitions: Vec<ShaderDefVal>,
-) -> CachedRenderPipelineId {
-    let shared_layout = &world.resource::<PostProcessingSharedLayout>().shared_layout;
+/// A post processing effect.
+///
+/// Implementing this for a component and adding a [`PostProcessingEffectPlugin`] for it
+/// makes the component act like any of the effects bundled in this crate:
+/// Adding it to a camera applies the effect, removing it disables the effect,
+/// and [`Order`] can be used to place it relative to other effects.
+/// To use the effect several times on the same camera, see [`EffectStack`].
+///
+/// The fragment shader of the effect has access to the same bind groups as the bundled effects:
+///
+/// ```wgsl
+/// @group(0) @binding(0)
+/// var source: texture_2d<f32>;
+/// @group(0) @binding(1)
+/// var source_sampler: sampler;
+/// @group(0) @binding(2)
+/// var<uniform> globals: Globals;
+///
+/// @group(1) @binding(0)
+/// var<uniform> settings: MyEffect;
+/// ```
+///
+/// Effects setting [`PostProcessingEffect::DEPTH`] also have the depth prepass and the view:
+///
+/// ```wgsl
+/// #import bevy_vfx_bag::depth
+///
+/// // The distance along the view direction to what is seen at the given UV.
+/// let depth = view_depth(uv);
+/// ```
+///
+/// See the `custom-effect` example for a complete effect defined outside of this crate.
+pub trait PostProcessingEffect: Component + Clone {
+    /// The uniform made available to the shader at `@group(1) @binding(0)`.
+    ///
+    /// Effects which are plain data may use `Self`.
+    type Uniform: Component + ShaderType + WriteInto + Clone;
+
+    /// Used to label the pipeline and bind groups of the effect.
+    const LABEL: &'static str;
+
+    /// Whether the effect reads the depth prepass, via the `bevy_vfx_bag::depth` shader import.
+    ///
+    /// Such effects only run on cameras with a [`DepthPrepass`], and are skipped on other cameras.
+    const DEPTH: bool = false;
+
+    /// The fragment shader of the effect.
+    /// The entry point must be called `fragment`.
+    fn shader() -> Handle<Shader>;
+
+    /// The uniform representation of this effect.
+    fn uniform(&self) -> Self::Uniform;
+
+    /// Shader definitions the pipeline of this effect should be specialized with.
+    ///
+    /// A separate pipeline is created for each unique set of definitions.
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        vec![]
+    }
 
-    let pipeline_cache = world.resource::<PipelineCache>();
+    /// How many full screen passes the effect needs.
+    ///
+    /// Each pass reads the output of the previous pass.
+    /// The shader is specialized with the `PASS` shader definition set to the index of the pass,
+    /// starting at zero, such that passes may be told apart via for example `#if PASS == 0`.
+    fn passes(&self) -> u32 {
+        1
+    }
 
-    pipeline_cache.queue_render_pipeline(render_pipeline_descriptor(
-        label,
-        shared_layout,
-        uniform_layout,
-        shader,
-        shader_definitions,
-    ))
+    /// How much of the output of the effect is used, mixed with its input.
+    ///
+    /// Passes after the first only read the output of the previous pass,
+    /// so effects with several passes can not mix their output with their input in the shader.
+    /// Anything but `1.0` saves the input of the effect for this, like an [`EffectMask`] does.
+    fn amount(&self) -> f32 {
+        1.0
+    }
+
+    /// Where in the render graph the effect runs, unless its [`Order`] says otherwise.
+    fn stage(&self) -> PostProcessingStage {
+        PostProcessingStage::PreTonemap
+    }
 }
 
-pub(crate) fn create_layout_and_pipeline(
-    world: &mut World,
-    label: &str,
-    layout_entries: &[BindGroupLayoutEntry],
-    shader: Handle<Shader>,
-) -> (BindGroupLayout, CachedRenderPipelineId) {
-    let uniform_layout = create_layout(world, label, layout_entries);
-    let pipeline_id = create_pipeline(world, label, &uniform_layout, shader, vec![]);
+/// Adds the render world systems and resources needed for a [`PostProcessingEffect`].
+///
+/// The [`BevyVfxBagPlugin`](crate::BevyVfxBagPlugin) must be added before this plugin.
+/// Loading the shader returned by [`PostProcessingEffect::shader`] is up to the user.
+pub struct PostProcessingEffectPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for PostProcessingEffectPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: PostProcessingEffect> Plugin for PostProcessingEffectPlugin<T> {
+    fn build(&self, app: &mut App) {
+        // This puts the uniform into the render world.
+        app.add_plugin(UniformComponentPlugin::<T::Uniform>::default());
+
+        render_app(app)
+            .add_system(extract_post_processing_effect::<T>.in_schedule(ExtractSchedule))
+            .add_system(extract_post_processing_effect_stack::<T>.in_schedule(ExtractSchedule))
+            .init_resource::<PostProcessingEffectData<T>>()
+            .init_resource::<UniformBindGroup<T::Uniform>>()
+            .init_resource::<SpecializedRenderPipelines<PostProcessingEffectData<T>>>()
+            .add_system(prepare_post_processing_effect::<T>.in_set(RenderSet::Prepare))
+            .add_system(queue_post_processing_effect::<T>.in_set(RenderSet::Queue))
+            .add_render_command::<PostProcessingPhaseItem, DrawPostProcessingEffect<T::Uniform>>()
+            .add_render_command::<PostProcessingPhaseItem, DrawDepthPostProcessingEffect<T::Uniform>>();
+    }
+}
+
+/// The key used to specialize post processing pipelines.
+///
+/// Views may or may not be HDR, so pipelines are specialized on the format of the texture they render to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PostProcessingPipelineKey {
+    pub(crate) shader_defs: Vec<ShaderDefVal>,
+    pub(crate) target_format: TextureFormat,
+    pub(crate) depth: Option<DepthBinding>,
+}
+
+/// How the depth prepass is bound for effects reading depth.
+///
+/// The prepass texture is multisampled when MSAA is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum DepthBinding {
+    Single,
+    Multisampled,
+}
+
+impl From<&Msaa> for DepthBinding {
+    fn from(msaa: &Msaa) -> Self {
+        if msaa.samples() > 1 {
+            Self::Multisampled
+        } else {
+            Self::Single
+        }
+    }
+}
+
+impl PostProcessingPipelineKey {
+    /// A key for rendering to the view target of the given view.
+    pub(crate) fn for_view(view: &ExtractedView, shader_defs: Vec<ShaderDefVal>) -> Self {
+        let target_format = if view.hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+
+        Self {
+            shader_defs,
+            target_format,
+            depth: None,
+        }
+    }
+
+    /// Binds the depth prepass and the view in the shared bind group, and returns the key.
+    pub(crate) fn with_depth(mut self, depth: DepthBinding) -> Self {
+        self.shader_defs.push("DEPTH_PREPASS".into());
+        if depth == DepthBinding::Multisampled {
+            self.shader_defs.push("MULTISAMPLED".into());
+        }
+        self.depth = Some(depth);
+        self
+    }
+}
+
+#[derive(Resource)]
+pub(crate) struct PostProcessingEffectData<T> {
+    shared_layout: PostProcessingSharedLayout,
+    uniform_layout: BindGroupLayout,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: PostProcessingEffect> FromWorld for PostProcessingEffectData<T> {
+    fn from_world(world: &mut World) -> Self {
+        let uniform_layout = create_layout(
+            world,
+            T::LABEL,
+            &[BindGroupLayoutEntry {
+                binding: 0,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: Some(T::Uniform::min_size()),
+                },
+                visibility: ShaderStages::FRAGMENT,
+                count: None,
+            }],
+        );
+
+        let shared_layout = world.resource::<PostProcessingSharedLayout>().clone();
+
+        Self {
+            shared_layout,
+            uniform_layout,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: PostProcessingEffect> SpecializedRenderPipeline for PostProcessingEffectData<T> {
+    type Key = PostProcessingPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        render_pipeline_descriptor(
+            T::LABEL,
+            self.shared_layout.layout(key.depth),
+            &self.uniform_layout,
+            T::shader(),
+            key,
+        )
+    }
+}
+
+/// Extracts the effect `T` as well as its uniform from active cameras.
+///
+/// See [`extract_post_processing_camera_phases`] for how the camera is prepared for post processing.
+#[allow(clippy::type_complexity)]
+fn extract_post_processing_effect<T: PostProcessingEffect>(
+    mut commands: Commands,
+    cameras: Extract<Query<(Entity, &Camera, &T, Option<&Order<T>>, Option<&Masked<T>>)>>,
+) {
+    for (entity, camera, effect, maybe_ordering, maybe_masked) in &cameras {
+        if camera.is_active {
+            let ordering = if let Some(o) = maybe_ordering {
+                o.clone()
+            } else {
+                Order::new(0.0)
+            };
+
+            let mut entity_commands = commands.get_or_spawn(entity);
+            // The uniform is inserted on its own, since it may be the effect itself.
+            entity_commands
+                .insert((
+                    effect.clone(),
+                    RenderPhase::<PostProcessingPhaseItem>::default(),
+                    ordering,
+                    PostProcessingCamera,
+                ))
+                .insert(effect.uniform());
+
+            if let Some(masked) = maybe_masked {
+                entity_commands.insert(masked.clone());
+            }
+        }
+    }
+}
+
+/// Extracts each enabled instance in an [`EffectStack`] of active cameras.
+///
+/// Each instance gets its own entity in the render world, such that it has its own uniform and ordering.
+fn extract_post_processing_effect_stack<T: PostProcessingEffect>(
+    mut commands: Commands,
+    cameras: Extract<Query<(Entity, &Camera, &EffectStack<T>)>>,
+) {
+    for (entity, camera, stack) in &cameras {
+        if camera.is_active {
+            commands.get_or_spawn(entity).insert((
+                RenderPhase::<PostProcessingPhaseItem>::default(),
+                PostProcessingCamera,
+            ));
+
+            for instance in stack.instances.iter().filter(|instance| instance.enabled) {
+                // The uniform is inserted on its own, since it may be the effect itself.
+                let mut instance_commands = commands.spawn((
+                    instance.effect.clone(),
+                    instance.order::<T>(),
+                    EffectInstanceOf(entity),
+                ));
+                instance_commands.insert(instance.effect.uniform());
+
+                if let Some(mask) = &instance.mask {
+                    instance_commands.insert(Masked::<T>::new(mask.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// Points to the camera (view) an extracted [`EffectStack`] instance belongs to.
+#[derive(Debug, Component)]
+struct EffectInstanceOf(Entity);
+
+#[allow(clippy::type_complexity)]
+fn prepare_post_processing_effect<T: PostProcessingEffect>(
+    data: Res<PostProcessingEffectData<T>>,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<PostProcessingEffectData<T>>>,
+    mut masking: EffectMasking,
+    msaa: Res<Msaa>,
+    mut views: Query<(
+        &mut RenderPhase<PostProcessingPhaseItem>,
+        &ExtractedView,
+        Option<&DepthPrepass>,
+    )>,
+    effects: Query<(
+        Entity,
+        &T,
+        &Order<T>,
+        Option<&Masked<T>>,
+        Option<&EffectInstanceOf>,
+    )>,
+    draw_functions: Res<DrawFunctions<PostProcessingPhaseItem>>,
+) {
+    for (entity, effect, order, maybe_masked, maybe_instance_of) in effects.iter() {
+        // Effects added directly to a camera live on the view entity,
+        // while effect stack instances point to it.
+        let view_entity = maybe_instance_of.map_or(entity, |instance_of| instance_of.0);
+
+        let Ok((mut phase, view, depth_prepass)) = views.get_mut(view_entity) else {
+            continue;
+        };
+
+        if T::DEPTH && depth_prepass.is_none() {
+            continue;
+        }
+
+        let draw_function = if T::DEPTH {
+            draw_functions
+                .read()
+                .id::<DrawDepthPostProcessingEffect<T::Uniform>>()
+        } else {
+            draw_functions
+                .read()
+                .id::<DrawPostProcessingEffect<T::Uniform>>()
+        };
+
+        // The passes share a sort key, see the phase item sorting.
+        let sort_key = order.sort_key(effect.stage());
+
+        let items: Vec<_> = (0..effect.passes())
+            .map(|pass| {
+                let mut shader_defs = effect.shader_defs();
+                shader_defs.push(ShaderDefVal::UInt("PASS".into(), pass));
+
+                let mut key = PostProcessingPipelineKey::for_view(view, shader_defs);
+                if T::DEPTH {
+                    key = key.with_depth(DepthBinding::from(&*msaa));
+                }
+                let pipeline_id = pipelines.specialize(&pipeline_cache, &data, key);
+
+                PostProcessingPhaseItem {
+                    entity,
+                    sort_key,
+                    draw_function,
+                    pipeline_id,
+                    target: PostProcessingTarget::ViewTarget,
+                }
+            })
+            .collect();
+
+        masking.add_mixed_items(
+            &mut phase,
+            view_entity,
+            sort_key,
+            maybe_masked.map(|masked| &masked.mask),
+            effect.amount(),
+            items,
+        );
+    }
+}
 
-    (uniform_layout, pipeline_id)
+fn queue_post_processing_effect<T: PostProcessingEffect>(
+    render_device: Res<RenderDevice>,
+    data: Res<PostProcessingEffectData<T>>,
+    mut bind_group: ResMut<UniformBindGroup<T::Uniform>>,
+    uniforms: Res<ComponentUniforms<T::Uniform>>,
+    views: Query<Entity, With<T::Uniform>>,
+) {
+    bind_group.inner = None;
+
+    if let Some(uniforms) = uniforms.binding() {
+        if !views.is_empty() {
+            bind_group.inner = Some(render_device.create_bind_group(&BindGroupDescriptor {
+                label: Some(&format!("{} Uniform Bind Group", T::LABEL)),
+                layout: &data.uniform_layout,
+                entries: &[BindGroupEntry {
+                    binding: 0,
+                    resource: uniforms.clone(),
+                }],
+            }));
+        }
+    }
 }
 
 /// Bind groups.
 #[derive(Resource, Default, Debug)]
 struct PostProcessingSharedBindGroups {
     cached_texture_bind_groups: HashMap<TextureViewId, BindGroup>,
+    /// Bind groups which also have the depth prepass and the view, by the source texture and the depth texture.
+    /// These are made each frame, since the buffer of the view uniforms may change.
+    depth_bind_groups: HashMap<(TextureViewId, TextureViewId), BindGroup>,
     current_source_texture: Mutex<Option<TextureViewId>>,
 }
 
@@ -261,6 +683,54 @@ impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetTextureSamplerGlobals
     }
 }
 
+/// Like [`SetTextureSamplerGlobals`], but also sets the depth prepass and the view of the view being drawn.
+struct SetTextureSamplerGlobalsDepth<const I: usize>;
+
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetTextureSamplerGlobalsDepth<I> {
+    type ViewWorldQuery = (
+        Option<Read<ViewPrepassTextures>>,
+        Option<Read<ViewUniformOffset>>,
+    );
+    type ItemWorldQuery = ();
+    type Param = SRes<PostProcessingSharedBindGroups>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        (prepass_textures, view_uniform_offset): ROQueryItem<'w, Self::ViewWorldQuery>,
+        _entity: (),
+        bind_groups: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let (Some(depth), Some(view_uniform_offset)) = (
+            prepass_textures.and_then(|textures| textures.depth.as_ref()),
+            view_uniform_offset,
+        ) else {
+            return RenderCommandResult::Failure;
+        };
+
+        let id = {
+            let lock = bind_groups
+                .current_source_texture
+                .try_lock()
+                .expect("Mutex should be available");
+            *lock.as_ref().expect("Source view id should be set")
+        };
+
+        let bind_groups = bind_groups.into_inner();
+
+        if let Some(bind_group) = bind_groups
+            .depth_bind_groups
+            .get(&(id, depth.default_view.id()))
+        {
+            pass.set_bind_group(I, bind_group, &[view_uniform_offset.offset]);
+            RenderCommandResult::Success
+        } else {
+            RenderCommandResult::Failure
+        }
+    }
+}
+
 /// Render command for drawing the full screen triangle.
 struct DrawPostProcessing;
 
@@ -290,14 +760,63 @@ struct PostProcessingCamera;
 fn queue_post_processing_shared_bind_groups(
     render_device: Res<RenderDevice>,
     globals: Res<GlobalsBuffer>,
+    view_uniforms: Res<ViewUniforms>,
+    msaa: Res<Msaa>,
     layout: Res<PostProcessingSharedLayout>,
     mut bind_groups: ResMut<PostProcessingSharedBindGroups>,
 
-    views: Query<(Entity, &ViewTarget), With<PostProcessingCamera>>,
+    views: Query<(Entity, &ViewTarget, Option<&ViewPrepassTextures>), With<PostProcessingCamera>>,
 ) {
-    for (_, view_target) in &views {
+    bind_groups.depth_bind_groups.clear();
+
+    let mut used_texture_views = HashSet::new();
+
+    for (_, view_target, prepass_textures) in &views {
+        if let (Some(depth), Some(view_binding)) = (
+            prepass_textures.and_then(|textures| textures.depth.as_ref()),
+            view_uniforms.uniforms.binding(),
+        ) {
+            for texture_view in [view_target.main_texture(), view_target.main_texture_other()] {
+                let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+                    label: Some("PostProcessing depth bind group"),
+                    layout: layout.layout(Some(DepthBinding::from(&*msaa))),
+                    entries: &[
+                        BindGroupEntry {
+                            binding: 0,
+                            resource: BindingResource::TextureView(texture_view),
+                        },
+                        BindGroupEntry {
+                            binding: 1,
+                            resource: BindingResource::Sampler(&layout.sampler),
+                        },
+                        BindGroupEntry {
+                            binding: 2,
+                            resource: globals
+                                .buffer
+                                .binding()
+                                .expect("Globals buffer should be available"),
+                        },
+                        BindGroupEntry {
+                            binding: 3,
+                            resource: BindingResource::TextureView(&depth.default_view),
+                        },
+                        BindGroupEntry {
+                            binding: 4,
+                            resource: view_binding.clone(),
+                        },
+                    ],
+                });
+
+                bind_groups
+                    .depth_bind_groups
+                    .insert((texture_view.id(), depth.default_view.id()), bind_group);
+            }
+        }
+
         for texture_view in [view_target.main_texture(), view_target.main_texture_other()] {
             let id = &texture_view.id();
+            used_texture_views.insert(*id);
+
             if !bind_groups.cached_texture_bind_groups.contains_key(id) {
                 bind_groups.cached_texture_bind_groups.insert(
                     *id,
@@ -311,15 +830,7 @@ fn queue_post_processing_shared_bind_groups(
                             },
                             BindGroupEntry {
                                 binding: 1,
-                                resource: BindingResource::Sampler(&render_device.create_sampler(
-                                    &SamplerDescriptor {
-                                        label: Some("PostProcessing texture sampler"),
-                                        mag_filter: FilterMode::Linear,
-                                        min_filter: FilterMode::Linear,
-                                        mipmap_filter: FilterMode::Linear,
-                                        ..default()
-                                    },
-                                )),
+                                resource: BindingResource::Sampler(&layout.sampler),
                             },
                             BindGroupEntry {
                                 binding: 2,
@@ -334,6 +845,11 @@ fn queue_post_processing_shared_bind_groups(
             }
         }
     }
+
+    // View targets are remade when for example a window is resized, which leaves their old views unused.
+    bind_groups
+        .cached_texture_bind_groups
+        .retain(|id, _| used_texture_views.contains(id));
 }
 
 /// A post processing phase item.
@@ -342,13 +858,41 @@ fn queue_post_processing_shared_bind_groups(
 /// having a bind group specialized for the material.
 struct PostProcessingPhaseItem {
     entity: Entity,
-    sort_key: FloatOrd,
+    sort_key: PostProcessingSortKey,
     draw_function: DrawFunctionId,
     pipeline_id: CachedRenderPipelineId,
+    target: PostProcessingTarget,
+}
+
+/// Where a post processing phase item renders to.
+#[derive(Clone)]
+enum PostProcessingTarget {
+    /// Reads the main texture of the view target and writes to the other post process texture,
+    /// which then becomes the main texture.
+    ViewTarget,
+
+    /// Reads the main texture of the view target and writes to an intermediate texture.
+    /// The view target is left as is.
+    ///
+    /// The existing contents of the texture are loaded, which allows additive blending.
+    /// Passes which do not blend must therefore write every texel.
+    Intermediate(TextureView),
+
+    /// Like [`PostProcessingTarget::Intermediate`], but the texture is the size of the view target,
+    /// such as the saved input of a masked effect.
+    /// The viewport of the camera applies like it does to the view target.
+    ViewSized(TextureView),
+}
+
+/// Sorts post processing phase items by the stage they run in, and then by their order within the stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct PostProcessingSortKey {
+    stage: PostProcessingStage,
+    order: FloatOrd,
 }
 
 impl PhaseItem for PostProcessingPhaseItem {
-    type SortKey = FloatOrd;
+    type SortKey = PostProcessingSortKey;
 
     #[inline]
     fn sort_key(&self) -> Self::SortKey {
@@ -363,6 +907,13 @@ impl PhaseItem for PostProcessingPhaseItem {
     fn entity(&self) -> Entity {
         self.entity
     }
+
+    #[inline]
+    fn sort(items: &mut [Self]) {
+        // A stable sort is needed, since effects with several passes
+        // add one item per pass using the same sort key.
+        items.sort_by_key(|item| item.sort_key());
+    }
 }
 
 impl CachedRenderPipelinePhaseItem for PostProcessingPhaseItem {
@@ -373,9 +924,26 @@ impl CachedRenderPipelinePhaseItem for PostProcessingPhaseItem {
 
 /// The bind group layout common to post processing effects.
 /// This includes the texture and sampler bind group entries and the globals uniform.
+///
+/// Effects reading depth use a layout which also has the depth prepass and the view uniform.
 #[derive(Debug, Resource, Clone)]
 struct PostProcessingSharedLayout {
     pub(crate) shared_layout: BindGroupLayout,
+    depth_layout: BindGroupLayout,
+    depth_multisampled_layout: BindGroupLayout,
+    /// The sampler of all shared bind groups.
+    sampler: Sampler,
+}
+
+impl PostProcessingSharedLayout {
+    /// The layout for effects reading depth as given, or not at all.
+    fn layout(&self, depth: Option<DepthBinding>) -> &BindGroupLayout {
+        match depth {
+            None => &self.shared_layout,
+            Some(DepthBinding::Single) => &self.depth_layout,
+            Some(DepthBinding::Multisampled) => &self.depth_multisampled_layout,
+        }
+    }
 }
 
 impl FromWorld for PostProcessingSharedLayout {
@@ -384,40 +952,82 @@ impl FromWorld for PostProcessingSharedLayout {
             .get_resource::<RenderDevice>()
             .expect("RenderDevice should be available");
 
+        let shared_entries = [
+            BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: true },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 1,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 2,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: Some(GlobalsUniform::min_size()),
+                },
+                count: None,
+            },
+        ];
+
         let textures_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("PostProcessing texture bind group layout"),
-            entries: &[
+            entries: &shared_entries,
+        });
+
+        let depth_layout = |multisampled| {
+            let depth_entries = [
                 BindGroupLayoutEntry {
-                    binding: 0,
+                    binding: 3,
                     visibility: ShaderStages::FRAGMENT,
+                    // Bound as a float texture, since the OpenGL backend cannot load from depth textures.
                     ty: BindingType::Texture {
-                        sample_type: TextureSampleType::Float { filterable: true },
+                        sample_type: TextureSampleType::Float { filterable: false },
                         view_dimension: TextureViewDimension::D2,
-                        multisampled: false,
+                        multisampled,
                     },
                     count: None,
                 },
                 BindGroupLayoutEntry {
-                    binding: 1,
-                    visibility: ShaderStages::FRAGMENT,
-                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
-                    count: None,
-                },
-                BindGroupLayoutEntry {
-                    binding: 2,
+                    binding: 4,
                     visibility: ShaderStages::FRAGMENT,
                     ty: BindingType::Buffer {
                         ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: Some(GlobalsUniform::min_size()),
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(ViewUniform::min_size()),
                     },
                     count: None,
                 },
-            ],
-        });
+            ];
+
+            render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("PostProcessing depth bind group layout"),
+                entries: &[&shared_entries[..], &depth_entries[..]].concat(),
+            })
+        };
 
         Self {
             shared_layout: textures_layout,
+            depth_layout: depth_layout(false),
+            depth_multisampled_layout: depth_layout(true),
+            sampler: render_device.create_sampler(&SamplerDescriptor {
+                label: Some("PostProcessing sampler"),
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                mipmap_filter: FilterMode::Linear,
+                ..default()
+            }),
         }
     }
 }
@@ -428,13 +1038,15 @@ impl FromWorld for PostProcessingSharedLayout {
 /// A `Order<C>` component can be added to the camera to control the ordering of the effect.
 /// Else a default is inserted.
 ///
+/// A `Masked<C>` component on the camera is extracted as well.
+///
 /// A `PostProcessingCamera` component is added in order to identify cameras that have any effect applied.
 #[allow(clippy::type_complexity)]
 pub(crate) fn extract_post_processing_camera_phases<C: Component>(
     mut commands: Commands,
-    cameras: Extract<Query<(Entity, &Camera, Option<&Order<C>>), With<C>>>,
+    cameras: Extract<Query<(Entity, &Camera, Option<&Order<C>>, Option<&Masked<C>>), With<C>>>,
 ) {
-    for (entity, camera, maybe_ordering) in &cameras {
+    for (entity, camera, maybe_ordering, maybe_masked) in &cameras {
         if camera.is_active {
             let ordering = if let Some(o) = maybe_ordering {
                 o.clone()
@@ -442,17 +1054,25 @@ pub(crate) fn extract_post_processing_camera_phases<C: Component>(
                 Order::new(0.0)
             };
 
-            commands.get_or_spawn(entity).insert((
+            let mut entity_commands = commands.get_or_spawn(entity);
+            entity_commands.insert((
                 RenderPhase::<PostProcessingPhaseItem>::default(),
                 ordering,
                 PostProcessingCamera,
             ));
+
+            if let Some(masked) = maybe_masked {
+                entity_commands.insert(masked.clone());
+            }
         }
     }
 }
 
 /// The post processing node.
+///
+/// There is one node per [`PostProcessingStage`], each running the phase items of its stage.
 struct PostProcessingNode {
+    stage: PostProcessingStage,
     query: QueryState<
         (
             &'static ExtractedCamera,
@@ -467,20 +1087,15 @@ impl PostProcessingNode {
     /// The slot input name.
     pub const IN_VIEW: &'static str = "view";
 
-    /// Create a a new post processing node.
-    pub fn new(world: &mut World) -> Self {
+    /// Create a a new post processing node for the given stage.
+    pub fn new(world: &mut World, stage: PostProcessingStage) -> Self {
         Self {
+            stage,
             query: QueryState::new(world),
         }
     }
 }
 
-impl FromWorld for PostProcessingNode {
-    fn from_world(world: &mut World) -> Self {
-        Self::new(world)
-    }
-}
-
 impl Node for PostProcessingNode {
     fn input(&self) -> Vec<SlotInfo> {
         vec![SlotInfo::new(Self::IN_VIEW, SlotType::Entity)]
@@ -508,10 +1123,25 @@ impl Node for PostProcessingNode {
         let mut draw_functions = draw_functions.write();
         draw_functions.prepare(world);
 
-        for (_index, item) in phase.items.iter().enumerate() {
-            let post_process = view_target.post_process_write();
-            let source = post_process.source;
-            let destination = post_process.destination;
+        for item in phase
+            .items
+            .iter()
+            .filter(|item| item.sort_key.stage == self.stage)
+        {
+            let (source, destination, load) = match &item.target {
+                PostProcessingTarget::ViewTarget => {
+                    let post_process = view_target.post_process_write();
+                    (
+                        post_process.source,
+                        post_process.destination,
+                        LoadOp::Clear(Default::default()),
+                    )
+                }
+                PostProcessingTarget::Intermediate(texture)
+                | PostProcessingTarget::ViewSized(texture) => {
+                    (view_target.main_texture(), texture, LoadOp::Load)
+                }
+            };
 
             shared_bind_groups
                 .current_source_texture
@@ -524,13 +1154,18 @@ impl Node for PostProcessingNode {
                 color_attachments: &[Some(RenderPassColorAttachment {
                     view: destination,
                     resolve_target: None,
-                    ops: Operations::default(),
+                    ops: Operations { load, store: true },
                 })],
                 depth_stencil_attachment: None,
             });
 
-            if let Some(viewport) = camera.viewport.as_ref() {
-                render_pass.set_camera_viewport(viewport);
+            // Intermediate textures are sized independently of the camera.
+            if let PostProcessingTarget::ViewTarget | PostProcessingTarget::ViewSized(_) =
+                item.target
+            {
+                if let Some(viewport) = camera.viewport.as_ref() {
+                    render_pass.set_camera_viewport(viewport);
+                }
             }
 
             draw_functions
@@ -543,6 +1178,70 @@ impl Node for PostProcessingNode {
     }
 }
 
+/// Where in the render graph of a camera post processing effects run.
+///
+/// Each stage runs its effects in their [`Order`], and all effects of a stage run before those of later stages.
+/// Effects run in the stage given by [`PostProcessingEffect::stage`] unless their [`Order`] moves them.
+/// Most effects run before tonemapping, [`Lut`](lut::Lut)s run after it,
+/// and CRT [`Mask`](masks::Mask)s and [`Transition`](transition::Transition)s cover the UI.
+///
+/// The stages after tonemapping and after the UI need the [`BevyVfxBagPlugin`](crate::BevyVfxBagPlugin)
+/// to be added after the plugins adding anti-aliasing and the UI, such as the `DefaultPlugins`.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Reflect,
+    FromReflect,
+    Serialize,
+    Deserialize,
+)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum PostProcessingStage {
+    /// After the main pass, on the colors of the scene before they are tonemapped.
+    /// On HDR cameras, colors may be brighter than one.
+    #[default]
+    PreTonemap,
+
+    /// After tonemapping and anti-aliasing, on the colors which will be displayed.
+    PostTonemap,
+
+    /// After the UI has been drawn, so the effect applies to the UI too.
+    PostUi,
+}
+
+impl PostProcessingStage {
+    /// All stages, in the order they run.
+    pub const ALL: [Self; 3] = [Self::PreTonemap, Self::PostTonemap, Self::PostUi];
+
+    /// The nodes the node of this stage runs after and before.
+    /// These have the same names in the 2D and 3D graphs.
+    fn graph_position(self) -> (&'static [&'static str], &'static [&'static str]) {
+        use bevy::core_pipeline::core_3d::graph::node;
+
+        /// Not available as a constant, since this crate does not depend on `bevy_ui`.
+        const UI_PASS: &str = "ui_pass";
+
+        match self {
+            Self::PreTonemap => (&[node::MAIN_PASS, node::BLOOM], &[node::TONEMAPPING]),
+            Self::PostTonemap => (
+                &[node::TONEMAPPING, node::FXAA],
+                &[node::END_MAIN_PASS_POST_PROCESSING],
+            ),
+            Self::PostUi => (
+                &[node::END_MAIN_PASS_POST_PROCESSING, UI_PASS],
+                &[node::UPSCALING],
+            ),
+        }
+    }
+}
+
 /// Decide on ordering for post processing effects.
 /// Lower numbers means run earlier.
 ///
@@ -550,36 +1249,179 @@ impl Node for PostProcessingNode {
 /// effect to change the order of.
 /// It's easier to use this via the [`PostProcessingOrder`] trait
 /// which adds a helper method for using this.
-#[derive(Debug, Component, Copy)]
-pub struct Order<C> {
+///
+/// The order also decides which [`PostProcessingStage`] the effect runs in, if set.
+/// Orders are only compared within a stage.
+#[derive(Debug, Component, Copy, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Order<C: 'static> {
     /// Priority
     pub order: f32,
-    marker: PhantomData<C>,
+    /// Which stage the effect runs in.
+    /// If not set, the stage is decided by the effect.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<PostProcessingStage>,
+    #[reflect(ignore)]
+    #[serde(skip)]
+    marker: PhantomData<fn() -> C>,
+}
+
+/// Effects without an order run at zero.
+impl<C: 'static> Default for Order<C> {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
 }
 
-impl<C> From<Order<C>> for FloatOrd {
+impl<C: 'static> From<Order<C>> for FloatOrd {
     fn from(ordering: Order<C>) -> Self {
         Self(ordering.order)
     }
 }
 
-impl<C> Clone for Order<C> {
+impl<C: 'static> Clone for Order<C> {
     fn clone(&self) -> Self {
         Self {
             order: self.order,
+            stage: self.stage,
             marker: self.marker,
         }
     }
 }
 
-impl<C> Order<C> {
+impl<C: 'static> Order<C> {
     /// Create a new ordering.
     pub fn new(priority: f32) -> Self {
         Self {
             order: priority,
+            stage: None,
             marker: PhantomData,
         }
     }
+
+    /// Moves the effect to the given stage, and returns the ordering.
+    pub fn in_stage(mut self, stage: PostProcessingStage) -> Self {
+        self.stage = Some(stage);
+        self
+    }
+
+    /// The sort key of the phase items of the effect, which runs in `default_stage` unless this ordering moves it.
+    pub(crate) fn sort_key(&self, default_stage: PostProcessingStage) -> PostProcessingSortKey {
+        PostProcessingSortKey {
+            stage: self.stage.unwrap_or(default_stage),
+            order: FloatOrd(self.order),
+        }
+    }
+}
+
+/// Several instances of the same effect applied to one camera.
+///
+/// Adding an effect component directly to a camera allows using it once.
+/// An effect stack allows using it any number of times, for example
+/// two [`Wave`](wave::Wave) passes with different axes, or a [`Blur`](blur::Blur) both before and after
+/// a [`Pixelate`](pixelate::Pixelate).
+///
+/// Each instance has its own order, which is compared against the orders of all other effects on the camera.
+///
+/// Works with any effect implementing [`PostProcessingEffect`].
+#[derive(Debug, Component, Clone)]
+pub struct EffectStack<T> {
+    /// The instances of the effect.
+    pub instances: Vec<EffectInstance<T>>,
+}
+
+impl<T> EffectStack<T> {
+    /// Create a new, empty effect stack.
+    pub fn new() -> Self {
+        Self { instances: vec![] }
+    }
+
+    /// Adds an instance of the effect with the given order, and returns the stack.
+    /// This is meant as a helper when inserting the effect stack.
+    pub fn with(mut self, effect: T, order: f32) -> Self {
+        self.push(effect, order);
+        self
+    }
+
+    /// Adds an instance of the effect with the given order.
+    pub fn push(&mut self, effect: T, order: f32) {
+        self.instances.push(EffectInstance::new(effect, order));
+    }
+
+    /// Adds an instance of the effect with the given order, limited by a mask, and returns the stack.
+    pub fn with_masked(mut self, effect: T, order: f32, mask: EffectMask) -> Self {
+        self.instances
+            .push(EffectInstance::new(effect, order).masked(mask));
+        self
+    }
+}
+
+impl<T> Default for EffectStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One instance of an effect within an [`EffectStack`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectInstance<T> {
+    /// The effect settings of this instance.
+    pub effect: T,
+
+    /// Decides when this instance runs. See [`Order`].
+    #[serde(default)]
+    pub order: f32,
+
+    /// Moves this instance to another stage than the one of the effect. See [`PostProcessingStage`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<PostProcessingStage>,
+
+    /// Disabled instances are skipped.
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+
+    /// Limits where this instance is applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<EffectMask>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl<T> EffectInstance<T> {
+    /// Create a new enabled effect instance.
+    pub fn new(effect: T, order: f32) -> Self {
+        Self {
+            effect,
+            order,
+            stage: None,
+            enabled: true,
+            mask: None,
+        }
+    }
+
+    /// Moves this instance to the given stage, and returns the instance.
+    pub fn in_stage(mut self, stage: PostProcessingStage) -> Self {
+        self.stage = Some(stage);
+        self
+    }
+
+    /// The ordering of this instance, as if its effect was added to a camera directly.
+    pub(crate) fn order<C: 'static>(&self) -> Order<C> {
+        Order {
+            order: self.order,
+            stage: self.stage,
+            marker: PhantomData,
+        }
+    }
+
+    /// Limits where this instance is applied, and returns the instance.
+    pub fn masked(mut self, mask: EffectMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
 }
 
 pub(crate) fn render_app(app: &mut App) -> &mut App {
@@ -587,21 +1429,38 @@ pub(crate) fn render_app(app: &mut App) -> &mut App {
         .expect("Need a render app for post processing")
 }
 
+/// Adds the render graph nodes, systems, and resources shared by all effects, and the bundled effects.
+///
+/// Must be added after the `DefaultPlugins`, or the plugins adding tonemapping, anti-aliasing, and the UI,
+/// since the nodes of the [`PostProcessingStage`]s are ordered against theirs when added.
 #[derive(Debug, Default)]
 pub(crate) struct PostProcessingPlugin;
 
 impl Plugin for PostProcessingPlugin {
     fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            DEPTH_SHADER_HANDLE,
+            concat!(env!("CARGO_MANIFEST_DIR"), "/assets/shaders/", "depth.wgsl"),
+            Shader::from_wgsl
+        );
+
         let render_app = app
             .get_sub_app_mut(RenderApp)
             .expect("Need a render app for post processing");
 
-        // All effects share this node.
-        crate::util::add_nodes::<PostProcessingNode>(
-            render_app,
-            "PostProcessing2d",
-            "PostProcessing3d",
-        );
+        // All effects share these nodes, one per stage.
+        for stage in PostProcessingStage::ALL {
+            let (after, before) = stage.graph_position();
+            crate::util::add_nodes(
+                render_app,
+                &format!("PostProcessing{stage:?}2d"),
+                &format!("PostProcessing{stage:?}3d"),
+                after,
+                before,
+                |world| PostProcessingNode::new(world, stage),
+            );
+        }
 
         render_app
             .init_resource::<DrawFunctions<PostProcessingPhaseItem>>()
@@ -611,14 +1470,27 @@ impl Plugin for PostProcessingPlugin {
             .add_system(queue_post_processing_shared_bind_groups.in_set(RenderSet::Queue))
             .add_system(sort_phase_system::<PostProcessingPhaseItem>.in_set(RenderSet::PhaseSort));
 
+        app.add_plugin(effect_mask::Plugin);
+        app.add_plugin(bloom::Plugin);
         app.add_plugin(blur::Plugin);
         app.add_plugin(chromatic_aberration::Plugin);
+        app.add_plugin(color_grade::Plugin);
+        app.add_plugin(crt::Plugin);
+        app.add_plugin(depth_fog::Plugin);
+        app.add_plugin(depth_of_field::Plugin);
+        app.add_plugin(film_grain::Plugin);
         app.add_plugin(flip::Plugin);
+        app.add_plugin(lens_distortion::Plugin);
         app.add_plugin(lut::Plugin);
         app.add_plugin(masks::Plugin);
         app.add_plugin(raindrops::Plugin);
+        app.add_plugin(palette::Plugin);
         app.add_plugin(pixelate::Plugin);
+        app.add_plugin(transition::Plugin);
         app.add_plugin(wave::Plugin);
+        app.add_plugin(preset::Plugin);
+        app.add_plugin(tween::Plugin);
+        app.add_plugin(screen_shake::ScreenShakePlugin);
     }
 }
 
@@ -0,0 +1,535 @@
+use std::{fmt::Display, sync::OnceLock};
+
+use bevy::{
+    asset::load_internal_asset,
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{ShaderDefVal, ShaderType, TextureFormat},
+    utils::HashSet,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    pixelate::Pixelate, Order, PostProcessingEffect, PostProcessingEffectPlugin,
+    PostProcessingStage,
+};
+
+const PALETTE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1518243177657431774);
+
+/// The most colors a [`Palette`] may have.
+pub const MAX_PALETTE_COLORS: usize = 256;
+
+/// The width and height of the largest threshold map, which all threshold maps are stored as.
+const THRESHOLD_MAP_SIZE: usize = 16;
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            PALETTE_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "palette.wgsl"
+            ),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<Palette>()
+            .register_type::<Order<Palette>>()
+            .register_type::<Dither>()
+            .add_system(read_palette_images)
+            .add_plugin(PostProcessingEffectPlugin::<Palette>::default());
+    }
+}
+
+/// How a [`Palette`] dithers, which mixes nearby palette colors in a pattern to show the colors in between.
+#[derive(
+    Debug, Default, Eq, PartialEq, Hash, Copy, Clone, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum Dither {
+    /// Each pixel becomes the nearest palette color, which shows as bands of color.
+    #[default]
+    None,
+
+    /// A 2x2 Bayer matrix, which is coarse but very regular.
+    Bayer2x2,
+
+    /// A 4x4 Bayer matrix.
+    Bayer4x4,
+
+    /// An 8x8 Bayer matrix, which shows many levels between palette colors.
+    Bayer8x8,
+
+    /// A 16x16 blue noise threshold map, which looks less regular than the Bayer matrices.
+    BlueNoise,
+}
+
+impl Dither {
+    /// The width and height of the threshold map, and its thresholds row by row.
+    fn threshold_map(self) -> Option<(usize, &'static [f32])> {
+        static BAYER_2X2: OnceLock<Vec<f32>> = OnceLock::new();
+        static BAYER_4X4: OnceLock<Vec<f32>> = OnceLock::new();
+        static BAYER_8X8: OnceLock<Vec<f32>> = OnceLock::new();
+        static BLUE_NOISE: OnceLock<Vec<f32>> = OnceLock::new();
+
+        let (size, map) = match self {
+            Dither::None => return None,
+            Dither::Bayer2x2 => (2, &BAYER_2X2),
+            Dither::Bayer4x4 => (4, &BAYER_4X4),
+            Dither::Bayer8x8 => (8, &BAYER_8X8),
+            Dither::BlueNoise => (THRESHOLD_MAP_SIZE, &BLUE_NOISE),
+        };
+
+        let thresholds = map.get_or_init(|| match self {
+            Dither::BlueNoise => void_and_cluster(size),
+            _ => bayer(size),
+        });
+
+        Some((size, thresholds))
+    }
+}
+
+/// Maps each pixel to the nearest color of a palette, optionally dithering between them.
+///
+/// The colors are compared as they are displayed, in sRGB.
+/// Use one of the constructors for a palette of a classic system, or make one from a palette image via [`Palette::from_image`].
+///
+/// Runs after tonemapping by default, since it picks the colors which are displayed.
+/// When pixelated by a [`Pixelate`], use [`Palette::with_blocks_of`] such that each block is dithered as a single pixel.
+/// If the anchor of the pixelation moves, for example via a [`PixelateAnchor`](super::pixelate::PixelateAnchor),
+/// the palette has to follow it.
+#[derive(Debug, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    /// The colors of the palette.
+    /// Colors after the first [`MAX_PALETTE_COLORS`] are ignored, and without any colors nothing changes.
+    pub colors: Vec<Color>,
+
+    /// An image the colors are read from, replacing `colors` once it is loaded and each time it changes.
+    /// The texels are read row by row, so a palette image is usually one texel tall.
+    ///
+    /// The image is not serialized, so presets store the colors read from it instead.
+    #[serde(skip)]
+    pub image: Option<Handle<Image>>,
+
+    /// Which [`Dither`] to use.
+    pub dither: Dither,
+
+    /// How far dithering moves colors before picking the nearest palette color.
+    /// About the distance between neighbouring palette colors works well.
+    pub dither_strength: f32,
+
+    /// The width and height of the pixels of the dithering pattern,
+    /// which should match the block dimensions of a [`Pixelate`].
+    pub block_size: Vec2,
+
+    /// Where in the view, in pixels, a corner of the dithering pattern is,
+    /// which should match the anchor of a [`Pixelate`].
+    pub anchor: Vec2,
+}
+
+impl Palette {
+    /// Creates a new palette of the given colors.
+    pub fn new(colors: impl IntoIterator<Item = Color>) -> Self {
+        Self {
+            colors: colors.into_iter().collect(),
+            image: None,
+            dither: Dither::None,
+            dither_strength: 0.2,
+            block_size: Vec2::ONE,
+            anchor: Vec2::ZERO,
+        }
+    }
+
+    /// Creates a new palette read from an image.
+    /// The colors are empty until the image is loaded.
+    pub fn from_image(image: Handle<Image>) -> Self {
+        Self {
+            image: Some(image),
+            ..Self::new([])
+        }
+    }
+
+    /// The four greens of the original Game Boy.
+    pub fn game_boy() -> Self {
+        Self {
+            dither_strength: 0.3,
+            ..Self::from_hex(&["0f380f", "306230", "8bac0f", "9bbc0f"])
+        }
+    }
+
+    /// The sixteen colors of the PICO-8 fantasy console.
+    pub fn pico8() -> Self {
+        Self {
+            dither_strength: 0.15,
+            ..Self::from_hex(&[
+                "000000", "1d2b53", "7e2553", "008751", "ab5236", "5f574f", "c2c3c7", "fff1e8",
+                "ff004d", "ffa300", "ffec27", "00e436", "29adff", "83769c", "ff77a8", "ffccaa",
+            ])
+        }
+    }
+
+    /// The sixteen colors of the IBM CGA.
+    pub fn cga() -> Self {
+        Self {
+            dither_strength: 0.25,
+            ..Self::from_hex(&[
+                "000000", "0000aa", "00aa00", "00aaaa", "aa0000", "aa00aa", "aa5500", "aaaaaa",
+                "555555", "5555ff", "55ff55", "55ffff", "ff5555", "ff55ff", "ffff55", "ffffff",
+            ])
+        }
+    }
+
+    /// Sets the dithering, and returns the palette.
+    pub fn with_dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Sets the block size of the dithering pattern to square blocks, and returns the palette.
+    pub fn with_block_size(mut self, block_size: f32) -> Self {
+        self.block_size = Vec2::splat(block_size);
+        self
+    }
+
+    /// Lines the dithering pattern up with the blocks of the given pixelation, and returns the palette.
+    pub fn with_blocks_of(mut self, pixelate: &Pixelate) -> Self {
+        self.block_size = pixelate.block_dimensions();
+        self.anchor = pixelate.anchor;
+        self
+    }
+
+    fn from_hex(colors: &[&str]) -> Self {
+        Self::new(
+            colors
+                .iter()
+                .map(|hex| Color::hex(hex).expect("Bundled palettes are valid hex colors")),
+        )
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::game_boy().with_dither(Dither::Bayer4x4)
+    }
+}
+
+impl Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Palette of {} colors, dither: {:?}, dither strength: {}, block size: {}, anchor: {}",
+            self.colors.len(),
+            self.dither,
+            self.dither_strength,
+            self.block_size,
+            self.anchor
+        )
+    }
+}
+
+/// Reads the colors of palettes from their images, when the palette or the image changes.
+fn read_palette_images(
+    mut events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    mut palettes: Query<&mut Palette>,
+) {
+    let changed: HashSet<_> = events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle.id()),
+            AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+
+    for mut palette in &mut palettes {
+        let Some(handle) = &palette.image else {
+            continue;
+        };
+
+        if !palette.is_changed() && !changed.contains(&handle.id()) {
+            continue;
+        }
+
+        let Some(image) = images.get(handle) else {
+            continue;
+        };
+
+        match image_colors(image) {
+            // Only change the palette if needed, so it is not read again next time.
+            Ok(colors) if colors != palette.colors => palette.colors = colors,
+            Ok(_) => {}
+            Err(e) => error!("Could not use image {handle:?} as a palette: {e}"),
+        }
+    }
+}
+
+/// The colors of the texels of an image, row by row.
+fn image_colors(image: &Image) -> Result<Vec<Color>, String> {
+    let texel_colors = image.data.chunks_exact(4);
+
+    let colors: Vec<_> = match image.texture_descriptor.format {
+        TextureFormat::Rgba8UnormSrgb => texel_colors
+            .map(|texel| Color::rgba_u8(texel[0], texel[1], texel[2], texel[3]))
+            .collect(),
+        TextureFormat::Rgba8Unorm => texel_colors
+            .map(|texel| {
+                let [r, g, b, a] =
+                    [texel[0], texel[1], texel[2], texel[3]].map(|c| c as f32 / 255.);
+                Color::rgba_linear(r, g, b, a)
+            })
+            .collect(),
+        other => {
+            return Err(format!(
+                "The image format {other:?} is not supported, use an 8-bit RGBA image"
+            ))
+        }
+    };
+
+    if colors.len() > MAX_PALETTE_COLORS {
+        return Err(format!(
+            "The image has {} texels, but a palette has at most {MAX_PALETTE_COLORS} colors",
+            colors.len()
+        ));
+    }
+
+    Ok(colors)
+}
+
+/// A Bayer matrix of the given size, which must be a power of two, as thresholds from zero to one.
+fn bayer(size: usize) -> Vec<f32> {
+    // Each doubling splits the cells of the previous matrix into four, which are filled in the order
+    // top left, bottom right, top right, bottom left.
+    let mut ranks = vec![0];
+    let mut n = 1;
+
+    while n < size {
+        let mut next = vec![0; 4 * n * n];
+        for (offset, bx, by) in [(0, 0, 0), (2, 1, 0), (3, 0, 1), (1, 1, 1)] {
+            for y in 0..n {
+                for x in 0..n {
+                    next[(x + bx * n) + (y + by * n) * 2 * n] = 4 * ranks[x + y * n] + offset;
+                }
+            }
+        }
+
+        ranks = next;
+        n *= 2;
+    }
+
+    thresholds(&ranks)
+}
+
+/// A blue noise threshold map of the given size, made via the void and cluster method.
+///
+/// See Robert Ulichney, "The void-and-cluster method for dither array generation", 1993.
+fn void_and_cluster(size: usize) -> Vec<f32> {
+    let count = size * size;
+
+    // Texels close to each other add a lot of energy, wrapping around the edges.
+    let energy_between = |a: usize, b: usize| {
+        let distance = |a: usize, b: usize| {
+            let d = a.abs_diff(b);
+            d.min(size - d) as f32
+        };
+        let (dx, dy) = (distance(a % size, b % size), distance(a / size, b / size));
+
+        (-(dx * dx + dy * dy) / (2.0 * 1.5 * 1.5)).exp()
+    };
+
+    #[derive(Clone)]
+    struct Pattern {
+        set: Vec<bool>,
+        energy: Vec<f32>,
+    }
+
+    impl Pattern {
+        fn toggle(&mut self, texel: usize, energy_between: impl Fn(usize, usize) -> f32) {
+            self.set[texel] = !self.set[texel];
+            let sign = if self.set[texel] { 1.0 } else { -1.0 };
+
+            for (other, energy) in self.energy.iter_mut().enumerate() {
+                *energy += sign * energy_between(texel, other);
+            }
+        }
+
+        /// The set texel with the most energy.
+        fn tightest_cluster(&self) -> usize {
+            (0..self.set.len())
+                .filter(|&texel| self.set[texel])
+                .max_by(|&a, &b| self.energy[a].total_cmp(&self.energy[b]))
+                .expect("Pattern should have a set texel")
+        }
+
+        /// The unset texel with the least energy.
+        fn largest_void(&self) -> usize {
+            (0..self.set.len())
+                .filter(|&texel| !self.set[texel])
+                .min_by(|&a, &b| self.energy[a].total_cmp(&self.energy[b]))
+                .expect("Pattern should have an unset texel")
+        }
+    }
+
+    let mut pattern = Pattern {
+        set: vec![false; count],
+        energy: vec![0.0; count],
+    };
+
+    // Start with a tenth of the texels set, picked by a simple deterministic random number generator.
+    let initial = count / 10;
+    let mut state = 0x9e37_79b9_u32;
+    let mut set = 0;
+    while set < initial {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+
+        let texel = state as usize % count;
+        if !pattern.set[texel] {
+            pattern.toggle(texel, energy_between);
+            set += 1;
+        }
+    }
+
+    // Spread the initial texels evenly, by moving the tightest cluster to the largest void until that does not change anything.
+    for _ in 0..count {
+        let cluster = pattern.tightest_cluster();
+        pattern.toggle(cluster, energy_between);
+
+        let void = pattern.largest_void();
+        pattern.toggle(void, energy_between);
+
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut ranks = vec![0; count];
+
+    // The initial texels are ranked by removing the tightest cluster first.
+    let mut removing = pattern.clone();
+    for rank in (0..initial).rev() {
+        let cluster = removing.tightest_cluster();
+        removing.toggle(cluster, energy_between);
+        ranks[cluster] = rank;
+    }
+
+    // The other texels are ranked by filling the largest void first.
+    for rank in initial..count {
+        let void = pattern.largest_void();
+        pattern.toggle(void, energy_between);
+        ranks[void] = rank;
+    }
+
+    thresholds(&ranks)
+}
+
+/// Turns ranks from zero to `n` into thresholds between zero and one, centered in their steps.
+fn thresholds(ranks: &[usize]) -> Vec<f32> {
+    ranks
+        .iter()
+        .map(|&rank| (rank as f32 + 0.5) / ranks.len() as f32)
+        .collect()
+}
+
+#[doc(hidden)]
+/// [`Palette`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct PaletteUniform {
+    /// The colors in sRGB.
+    pub(crate) colors: [Vec4; MAX_PALETTE_COLORS],
+
+    /// The thresholds of the threshold map, four per element and row by row.
+    pub(crate) thresholds: [Vec4; THRESHOLD_MAP_SIZE * THRESHOLD_MAP_SIZE / 4],
+    pub(crate) count: u32,
+    pub(crate) dither_size: u32,
+    pub(crate) dither_strength: f32,
+    pub(crate) block_size: Vec2,
+    pub(crate) anchor: Vec2,
+}
+
+#[cfg(feature = "cpu")]
+impl PaletteUniform {
+    /// The dithering threshold at the given texel of the threshold map, which wraps around.
+    pub(crate) fn threshold(&self, x: i32, y: i32) -> f32 {
+        let size = self.dither_size.max(1) as i32;
+        let index = (x.rem_euclid(size) + y.rem_euclid(size) * size) as usize;
+
+        self.thresholds[index / 4][index % 4]
+    }
+
+    /// The palette color nearest to the given sRGB color.
+    /// Mirrors `nearest` in `palette.wgsl`.
+    pub(crate) fn nearest(&self, color: Vec3) -> Vec3 {
+        self.colors[..self.count as usize]
+            .iter()
+            .map(|entry| entry.truncate())
+            .min_by(|a, b| {
+                a.distance_squared(color)
+                    .total_cmp(&b.distance_squared(color))
+            })
+            .unwrap_or(color)
+    }
+}
+
+impl From<&Palette> for PaletteUniform {
+    fn from(palette: &Palette) -> Self {
+        let mut colors = [Vec4::ZERO; MAX_PALETTE_COLORS];
+        for (uniform, color) in colors.iter_mut().zip(&palette.colors) {
+            *uniform = Vec4::from(color.as_rgba_f32());
+        }
+
+        let mut thresholds = [Vec4::ZERO; THRESHOLD_MAP_SIZE * THRESHOLD_MAP_SIZE / 4];
+        let dither_size = match palette.dither.threshold_map() {
+            Some((size, map)) => {
+                for (index, threshold) in map.iter().enumerate() {
+                    thresholds[index / 4][index % 4] = *threshold;
+                }
+                size as u32
+            }
+            None => 0,
+        };
+
+        Self {
+            colors,
+            thresholds,
+            count: palette.colors.len().min(MAX_PALETTE_COLORS) as u32,
+            dither_size,
+            dither_strength: palette.dither_strength,
+            block_size: palette.block_size.max(Vec2::ONE),
+            anchor: palette.anchor,
+        }
+    }
+}
+
+impl PostProcessingEffect for Palette {
+    type Uniform = PaletteUniform;
+
+    const LABEL: &'static str = "Palette";
+
+    fn shader() -> Handle<Shader> {
+        PALETTE_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        self.into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        match self.dither {
+            Dither::None => vec![],
+            _ => vec!["DITHER".into()],
+        }
+    }
+
+    /// Palettes pick the colors which are displayed.
+    fn stage(&self) -> PostProcessingStage {
+        PostProcessingStage::PostTonemap
+    }
+}
@@ -1,61 +1,26 @@
 use std::fmt::Display;
 
-use bevy::render::RenderSet;
-pub(crate) use bevy::{
+use bevy::{
     asset::load_internal_asset,
-    ecs::query::QueryItem,
     prelude::*,
     reflect::TypeUuid,
     render::{
-        extract_component::{
-            ComponentUniforms, ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin,
-        },
-        render_phase::{AddRenderCommand, DrawFunctions, RenderPhase},
-        render_resource::{
-            BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry,
-            BindingType, BufferBindingType, CachedRenderPipelineId, ShaderStages, ShaderType,
-        },
-        renderer::RenderDevice,
+        camera::CameraUpdateSystem,
+        render_resource::{ShaderDefVal, ShaderType},
     },
+    transform::TransformSystem,
 };
 
-use crate::post_processing::{DrawPostProcessingEffect, UniformBindGroup};
+use serde::{Deserialize, Serialize};
 
-use super::{Order, PostProcessingPhaseItem};
+use super::{Order, PostProcessingEffect, PostProcessingEffectPlugin};
 
 pub(crate) const PIXELATE_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 11093977931118718560);
 
-#[derive(Resource)]
-pub(crate) struct PixelateData {
-    pub pipeline_id: CachedRenderPipelineId,
-    pub uniform_layout: BindGroupLayout,
-}
-
-impl FromWorld for PixelateData {
-    fn from_world(world: &mut World) -> Self {
-        let (uniform_layout, pipeline_id) = super::create_layout_and_pipeline(
-            world,
-            "Pixelate",
-            &[BindGroupLayoutEntry {
-                binding: 0,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: true,
-                    min_binding_size: Some(Pixelate::min_size()),
-                },
-                visibility: ShaderStages::FRAGMENT,
-                count: None,
-            }],
-            PIXELATE_SHADER_HANDLE.typed(),
-        );
-
-        PixelateData {
-            pipeline_id,
-            uniform_layout,
-        }
-    }
-}
+/// Shader functions for pixelating, importable as `bevy_vfx_bag::pixelate`.
+const PIXELATE_FUNCTIONS_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1838606738738607133);
 
 pub(crate) struct Plugin;
 impl bevy::prelude::Plugin for Plugin {
@@ -70,101 +35,228 @@ impl bevy::prelude::Plugin for Plugin {
             ),
             Shader::from_wgsl
         );
+        // Also used by transitions.
+        load_internal_asset!(
+            app,
+            PIXELATE_FUNCTIONS_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "pixelate-functions.wgsl"
+            ),
+            Shader::from_wgsl
+        );
 
-        // This puts the uniform into the render world.
-        app.add_plugin(ExtractComponentPlugin::<Pixelate>::default())
-            .add_plugin(UniformComponentPlugin::<Pixelate>::default());
-
-        super::render_app(app)
+        app.register_type::<Pixelate>()
+            .register_type::<Order<Pixelate>>()
+            .register_type::<PixelateShape>()
+            .register_type::<PixelateSampling>()
+            .register_type::<PixelateAnchor>()
             .add_system(
-                super::extract_post_processing_camera_phases::<Pixelate>
-                    .in_schedule(ExtractSchedule),
+                anchor_pixelation
+                    .in_base_set(CoreSet::PostUpdate)
+                    .after(TransformSystem::TransformPropagate)
+                    .after(CameraUpdateSystem),
             )
-            .init_resource::<PixelateData>()
-            .init_resource::<UniformBindGroup<Pixelate>>()
-            .add_system(prepare.in_set(RenderSet::Prepare))
-            .add_system(queue.in_set(RenderSet::Queue))
-            .add_render_command::<PostProcessingPhaseItem, DrawPostProcessingEffect<Pixelate>>();
+            .add_plugin(PostProcessingEffectPlugin::<Pixelate>::default());
     }
 }
 
-fn prepare(
-    data: Res<PixelateData>,
-    mut views: Query<(
-        Entity,
-        &mut RenderPhase<PostProcessingPhaseItem>,
-        &Order<Pixelate>,
-    )>,
-    draw_functions: Res<DrawFunctions<PostProcessingPhaseItem>>,
-) {
-    for (entity, mut phase, order) in views.iter_mut() {
-        let draw_function = draw_functions
-            .read()
-            .id::<DrawPostProcessingEffect<Pixelate>>();
-
-        phase.add(PostProcessingPhaseItem {
-            entity,
-            sort_key: (*order).into(),
-            draw_function,
-            pipeline_id: data.pipeline_id,
-        });
-    }
+/// The shape of the blocks of a [`Pixelate`].
+#[derive(
+    Debug, Default, Eq, PartialEq, Hash, Copy, Clone, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum PixelateShape {
+    /// Rectangular blocks, which are square unless the aspect ratio is changed.
+    #[default]
+    Square,
+
+    /// Hexagons one block wide, in rows offset by half a block.
+    Hexagon,
+
+    /// Round dots with dark gaps between them, like the lights of an LED screen.
+    Circle,
+
+    /// Each block is split into two triangles along a diagonal, alternating between the diagonals.
+    Triangle,
 }
 
-fn queue(
-    render_device: Res<RenderDevice>,
-    data: Res<PixelateData>,
-    mut bind_group: ResMut<UniformBindGroup<Pixelate>>,
-    uniforms: Res<ComponentUniforms<Pixelate>>,
-    views: Query<Entity, With<Pixelate>>,
-) {
-    bind_group.inner = None;
-
-    if let Some(uniforms) = uniforms.binding() {
-        if !views.is_empty() {
-            bind_group.inner = Some(render_device.create_bind_group(&BindGroupDescriptor {
-                label: Some("Pixelate Uniform Bind Group"),
-                layout: &data.uniform_layout,
-                entries: &[BindGroupEntry {
-                    binding: 0,
-                    resource: uniforms.clone(),
-                }],
-            }));
+impl PixelateShape {
+    fn shader_def(self) -> Option<ShaderDefVal> {
+        match self {
+            PixelateShape::Square => None,
+            PixelateShape::Hexagon => Some("HEXAGON".into()),
+            PixelateShape::Circle => Some("CIRCLE".into()),
+            PixelateShape::Triangle => Some("TRIANGLE".into()),
         }
     }
 }
 
+/// How a [`Pixelate`] picks the color of a block.
+#[derive(
+    Debug, Default, Eq, PartialEq, Hash, Copy, Clone, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum PixelateSampling {
+    /// The texel at the center of the block, which keeps colors crisp.
+    #[default]
+    Center,
+
+    /// The average of 4x4 filtered samples spread over the block, which is smoother and flickers less in motion.
+    /// Blocks which are not squares average the rectangle of a square block around their center.
+    Average,
+}
+
 /// Pixelate settings.
-#[derive(Debug, ShaderType, Component, Clone, Copy)]
+///
+/// The blocks are laid out on a grid in the pixels of the view, starting at `anchor`.
+/// With the default anchor the grid moves along with the view,
+/// so add a [`PixelateAnchor`] to keep the blocks in place in the world while the camera moves.
+#[derive(Debug, Component, Clone, Copy, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Pixelate {
-    /// How many pixels in the width and height in a block after pixelation. One block has a constant color within it.
+    /// How many pixels tall a block is. One block has a constant color within it.
     ///
     /// The shader sets a lower bound to 1.0, since that would not change the outcome.
     pub block_size: f32,
+
+    /// The width of a block divided by its height, such that blocks are `block_size * aspect_ratio` pixels wide.
+    pub aspect_ratio: f32,
+
+    /// Which [`PixelateShape`] the blocks have.
+    pub shape: PixelateShape,
+
+    /// Which [`PixelateSampling`] picks the color of a block.
+    pub sampling: PixelateSampling,
+
+    /// A corner of a block, in pixels from the top left of the view.
+    pub anchor: Vec2,
+}
+
+impl Pixelate {
+    /// Square blocks of the given size in pixels.
+    pub fn new(block_size: f32) -> Self {
+        Self {
+            block_size,
+            ..default()
+        }
+    }
+
+    /// Blocks of the given width and height in pixels.
+    pub fn with_size(width: f32, height: f32) -> Self {
+        Self {
+            block_size: height,
+            aspect_ratio: width / height,
+            ..default()
+        }
+    }
+
+    /// Returns the pixelation with blocks of the given shape.
+    pub fn with_shape(mut self, shape: PixelateShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Returns the pixelation with blocks of the average color within them.
+    pub fn averaged(mut self) -> Self {
+        self.sampling = PixelateSampling::Average;
+        self
+    }
+
+    /// The width and height of a block in pixels, at least one pixel each.
+    pub fn block_dimensions(&self) -> Vec2 {
+        Vec2::new(self.block_size * self.aspect_ratio, self.block_size).max(Vec2::ONE)
+    }
 }
 
 impl Default for Pixelate {
     fn default() -> Self {
-        Self { block_size: 8.0 }
+        Self {
+            block_size: 8.0,
+            aspect_ratio: 1.0,
+            shape: PixelateShape::Square,
+            sampling: PixelateSampling::Center,
+            anchor: Vec2::ZERO,
+        }
     }
 }
 
 impl Display for Pixelate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Pixelate block size: {}", self.block_size)
+        write!(
+            f,
+            "Pixelate {:?} {:?}, block size: {}, aspect ratio: {}, anchor: {}",
+            self.shape, self.sampling, self.block_size, self.aspect_ratio, self.anchor
+        )
+    }
+}
+
+#[doc(hidden)]
+/// [`Pixelate`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct PixelateUniform {
+    pub(crate) block_size: Vec2,
+    pub(crate) anchor: Vec2,
+}
+
+impl From<Pixelate> for PixelateUniform {
+    fn from(pixelate: Pixelate) -> Self {
+        Self {
+            block_size: pixelate.block_dimensions(),
+            anchor: pixelate.anchor,
+        }
     }
 }
 
-impl ExtractComponent for Pixelate {
-    type Query = (&'static Self, &'static Camera);
-    type Filter = ();
-    type Out = Self;
+impl PostProcessingEffect for Pixelate {
+    type Uniform = PixelateUniform;
 
-    fn extract_component((settings, camera): QueryItem<'_, Self::Query>) -> Option<Self::Out> {
-        if !camera.is_active {
-            return None;
+    const LABEL: &'static str = "Pixelate";
+
+    fn shader() -> Handle<Shader> {
+        PIXELATE_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        let mut shader_defs: Vec<_> = self.shape.shader_def().into_iter().collect();
+        if self.sampling == PixelateSampling::Average {
+            shader_defs.push("AVERAGE".into());
         }
+        shader_defs
+    }
+}
+
+/// Keeps the blocks of the [`Pixelate`] of a camera in place in the world,
+/// by moving its `anchor` to where the given point in the world is in the view.
+///
+/// This stops blocks from flickering while a 2D or orthographic camera pans,
+/// since each block keeps covering the same part of the world.
+/// Perspective cameras only keep the blocks at the depth of the point in place.
+#[derive(Debug, Default, Component, Clone, Copy, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct PixelateAnchor(pub Vec3);
 
-        Some(*settings)
+fn anchor_pixelation(
+    mut cameras: Query<(&Camera, &GlobalTransform, &PixelateAnchor, &mut Pixelate)>,
+) {
+    for (camera, transform, anchor, mut pixelate) in &mut cameras {
+        let (Some(size), Some(ndc)) = (
+            camera.physical_viewport_size(),
+            camera.world_to_ndc(transform, anchor.0),
+        ) else {
+            continue;
+        };
+
+        // Normalized device coordinates point up, the pixels of the view point down.
+        let anchor = Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) * 0.5 * size.as_vec2();
+        if pixelate.anchor != anchor {
+            pixelate.anchor = anchor;
+        }
     }
 }
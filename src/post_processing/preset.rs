@@ -0,0 +1,382 @@
+use bevy::{
+    asset::{AssetLoader, HandleId, LoadContext, LoadedAsset},
+    ecs::system::EntityCommands,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::{get_short_name, BoxedFuture, HashSet},
+};
+use ron::{extensions::Extensions, ser::PrettyConfig, Options};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bloom::Bloom,
+    blur::Blur,
+    chromatic_aberration::ChromaticAberration,
+    color_grade::ColorGrade,
+    crt::Crt,
+    effect_mask::{EffectMask, Masked},
+    film_grain::FilmGrain,
+    flip::Flip,
+    lens_distortion::LensDistortion,
+    lut::Lut,
+    masks::Mask,
+    palette::Palette,
+    pixelate::Pixelate,
+    raindrops::Raindrops,
+    wave::Wave,
+    EffectInstance, EffectStack, Order,
+};
+
+/// A complete set of post processing effects for a camera, usually loaded from a `.vfx.ron` file.
+///
+/// Adding a `Handle<PostProcessingPreset>` to a camera inserts the effects of the preset once it is loaded.
+/// Effects which can be stacked are inserted as an [`EffectStack`] per effect,
+/// while a [`Bloom`], [`Lut`], or [`Raindrops`] is inserted as a component along with its [`Order`]
+/// and its [`Masked`] if it has a mask.
+/// These can only be used once per camera, so only the first enabled one of each is used.
+///
+/// The effects are kept in sync with the preset:
+/// Changing the handle or the preset asset, for example by editing the file while the asset server watches for changes,
+/// replaces the effects of the previous version of the preset.
+/// This also replaces any [`Bloom`], [`Lut`], or [`Raindrops`] added to the camera directly.
+/// Removing the handle removes the effects.
+///
+/// A preset file looks like this:
+///
+/// ```ron
+/// (
+///     effects: [
+///         (effect: Pixelate(block_size: 4.0)),
+///         (effect: Lut(image: Arctic, intensity: 0.8), order: 1.0),
+///         (
+///             effect: Blur(amount: 0.5),
+///             order: 2.0,
+///             mask: (shape: Circle(center: (0.5, 0.5), radius: 0.3), inverted: true),
+///         ),
+///     ],
+/// )
+/// ```
+///
+/// Settings left out use their defaults, and effects without an order run at zero.
+/// Effects may be moved to another [`PostProcessingStage`](super::PostProcessingStage) via `stage`, for example `stage: PostUi`.
+/// Optional values such as masks are not wrapped in `Some`.
+/// Image masks and palette images can not be used in presets, but LUT images can be given by path, see [`LutImage`](super::lut::LutImage).
+#[derive(Debug, Clone, Default, TypeUuid, Serialize, Deserialize)]
+#[uuid = "b32b15cf-fb65-43ca-a6e4-7051d1847c08"]
+pub struct PostProcessingPreset {
+    /// The effects, each with its order and optionally a mask.
+    pub effects: Vec<EffectInstance<PresetEffect>>,
+}
+
+/// A [`PostProcessingPreset`] used as an ordered stack of effects, which a camera keeps in sync with.
+pub type VfxStack = PostProcessingPreset;
+
+/// An effect within a [`PostProcessingPreset`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PresetEffect {
+    /// See [`Bloom`].
+    Bloom(Bloom),
+    /// See [`Blur`].
+    Blur(Blur),
+    /// See [`ChromaticAberration`].
+    ChromaticAberration(ChromaticAberration),
+    /// See [`ColorGrade`].
+    ColorGrade(ColorGrade),
+    /// See [`Crt`].
+    Crt(Crt),
+    /// See [`FilmGrain`].
+    FilmGrain(FilmGrain),
+    /// See [`Flip`].
+    Flip(Flip),
+    /// See [`LensDistortion`].
+    LensDistortion(LensDistortion),
+    /// See [`Lut`].
+    Lut(Lut),
+    /// See [`Mask`].
+    Mask(Mask),
+    /// See [`Palette`].
+    Palette(Palette),
+    /// See [`Pixelate`].
+    Pixelate(Pixelate),
+    /// See [`Raindrops`].
+    Raindrops(Raindrops),
+    /// See [`Wave`].
+    Wave(Wave),
+}
+
+macro_rules! preset_effects {
+    ($($effect:ident),*) => {
+        $(
+            impl From<$effect> for PresetEffect {
+                fn from(effect: $effect) -> Self {
+                    Self::$effect(effect)
+                }
+            }
+        )*
+    };
+}
+
+preset_effects!(
+    Bloom,
+    Blur,
+    ChromaticAberration,
+    ColorGrade,
+    Crt,
+    FilmGrain,
+    Flip,
+    LensDistortion,
+    Lut,
+    Mask,
+    Palette,
+    Pixelate,
+    Raindrops,
+    Wave
+);
+
+/// Gets the effect of the given variant out of a [`PresetEffect`].
+macro_rules! variant {
+    ($effect:ident) => {
+        |effect: &PresetEffect| match effect {
+            PresetEffect::$effect(effect) => Some(effect),
+            _ => None,
+        }
+    };
+}
+
+/// The components a preset may insert on a camera, which are removed before inserting new ones.
+///
+/// Nested, since bundles are tuples of at most 15 components.
+type PresetComponents = (
+    (
+        EffectStack<Blur>,
+        EffectStack<ChromaticAberration>,
+        EffectStack<ColorGrade>,
+        EffectStack<Crt>,
+        EffectStack<FilmGrain>,
+        EffectStack<Flip>,
+        EffectStack<LensDistortion>,
+        EffectStack<Mask>,
+        EffectStack<Palette>,
+        EffectStack<Pixelate>,
+        EffectStack<Wave>,
+    ),
+    Bloom,
+    Order<Bloom>,
+    Masked<Bloom>,
+    Lut,
+    Order<Lut>,
+    Masked<Lut>,
+    Raindrops,
+    Order<Raindrops>,
+    Masked<Raindrops>,
+);
+
+/// The RON options of `.vfx.ron` files.
+fn ron_options() -> Options {
+    Options::default()
+        .with_default_extension(Extensions::IMPLICIT_SOME)
+        .with_default_extension(Extensions::UNWRAP_VARIANT_NEWTYPES)
+}
+
+impl PostProcessingPreset {
+    /// Create a new, empty preset.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an effect with the given order, and returns the preset.
+    pub fn with(mut self, effect: impl Into<PresetEffect>, order: f32) -> Self {
+        self.effects.push(EffectInstance::new(effect.into(), order));
+        self
+    }
+
+    /// Adds an effect with the given order, limited by a mask, and returns the preset.
+    pub fn with_masked(
+        mut self,
+        effect: impl Into<PresetEffect>,
+        order: f32,
+        mask: EffectMask,
+    ) -> Self {
+        self.effects
+            .push(EffectInstance::new(effect.into(), order).masked(mask));
+        self
+    }
+
+    /// Parses a preset in the format of `.vfx.ron` files.
+    ///
+    /// Unlike when loaded as an asset, images of LUTs given by path are not loaded.
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron_options().from_str(ron)
+    }
+
+    /// Writes the preset in the format of `.vfx.ron` files.
+    ///
+    /// Fails if the preset has a LUT made via [`Lut::new`] or an image mask.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron_options().to_string_pretty(self, PrettyConfig::default())
+    }
+
+    /// Removes the effects of any previous preset from the camera, and inserts the effects of this preset.
+    pub fn apply(&self, camera: &mut EntityCommands) {
+        Self::remove(camera);
+
+        self.insert_stack(camera, variant!(Blur));
+        self.insert_stack(camera, variant!(ChromaticAberration));
+        self.insert_stack(camera, variant!(ColorGrade));
+        self.insert_stack(camera, variant!(Crt));
+        self.insert_stack(camera, variant!(FilmGrain));
+        self.insert_stack(camera, variant!(Flip));
+        self.insert_stack(camera, variant!(LensDistortion));
+        self.insert_stack(camera, variant!(Mask));
+        self.insert_stack(camera, variant!(Palette));
+        self.insert_stack(camera, variant!(Pixelate));
+        self.insert_stack(camera, variant!(Wave));
+
+        self.insert_single(camera, variant!(Bloom));
+        self.insert_single(camera, variant!(Lut));
+        self.insert_single(camera, variant!(Raindrops));
+    }
+
+    /// Removes the effects any preset may have inserted from the camera.
+    pub fn remove(camera: &mut EntityCommands) {
+        camera.remove::<PresetComponents>();
+    }
+
+    fn insert_stack<T: Component + Clone>(
+        &self,
+        camera: &mut EntityCommands,
+        effect: impl Fn(&PresetEffect) -> Option<&T>,
+    ) {
+        let instances: Vec<_> = self
+            .effects
+            .iter()
+            .filter_map(|instance| {
+                Some(EffectInstance {
+                    effect: effect(&instance.effect)?.clone(),
+                    order: instance.order,
+                    stage: instance.stage,
+                    enabled: instance.enabled,
+                    mask: instance.mask.clone(),
+                })
+            })
+            .collect();
+
+        if !instances.is_empty() {
+            camera.insert(EffectStack { instances });
+        }
+    }
+
+    fn insert_single<C: Component + Clone>(
+        &self,
+        camera: &mut EntityCommands,
+        effect: impl Fn(&PresetEffect) -> Option<&C>,
+    ) {
+        let mut instances = self
+            .effects
+            .iter()
+            .filter(|instance| instance.enabled)
+            .filter_map(|instance| Some((effect(&instance.effect)?, instance)));
+
+        let Some((single, instance)) = instances.next() else {
+            return;
+        };
+
+        if instances.next().is_some() {
+            warn!(
+                "A camera can only use one {}, so only the first one in the preset is used",
+                get_short_name(std::any::type_name::<C>())
+            );
+        }
+
+        camera.insert((single.clone(), instance.order::<C>()));
+        if let Some(mask) = &instance.mask {
+            camera.insert(Masked::<C>::new(mask.clone()));
+        }
+    }
+}
+
+/// Loads [`PostProcessingPreset`]s from `.vfx.ron` files.
+///
+/// Images of LUTs given by path are loaded along with the preset.
+///
+/// This loader is added by the [`BevyVfxBagPlugin`](crate::BevyVfxBagPlugin).
+#[derive(Debug, Default)]
+pub struct PresetLoader;
+
+impl AssetLoader for PresetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut preset: PostProcessingPreset = ron_options().from_bytes(bytes)?;
+
+            let mut dependencies = vec![];
+            for instance in &mut preset.effects {
+                if let PresetEffect::Lut(lut) = &mut instance.effect {
+                    dependencies.extend(lut.load_images(load_context));
+                }
+            }
+
+            load_context
+                .set_default_asset(LoadedAsset::new(preset).with_dependencies(dependencies));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["vfx.ron"]
+    }
+}
+
+/// Applies presets to cameras when their handle changes, or when the preset is loaded or modified.
+fn apply_presets(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<PostProcessingPreset>>,
+    presets: Res<Assets<PostProcessingPreset>>,
+    cameras: Query<(Entity, Ref<Handle<PostProcessingPreset>>)>,
+) {
+    let changed: HashSet<HandleId> = events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle.id()),
+            AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+
+    for (entity, handle) in &cameras {
+        if !handle.is_changed() && !changed.contains(&handle.id()) {
+            continue;
+        }
+
+        if let Some(preset) = presets.get(&*handle) {
+            preset.apply(&mut commands.entity(entity));
+        }
+    }
+}
+
+/// Removes the effects of presets from cameras which no longer have a preset.
+fn remove_presets(
+    mut commands: Commands,
+    mut removed: RemovedComponents<Handle<PostProcessingPreset>>,
+) {
+    for entity in removed.iter() {
+        if let Some(mut camera) = commands.get_entity(entity) {
+            PostProcessingPreset::remove(&mut camera);
+        }
+    }
+}
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<PostProcessingPreset>()
+            .add_asset_loader(PresetLoader)
+            .add_systems(
+                (remove_presets, apply_presets)
+                    .chain()
+                    .in_base_set(CoreSet::PostUpdate),
+            );
+    }
+}
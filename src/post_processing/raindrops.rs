@@ -5,10 +5,12 @@ use bevy::render::{
     render_asset::RenderAssets,
     render_phase::AddRenderCommand,
     render_resource::{
-        AddressMode, BindingResource, Sampler, SamplerBindingType, SamplerDescriptor,
-        TextureSampleType, TextureViewDimension,
+        AddressMode, BindingResource, PipelineCache, RenderPipelineDescriptor, Sampler,
+        SamplerBindingType, SamplerDescriptor, SpecializedRenderPipeline,
+        SpecializedRenderPipelines, TextureSampleType, TextureViewDimension,
     },
     texture::{CompressedImageFormats, ImageType},
+    view::ExtractedView,
     RenderSet,
 };
 pub(crate) use bevy::{
@@ -23,15 +25,21 @@ pub(crate) use bevy::{
         render_phase::{DrawFunctions, RenderPhase},
         render_resource::{
             BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry,
-            BindingType, BufferBindingType, CachedRenderPipelineId, ShaderStages, ShaderType,
+            BindingType, BufferBindingType, ShaderStages, ShaderType,
         },
         renderer::RenderDevice,
     },
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::post_processing::{DrawPostProcessingEffect, UniformBindGroup};
 
-use super::{Order, PostProcessingPhaseItem};
+use super::{
+    effect_mask::{EffectMasking, Masked},
+    Order, PostProcessingPhaseItem, PostProcessingPipelineKey, PostProcessingSharedLayout,
+    PostProcessingStage, PostProcessingTarget,
+};
 
 pub(crate) const RAINDROPS_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3481202994982538867);
@@ -43,14 +51,14 @@ struct RaindropsTextureHandle(Handle<Image>);
 
 #[derive(Resource)]
 pub(crate) struct RaindropsData {
-    pub pipeline_id: CachedRenderPipelineId,
+    pub shared_layout: BindGroupLayout,
     pub layout: BindGroupLayout,
     pub sampler: Sampler,
 }
 
 impl FromWorld for RaindropsData {
     fn from_world(world: &mut World) -> Self {
-        let (raindrops_layout, pipeline_id) = super::create_layout_and_pipeline(
+        let raindrops_layout = super::create_layout(
             world,
             "Raindrops",
             &[
@@ -81,7 +89,6 @@ impl FromWorld for RaindropsData {
                     count: None,
                 },
             ],
-            RAINDROPS_SHADER_HANDLE.typed(),
         );
 
         let raindrops_sampler = world
@@ -95,14 +102,33 @@ impl FromWorld for RaindropsData {
                 ..default()
             });
 
+        let shared_layout = world
+            .resource::<PostProcessingSharedLayout>()
+            .shared_layout
+            .clone();
+
         RaindropsData {
-            pipeline_id,
+            shared_layout,
             layout: raindrops_layout,
             sampler: raindrops_sampler,
         }
     }
 }
 
+impl SpecializedRenderPipeline for RaindropsData {
+    type Key = PostProcessingPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        super::render_pipeline_descriptor(
+            "Raindrops",
+            &self.shared_layout,
+            &self.layout,
+            RAINDROPS_SHADER_HANDLE.typed(),
+            key,
+        )
+    }
+}
+
 pub(crate) struct Plugin;
 impl bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
@@ -132,6 +158,9 @@ impl bevy::prelude::Plugin for Plugin {
         .expect("Should load raindrops successfully");
         assets.set_untracked(RAINDROPS_IMAGE_HANDLE, image);
 
+        app.register_type::<Raindrops>()
+            .register_type::<Order<Raindrops>>();
+
         // This puts the uniform into the render world.
         app.add_plugin(ExtractComponentPlugin::<Raindrops>::default())
             .add_plugin(UniformComponentPlugin::<Raindrops>::default())
@@ -146,6 +175,7 @@ impl bevy::prelude::Plugin for Plugin {
                     .in_schedule(ExtractSchedule),
             )
             .init_resource::<RaindropsData>()
+            .init_resource::<SpecializedRenderPipelines<RaindropsData>>()
             .init_resource::<UniformBindGroup<Raindrops>>()
             .add_system(prepare.in_set(RenderSet::Prepare))
             .add_system(queue.in_set(RenderSet::Queue))
@@ -153,26 +183,43 @@ impl bevy::prelude::Plugin for Plugin {
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn prepare(
     data: Res<RaindropsData>,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<RaindropsData>>,
+    mut masking: EffectMasking,
     mut views: Query<(
         Entity,
         &mut RenderPhase<PostProcessingPhaseItem>,
+        &ExtractedView,
         &Order<Raindrops>,
+        Option<&Masked<Raindrops>>,
     )>,
     draw_functions: Res<DrawFunctions<PostProcessingPhaseItem>>,
 ) {
-    for (entity, mut phase, order) in views.iter_mut() {
+    for (entity, mut phase, view, order, maybe_masked) in views.iter_mut() {
         let draw_function = draw_functions
             .read()
             .id::<DrawPostProcessingEffect<Raindrops>>();
 
-        phase.add(PostProcessingPhaseItem {
+        let key = PostProcessingPipelineKey::for_view(view, vec![]);
+        let pipeline_id = pipelines.specialize(&pipeline_cache, &data, key);
+
+        let sort_key = order.sort_key(PostProcessingStage::PreTonemap);
+        masking.add_items(
+            &mut phase,
             entity,
-            sort_key: (*order).into(),
-            draw_function,
-            pipeline_id: data.pipeline_id,
-        });
+            sort_key,
+            maybe_masked.map(|masked| &masked.mask),
+            [PostProcessingPhaseItem {
+                entity,
+                sort_key,
+                draw_function,
+                pipeline_id,
+                target: PostProcessingTarget::ViewTarget,
+            }],
+        );
     }
 }
 
@@ -214,7 +261,11 @@ fn queue(
 }
 
 /// Raindrops settings.
-#[derive(Debug, Component, Clone, Copy, ShaderType)]
+#[derive(
+    Debug, Component, Clone, Copy, ShaderType, Reflect, FromReflect, Serialize, Deserialize,
+)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Raindrops {
     /// How quickly the raindrops animate.
     pub speed: f32,
@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+
+use super::{chromatic_aberration::ChromaticAberration, wave::Wave};
+
+/// Shakes a camera by driving its [`Wave`], and optionally its [`ChromaticAberration`], from trauma.
+///
+/// Trauma is added via [`ScreenShakeImpulse`] events, is at most one, and decays over time.
+/// How much the camera shakes is the trauma squared, so small impulses give subtle shakes
+/// and stacking impulses quickly gives violent shakes.
+/// The shake varies smoothly and randomly over time via noise.
+///
+/// While shaking, the shake owns the [`Wave`] of the camera and sets it every frame.
+/// Once the trauma reaches zero, the wave the camera had before shaking is restored,
+/// or the wave is removed if there was none.
+/// The same goes for the [`ChromaticAberration`], if the shake drives one.
+/// Use an [`Order`](super::Order) on the camera to place them relative to other effects.
+#[derive(Debug, Component, Clone)]
+pub struct ScreenShake {
+    /// The wave at full trauma.
+    /// Amplitudes are scaled by the shake.
+    /// The number of waves and their speeds are not, since the waves are animated by the global time
+    /// and changing their speeds would make them jump.
+    pub max_wave: Wave,
+
+    /// The chromatic aberration at full trauma, if any.
+    /// Magnitudes are scaled by the shake.
+    pub max_chromatic_aberration: Option<ChromaticAberration>,
+
+    /// How much trauma is lost per second.
+    pub decay: f32,
+
+    /// How many times per second the noise varies the shake.
+    pub frequency: f32,
+
+    trauma: f32,
+
+    /// Where the noise is sampled.
+    noise_position: f32,
+
+    /// Whether the shake currently owns the effects of the camera.
+    shaking: bool,
+
+    /// The effects the camera had before shaking, restored once the shake is over.
+    previous_wave: Option<Wave>,
+    previous_chromatic_aberration: Option<ChromaticAberration>,
+}
+
+impl Default for ScreenShake {
+    fn default() -> Self {
+        Self {
+            max_wave: Wave {
+                waves_x: 2.0,
+                waves_y: 3.0,
+                speed_x: 30.0,
+                speed_y: 20.0,
+                amplitude_x: 0.02,
+                amplitude_y: 0.02,
+            },
+            max_chromatic_aberration: None,
+            decay: 1.0,
+            frequency: 15.0,
+            trauma: 0.0,
+            noise_position: 0.0,
+            shaking: false,
+            previous_wave: None,
+            previous_chromatic_aberration: None,
+        }
+    }
+}
+
+impl ScreenShake {
+    /// Also drives a chromatic aberration, which is the given one at full trauma.
+    pub fn with_chromatic_aberration(mut self, max: ChromaticAberration) -> Self {
+        self.max_chromatic_aberration = Some(max);
+        self
+    }
+
+    /// The current trauma, from zero to one.
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Adds trauma, which is clamped to zero to one.
+    /// Prefer sending a [`ScreenShakeImpulse`], which does this.
+    pub fn add_trauma(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+    }
+
+    /// How much the camera shakes, from zero to one.
+    pub fn shake(&self) -> f32 {
+        self.trauma * self.trauma
+    }
+}
+
+/// Adds trauma to cameras with a [`ScreenShake`].
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenShakeImpulse {
+    /// The camera to shake.
+    /// If not set, all cameras with a [`ScreenShake`] are shaken.
+    pub camera: Option<Entity>,
+
+    /// How much trauma to add.
+    /// Trauma is at most one, which is the strongest shake.
+    pub trauma: f32,
+}
+
+impl ScreenShakeImpulse {
+    /// Shakes all cameras with a [`ScreenShake`].
+    pub fn all(trauma: f32) -> Self {
+        Self {
+            camera: None,
+            trauma,
+        }
+    }
+
+    /// Shakes the given camera.
+    pub fn camera(camera: Entity, trauma: f32) -> Self {
+        Self {
+            camera: Some(camera),
+            trauma,
+        }
+    }
+}
+
+/// Smooth noise from minus one to one, which varies about once per unit of `x`.
+fn noise(x: f32, seed: u32) -> f32 {
+    let hash = |i: i32| {
+        let mut h = (i as u32).wrapping_mul(0x9E37_79B9) ^ seed.wrapping_mul(0x85EB_CA6B);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x2C1B_3C6D);
+        h ^= h >> 12;
+        h as f32 / u32::MAX as f32 * 2.0 - 1.0
+    };
+
+    let i = x.floor();
+    let f = x - i;
+    let smooth = f * f * (3.0 - 2.0 * f);
+
+    hash(i as i32) + (hash(i as i32 + 1) - hash(i as i32)) * smooth
+}
+
+fn add_trauma(
+    mut impulses: EventReader<ScreenShakeImpulse>,
+    mut shakes: Query<(Entity, &mut ScreenShake)>,
+) {
+    for impulse in impulses.iter() {
+        match impulse.camera {
+            Some(camera) => match shakes.get_mut(camera) {
+                Ok((_, mut shake)) => shake.add_trauma(impulse.trauma),
+                Err(_) => warn!("Camera {camera:?} can not be shaken without a `ScreenShake`"),
+            },
+            None => {
+                for (_, mut shake) in &mut shakes {
+                    shake.add_trauma(impulse.trauma);
+                }
+            }
+        }
+    }
+}
+
+fn shake_cameras(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut shakes: Query<(
+        Entity,
+        &mut ScreenShake,
+        Option<&Wave>,
+        Option<&ChromaticAberration>,
+    )>,
+) {
+    for (entity, mut screen_shake, wave, chromatic_aberration) in &mut shakes {
+        if screen_shake.trauma <= 0.0 {
+            continue;
+        }
+
+        if !screen_shake.shaking {
+            screen_shake.shaking = true;
+            screen_shake.previous_wave = wave.copied();
+            screen_shake.previous_chromatic_aberration = chromatic_aberration.copied();
+        }
+
+        let delta = time.delta_seconds();
+        screen_shake.trauma = (screen_shake.trauma - screen_shake.decay * delta).max(0.0);
+        screen_shake.noise_position += screen_shake.frequency * delta;
+
+        if screen_shake.trauma == 0.0 {
+            screen_shake.shaking = false;
+
+            let mut camera = commands.entity(entity);
+            match screen_shake.previous_wave.take() {
+                Some(wave) => camera.insert(wave),
+                None => camera.remove::<Wave>(),
+            };
+
+            let previous = screen_shake.previous_chromatic_aberration.take();
+            if screen_shake.max_chromatic_aberration.is_some() {
+                match previous {
+                    Some(chromatic_aberration) => camera.insert(chromatic_aberration),
+                    None => camera.remove::<ChromaticAberration>(),
+                };
+            }
+            continue;
+        }
+
+        let shake = screen_shake.shake();
+        let position = screen_shake.noise_position;
+        // Noise from zero to one, so waves keep their direction and magnitudes stay positive.
+        let positive = |seed: u32| noise(position, seed) * 0.5 + 0.5;
+
+        let max = screen_shake.max_wave;
+        let mut camera = commands.entity(entity);
+        camera.insert(Wave {
+            amplitude_x: max.amplitude_x * shake * positive(0),
+            amplitude_y: max.amplitude_y * shake * positive(1),
+            ..max
+        });
+
+        if let Some(max) = screen_shake.max_chromatic_aberration {
+            camera.insert(ChromaticAberration {
+                magnitude_r: max.magnitude_r * shake * positive(2),
+                magnitude_g: max.magnitude_g * shake * positive(3),
+                magnitude_b: max.magnitude_b * shake * positive(4),
+                ..max
+            });
+        }
+    }
+}
+
+/// Adds the systems and events for [`ScreenShake`].
+///
+/// Added by the [`BevyVfxBagPlugin`](crate::BevyVfxBagPlugin), and does not need rendering on its own.
+#[derive(Debug, Default)]
+pub struct ScreenShakePlugin;
+
+impl Plugin for ScreenShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ScreenShakeImpulse>()
+            .add_systems((add_trauma, shake_cameras).chain());
+    }
+}
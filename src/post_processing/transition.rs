@@ -0,0 +1,277 @@
+use std::{fmt::Display, time::Duration};
+
+use bevy::{
+    asset::load_internal_asset,
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{ShaderDefVal, ShaderType},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    tween::{EffectTween, EffectTweenCompleted},
+    Order, PostProcessingEffect, PostProcessingEffectPlugin, PostProcessingStage,
+};
+
+pub(crate) const TRANSITION_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 10721720985445241341);
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            TRANSITION_SHADER_HANDLE,
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/assets/shaders/",
+                "transition.wgsl"
+            ),
+            Shader::from_wgsl
+        );
+
+        app.register_type::<Transition>()
+            .register_type::<Order<Transition>>()
+            .register_type::<TransitionVariant>()
+            .add_event::<TransitionFinished>()
+            .add_system(send_finished)
+            .add_plugin(PostProcessingEffectPlugin::<Transition>::default());
+    }
+}
+
+/// How a [`Transition`] covers the view.
+#[derive(Debug, Default, PartialEq, Copy, Clone, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Default, Serialize, Deserialize)]
+pub enum TransitionVariant {
+    /// The whole view fades to the color.
+    #[default]
+    Fade,
+
+    /// The color sweeps over the view in a direction.
+    Wipe {
+        /// The direction the edge moves in, in UV coordinates.
+        /// For example `Vec2::X` wipes from the left to the right, and `Vec2::Y` from the top to the bottom.
+        direction: Vec2,
+    },
+
+    /// A circle around a center closes, covering the view from the outside in.
+    Iris {
+        /// The center of the circle, in UV coordinates.
+        center: Vec2,
+    },
+
+    /// The color covers the view in random, noisy patches.
+    Dissolve {
+        /// How many patches there are across the height of the view, roughly.
+        scale: f32,
+    },
+
+    /// The view is pixelated into increasingly large blocks, which are covered one by one.
+    ///
+    /// Uses the same maths as [`Pixelate`](super::pixelate::Pixelate).
+    PixelateDissolve {
+        /// The size of the blocks in pixels once the view is fully covered.
+        max_block_size: f32,
+    },
+}
+
+impl From<TransitionVariant> for ShaderDefVal {
+    fn from(variant: TransitionVariant) -> Self {
+        match variant {
+            TransitionVariant::Fade => "FADE",
+            TransitionVariant::Wipe { .. } => "WIPE",
+            TransitionVariant::Iris { .. } => "IRIS",
+            TransitionVariant::Dissolve { .. } => "DISSOLVE",
+            TransitionVariant::PixelateDissolve { .. } => "PIXELATE_DISSOLVE",
+        }
+        .into()
+    }
+}
+
+/// Covers the view with a color, for example when changing scenes.
+///
+/// At a `progress` of `0.0` the view is not covered at all, and at `1.0` it is fully covered.
+/// Use [`Transition::animate_to`] to animate the progress,
+/// and listen for [`TransitionFinished`] to know when it is done.
+#[derive(Debug, Copy, Clone, Component, Reflect, FromReflect, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transition {
+    /// How far the transition is, from `0.0` to `1.0`.
+    pub progress: f32,
+
+    /// The color covering the view.
+    pub color: Color,
+
+    /// How wide the edge between the view and the color is, relative to the whole transition.
+    /// Has no effect on [`TransitionVariant::Fade`].
+    pub softness: f32,
+
+    /// Which [`TransitionVariant`] to use.
+    pub variant: TransitionVariant,
+}
+
+impl Transition {
+    /// Create a new fade to black.
+    pub fn fade() -> Self {
+        Self::default()
+    }
+
+    /// Create a new black wipe from the left to the right.
+    pub fn wipe() -> Self {
+        Self {
+            variant: TransitionVariant::Wipe { direction: Vec2::X },
+            ..default()
+        }
+    }
+
+    /// Create a new black iris closing on the center of the view.
+    pub fn iris() -> Self {
+        Self {
+            softness: 0.02,
+            variant: TransitionVariant::Iris {
+                center: Vec2::splat(0.5),
+            },
+            ..default()
+        }
+    }
+
+    /// Create a new black dissolve.
+    pub fn dissolve() -> Self {
+        Self {
+            variant: TransitionVariant::Dissolve { scale: 8.0 },
+            ..default()
+        }
+    }
+
+    /// Create a new black pixelating dissolve.
+    pub fn pixelate_dissolve() -> Self {
+        Self {
+            variant: TransitionVariant::PixelateDissolve {
+                max_block_size: 64.0,
+            },
+            ..default()
+        }
+    }
+
+    /// Sets the color, and returns the transition.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// A tween which animates the progress from its current value to `progress` over `duration`.
+    ///
+    /// Insert it on the same entity as the transition.
+    /// A [`TransitionFinished`] event is sent when it is done.
+    pub fn animate_to(&self, progress: f32, duration: Duration) -> EffectTween<Self> {
+        EffectTween::new(*self, Self { progress, ..*self }, duration)
+    }
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self {
+            progress: 0.0,
+            color: Color::BLACK,
+            softness: 0.1,
+            variant: TransitionVariant::default(),
+        }
+    }
+}
+
+impl Display for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Transition {:?}, progress: {}, softness: {}",
+            self.variant, self.progress, self.softness
+        )
+    }
+}
+
+/// Sent when a tween made via [`Transition::animate_to`], or any other [`EffectTween`] of a [`Transition`], finishes.
+#[derive(Debug, Clone, Copy)]
+pub struct TransitionFinished {
+    /// The camera of the transition.
+    pub camera: Entity,
+
+    /// The progress the transition ended at.
+    pub progress: f32,
+}
+
+fn send_finished(
+    mut completed: EventReader<EffectTweenCompleted<Transition>>,
+    mut finished: EventWriter<TransitionFinished>,
+    transitions: Query<&Transition>,
+) {
+    for event in completed.iter() {
+        if let Ok(transition) = transitions.get(event.entity) {
+            finished.send(TransitionFinished {
+                camera: event.entity,
+                progress: transition.progress,
+            });
+        }
+    }
+}
+
+#[doc(hidden)]
+/// [`Transition`] as a uniform.
+#[derive(Debug, ShaderType, Clone, Component, Copy)]
+pub struct TransitionUniform {
+    pub(crate) color: Vec4,
+    pub(crate) direction: Vec2,
+    pub(crate) center: Vec2,
+    pub(crate) progress: f32,
+    pub(crate) softness: f32,
+    pub(crate) scale: f32,
+}
+
+impl From<Transition> for TransitionUniform {
+    fn from(transition: Transition) -> Self {
+        let mut uniform = Self {
+            color: Vec4::from(transition.color.as_linear_rgba_f32()),
+            direction: Vec2::X,
+            center: Vec2::splat(0.5),
+            progress: transition.progress,
+            softness: transition.softness,
+            scale: 1.0,
+        };
+
+        match transition.variant {
+            TransitionVariant::Fade => {}
+            TransitionVariant::Wipe { direction } => uniform.direction = direction,
+            TransitionVariant::Iris { center } => uniform.center = center,
+            TransitionVariant::Dissolve { scale } => uniform.scale = scale,
+            TransitionVariant::PixelateDissolve { max_block_size } => {
+                uniform.scale = max_block_size
+            }
+        }
+
+        uniform
+    }
+}
+
+impl PostProcessingEffect for Transition {
+    type Uniform = TransitionUniform;
+
+    const LABEL: &'static str = "Transition";
+
+    fn shader() -> Handle<Shader> {
+        TRANSITION_SHADER_HANDLE.typed()
+    }
+
+    fn uniform(&self) -> Self::Uniform {
+        (*self).into()
+    }
+
+    fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        vec![self.variant.into()]
+    }
+
+    /// Transitions cover the UI as well.
+    fn stage(&self) -> PostProcessingStage {
+        PostProcessingStage::PostUi
+    }
+}
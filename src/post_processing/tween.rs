@@ -0,0 +1,534 @@
+use std::{f32::consts::PI, marker::PhantomData, time::Duration};
+
+use bevy::prelude::*;
+
+use super::{
+    bloom::Bloom,
+    blur::{Blur, BlurKernel},
+    chromatic_aberration::ChromaticAberration,
+    color_grade::ColorGrade,
+    crt::Crt,
+    depth_fog::DepthFog,
+    depth_of_field::DepthOfField,
+    film_grain::FilmGrain,
+    flip::Flip,
+    lens_distortion::LensDistortion,
+    lut::{Lut, LutSplit},
+    masks::Mask,
+    palette::Palette,
+    pixelate::Pixelate,
+    raindrops::Raindrops,
+    transition::Transition,
+    wave::Wave,
+};
+
+/// An effect whose parameters can be interpolated by an [`EffectTween`].
+///
+/// Implemented for all effects bundled in this crate.
+/// Custom effects may implement it too, and add an [`EffectTweenPlugin`] for themselves.
+pub trait Tweenable: Component {
+    /// Sets the parameters of `self` to those between `from` and `to`,
+    /// where `t` is zero at `from` and one at `to`.
+    ///
+    /// Eased values of `t` may overshoot, so `t` may be outside of zero to one.
+    /// Parameters which can not be interpolated should switch from `from` to `to` halfway.
+    fn tween(&mut self, from: &Self, to: &Self, t: f32);
+}
+
+/// Picks `from` for the first half of a tween and `to` for the second half.
+fn step<T: Clone>(from: &T, to: &T, t: f32) -> T {
+    if t < 0.5 {
+        from.clone()
+    } else {
+        to.clone()
+    }
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+impl Tweenable for Bloom {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.threshold = lerp(from.threshold, to.threshold, t);
+        self.knee = lerp(from.knee, to.knee, t);
+        self.intensity = lerp(from.intensity, to.intensity, t);
+
+        let tint = Vec4::from(from.tint.as_linear_rgba_f32())
+            .lerp(Vec4::from(to.tint.as_linear_rgba_f32()), t);
+        self.tint = Color::rgba_linear(tint.x, tint.y, tint.z, tint.w);
+    }
+}
+
+impl Tweenable for Blur {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.amount = lerp(from.amount, to.amount, t);
+        self.kernel_radius = lerp(from.kernel_radius, to.kernel_radius, t);
+
+        self.kernel = match (from.kernel, to.kernel) {
+            (
+                BlurKernel::Gaussian {
+                    sigma: from_sigma,
+                    taps: from_taps,
+                },
+                BlurKernel::Gaussian {
+                    sigma: to_sigma,
+                    taps: to_taps,
+                },
+            ) => BlurKernel::Gaussian {
+                sigma: lerp(from_sigma, to_sigma, t),
+                taps: lerp(from_taps as f32, to_taps as f32, t).round().max(0.0) as u32,
+            },
+            (from_kernel, to_kernel) => step(&from_kernel, &to_kernel, t),
+        };
+    }
+}
+
+impl Tweenable for ChromaticAberration {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.dir_r = from.dir_r.lerp(to.dir_r, t);
+        self.magnitude_r = lerp(from.magnitude_r, to.magnitude_r, t);
+        self.dir_g = from.dir_g.lerp(to.dir_g, t);
+        self.magnitude_g = lerp(from.magnitude_g, to.magnitude_g, t);
+        self.dir_b = from.dir_b.lerp(to.dir_b, t);
+        self.magnitude_b = lerp(from.magnitude_b, to.magnitude_b, t);
+        self.mode = step(&from.mode, &to.mode, t);
+        self.center = from.center.lerp(to.center, t);
+    }
+}
+
+impl Tweenable for ColorGrade {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.exposure = lerp(from.exposure, to.exposure, t);
+        self.contrast = lerp(from.contrast, to.contrast, t);
+        self.pivot = lerp(from.pivot, to.pivot, t);
+        self.saturation = lerp(from.saturation, to.saturation, t);
+        self.vibrance = lerp(from.vibrance, to.vibrance, t);
+        self.hue_shift = lerp(from.hue_shift, to.hue_shift, t);
+        self.temperature = lerp(from.temperature, to.temperature, t);
+        self.tint = lerp(from.tint, to.tint, t);
+        self.lift = from.lift.lerp(to.lift, t);
+        self.gamma = from.gamma.lerp(to.gamma, t);
+        self.gain = from.gain.lerp(to.gain, t);
+    }
+}
+
+impl Tweenable for Crt {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.curvature = lerp(from.curvature, to.curvature, t);
+        self.lines = lerp(from.lines, to.lines, t);
+        self.scanlines = lerp(from.scanlines, to.scanlines, t);
+        self.phosphor_mask = step(&from.phosphor_mask, &to.phosphor_mask, t);
+        self.mask_intensity = lerp(from.mask_intensity, to.mask_intensity, t);
+        self.mask_size = lerp(from.mask_size, to.mask_size, t);
+        self.bleed = lerp(from.bleed, to.bleed, t);
+        self.edge = lerp(from.edge, to.edge, t);
+        self.brightness = lerp(from.brightness, to.brightness, t);
+    }
+}
+
+impl Tweenable for DepthFog {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        let color = Vec4::from(from.color.as_linear_rgba_f32())
+            .lerp(Vec4::from(to.color.as_linear_rgba_f32()), t);
+        self.color = Color::rgba_linear(color.x, color.y, color.z, color.w);
+        self.start = lerp(from.start, to.start, t);
+        self.density = lerp(from.density, to.density, t);
+    }
+}
+
+impl Tweenable for DepthOfField {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.focal_distance = lerp(from.focal_distance, to.focal_distance, t);
+        self.aperture = lerp(from.aperture, to.aperture, t);
+        self.max_coc = lerp(from.max_coc, to.max_coc, t);
+    }
+}
+
+impl Tweenable for FilmGrain {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.intensity = lerp(from.intensity, to.intensity, t);
+        self.size = lerp(from.size, to.size, t);
+        self.colored = step(&from.colored, &to.colored, t);
+        self.frame_rate = lerp(from.frame_rate, to.frame_rate, t);
+        self.shadows = lerp(from.shadows, to.shadows, t);
+        self.midtones = lerp(from.midtones, to.midtones, t);
+        self.highlights = lerp(from.highlights, to.highlights, t);
+    }
+}
+
+/// Flips switch halfway.
+impl Tweenable for Flip {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        *self = step(from, to, t);
+    }
+}
+
+impl Tweenable for LensDistortion {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.k1 = lerp(from.k1, to.k1, t);
+        self.k2 = lerp(from.k2, to.k2, t);
+        self.center = from.center.lerp(to.center, t);
+        self.zoom_to_fill = step(&from.zoom_to_fill, &to.zoom_to_fill, t);
+        self.chromatic_aberration = lerp(from.chromatic_aberration, to.chromatic_aberration, t);
+    }
+}
+
+/// The intensity, blend, and split of the LUT are interpolated.
+/// The images are not, and stay those of the tweened LUT.
+/// Cross-fade to another LUT via [`Lut::cross_fade`] and tween the blend to change the grade smoothly.
+impl Tweenable for Lut {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.intensity = lerp(from.intensity, to.intensity, t);
+        self.blend = lerp(from.blend, to.blend, t);
+
+        self.split = match (from.split, to.split) {
+            (Some(from_split), Some(to_split)) => Some(LutSplit {
+                position: lerp(from_split.position, to_split.position, t),
+                angle: lerp(from_split.angle, to_split.angle, t),
+            }),
+            (from_split, to_split) => step(&from_split, &to_split, t),
+        };
+    }
+}
+
+impl Tweenable for Mask {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.strength = lerp(from.strength, to.strength, t);
+        self.fade = lerp(from.fade, to.fade, t);
+        self.variant = step(&from.variant, &to.variant, t);
+    }
+}
+
+impl Tweenable for Palette {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        let nearest = if t < 0.5 { from } else { to };
+
+        // Colors read from images are left to be read again when the image changes.
+        if from.image.is_none() && to.image.is_none() {
+            if from.colors.len() == to.colors.len() {
+                self.colors.resize(from.colors.len(), Color::NONE);
+
+                for ((color, from), to) in self.colors.iter_mut().zip(&from.colors).zip(&to.colors)
+                {
+                    let lerped = Vec4::from(from.as_linear_rgba_f32())
+                        .lerp(Vec4::from(to.as_linear_rgba_f32()), t);
+                    *color = Color::rgba_linear(lerped.x, lerped.y, lerped.z, lerped.w);
+                }
+            } else {
+                self.colors.clone_from(&nearest.colors);
+            }
+        }
+
+        if self.image != nearest.image {
+            self.image.clone_from(&nearest.image);
+        }
+
+        self.dither = step(&from.dither, &to.dither, t);
+        self.dither_strength = lerp(from.dither_strength, to.dither_strength, t);
+        self.block_size = from.block_size.lerp(to.block_size, t);
+        self.anchor = from.anchor.lerp(to.anchor, t);
+    }
+}
+
+impl Tweenable for Pixelate {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.block_size = lerp(from.block_size, to.block_size, t);
+        self.aspect_ratio = lerp(from.aspect_ratio, to.aspect_ratio, t);
+        self.shape = step(&from.shape, &to.shape, t);
+        self.sampling = step(&from.sampling, &to.sampling, t);
+        self.anchor = from.anchor.lerp(to.anchor, t);
+    }
+}
+
+impl Tweenable for Raindrops {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.speed = lerp(from.speed, to.speed, t);
+        self.warping = lerp(from.warping, to.warping, t);
+        self.zoom = lerp(from.zoom, to.zoom, t);
+    }
+}
+
+impl Tweenable for Transition {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.progress = lerp(from.progress, to.progress, t);
+        self.softness = lerp(from.softness, to.softness, t);
+
+        let color = Vec4::from(from.color.as_linear_rgba_f32())
+            .lerp(Vec4::from(to.color.as_linear_rgba_f32()), t);
+        self.color = Color::rgba_linear(color.x, color.y, color.z, color.w);
+        self.variant = step(&from.variant, &to.variant, t);
+    }
+}
+
+impl Tweenable for Wave {
+    fn tween(&mut self, from: &Self, to: &Self, t: f32) {
+        self.waves_x = lerp(from.waves_x, to.waves_x, t);
+        self.waves_y = lerp(from.waves_y, to.waves_y, t);
+        self.speed_x = lerp(from.speed_x, to.speed_x, t);
+        self.speed_y = lerp(from.speed_y, to.speed_y, t);
+        self.amplitude_x = lerp(from.amplitude_x, to.amplitude_x, t);
+        self.amplitude_y = lerp(from.amplitude_y, to.amplitude_y, t);
+    }
+}
+
+/// How the progress of an [`EffectTween`] maps to how far the parameters are between the start and the end.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Starts slow, quadratically.
+    QuadraticIn,
+    /// Ends slow, quadratically.
+    QuadraticOut,
+    /// Starts and ends slow, quadratically.
+    QuadraticInOut,
+    /// Starts slow, cubically.
+    CubicIn,
+    /// Ends slow, cubically.
+    CubicOut,
+    /// Starts and ends slow, cubically.
+    CubicInOut,
+    /// Starts slow, along a sine curve.
+    SineIn,
+    /// Ends slow, along a sine curve.
+    SineOut,
+    /// Starts and ends slow, along a sine curve.
+    SineInOut,
+    /// Ends slow, exponentially.
+    /// Good for decaying effects such as a camera shake.
+    ExponentialOut,
+    /// Overshoots the end a little before settling.
+    BackOut,
+}
+
+impl Easing {
+    /// Eases the progress `t`, going from zero to one.
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+            Easing::QuadraticIn => t * t,
+            Easing::QuadraticOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadraticInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => -((t * PI).cos() - 1.0) / 2.0,
+            Easing::ExponentialOut => {
+                if t == 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2f32.powf(-10.0 * t)
+                }
+            }
+            Easing::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                let t = t - 1.0;
+                1.0 + t * t * ((OVERSHOOT + 1.0) * t + OVERSHOOT)
+            }
+        }
+    }
+}
+
+/// What an [`EffectTween`] does when it reaches its end.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TweenRepeat {
+    /// The tween stops at the end, and the [`EffectTween`] component is removed.
+    #[default]
+    Once,
+    /// The tween starts over from the start.
+    Loop,
+    /// The tween goes back to the start, and then forth again.
+    PingPong,
+}
+
+/// Interpolates the parameters of the effect `T` on the same entity from one set of parameters to another.
+///
+/// For example a camera shake may be made by tweening the amplitudes of a [`Wave`] to zero,
+/// or a damage flash by tweening the magnitudes of a [`ChromaticAberration`] from large to zero.
+/// Only an effect component on the entity is tweened, not the instances of an [`EffectStack`](super::EffectStack).
+///
+/// Each time the tween reaches its end, which for [`TweenRepeat::PingPong`] is back at the start,
+/// an [`EffectTweenCompleted`] event is sent.
+/// The effect component itself is not removed when the tween is done.
+#[derive(Debug, Component, Clone)]
+pub struct EffectTween<T> {
+    /// The parameters at the start of the tween.
+    pub from: T,
+
+    /// The parameters at the end of the tween.
+    pub to: T,
+
+    /// How long it takes to go from the start to the end.
+    /// A zero duration ends the tween immediately, even when repeating.
+    pub duration: Duration,
+
+    /// How the progress maps to the parameters.
+    pub easing: Easing,
+
+    /// What happens at the end.
+    pub repeat: TweenRepeat,
+
+    /// Time since the tween started, in seconds.
+    elapsed: f32,
+}
+
+impl<T> EffectTween<T> {
+    /// Create a new tween, which goes linearly from `from` to `to` once.
+    pub fn new(from: T, to: T, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            easing: Easing::Linear,
+            repeat: TweenRepeat::Once,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Sets the easing, and returns the tween.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Makes the tween start over at its end, and returns the tween.
+    pub fn looping(mut self) -> Self {
+        self.repeat = TweenRepeat::Loop;
+        self
+    }
+
+    /// Makes the tween go back and forth, and returns the tween.
+    pub fn ping_pong(mut self) -> Self {
+        self.repeat = TweenRepeat::PingPong;
+        self
+    }
+
+    /// How far the tween is from the start (zero) to the end (one), before easing.
+    pub fn progress(&self) -> f32 {
+        let duration = self.duration.as_secs_f32();
+        if duration == 0.0 {
+            return 1.0;
+        }
+
+        let cycles = self.elapsed / duration;
+        match self.repeat {
+            TweenRepeat::Once => cycles.min(1.0),
+            TweenRepeat::Loop => cycles.fract(),
+            TweenRepeat::PingPong => 1.0 - ((cycles % 2.0) - 1.0).abs(),
+        }
+    }
+
+    /// How many times the tween has reached its end.
+    fn completions(&self) -> u32 {
+        let duration = self.duration.as_secs_f32();
+        if duration == 0.0 {
+            return 1;
+        }
+
+        let cycle = match self.repeat {
+            TweenRepeat::Once | TweenRepeat::Loop => duration,
+            TweenRepeat::PingPong => duration * 2.0,
+        };
+        (self.elapsed / cycle) as u32
+    }
+
+    fn done(&self) -> bool {
+        self.repeat == TweenRepeat::Once && self.completions() > 0
+            || self.duration == Duration::ZERO
+    }
+}
+
+/// Sent when an [`EffectTween`] of the effect `T` reaches its end.
+///
+/// Sent at most once per frame per tween.
+#[derive(Debug)]
+pub struct EffectTweenCompleted<T> {
+    /// The entity of the tween.
+    pub entity: Entity,
+    marker: PhantomData<fn() -> T>,
+}
+
+fn tween_effects<T: Tweenable>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut completed: EventWriter<EffectTweenCompleted<T>>,
+    mut tweens: Query<(Entity, &mut EffectTween<T>, &mut T)>,
+) {
+    for (entity, mut tween, mut effect) in &mut tweens {
+        let completions = tween.completions();
+        tween.elapsed += time.delta_seconds();
+
+        let t = tween.easing.ease(tween.progress());
+        effect.tween(&tween.from, &tween.to, t);
+
+        if tween.completions() > completions || tween.done() {
+            completed.send(EffectTweenCompleted {
+                entity,
+                marker: PhantomData,
+            });
+        }
+
+        if tween.done() {
+            commands.entity(entity).remove::<EffectTween<T>>();
+        }
+    }
+}
+
+/// Adds the systems and events for tweening the effect `T` via [`EffectTween`].
+///
+/// Added for all effects bundled in this crate by the [`BevyVfxBagPlugin`](crate::BevyVfxBagPlugin).
+pub struct EffectTweenPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for EffectTweenPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Tweenable> bevy::prelude::Plugin for EffectTweenPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EffectTweenCompleted<T>>()
+            .add_system(tween_effects::<T>);
+    }
+}
+
+pub(crate) struct Plugin;
+impl bevy::prelude::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(EffectTweenPlugin::<Bloom>::default())
+            .add_plugin(EffectTweenPlugin::<Blur>::default())
+            .add_plugin(EffectTweenPlugin::<ChromaticAberration>::default())
+            .add_plugin(EffectTweenPlugin::<ColorGrade>::default())
+            .add_plugin(EffectTweenPlugin::<Crt>::default())
+            .add_plugin(EffectTweenPlugin::<DepthFog>::default())
+            .add_plugin(EffectTweenPlugin::<DepthOfField>::default())
+            .add_plugin(EffectTweenPlugin::<FilmGrain>::default())
+            .add_plugin(EffectTweenPlugin::<Flip>::default())
+            .add_plugin(EffectTweenPlugin::<LensDistortion>::default())
+            .add_plugin(EffectTweenPlugin::<Lut>::default())
+            .add_plugin(EffectTweenPlugin::<Mask>::default())
+            .add_plugin(EffectTweenPlugin::<Palette>::default())
+            .add_plugin(EffectTweenPlugin::<Pixelate>::default())
+            .add_plugin(EffectTweenPlugin::<Raindrops>::default())
+            .add_plugin(EffectTweenPlugin::<Transition>::default())
+            .add_plugin(EffectTweenPlugin::<Wave>::default());
+    }
+}
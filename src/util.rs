@@ -4,53 +4,65 @@ use bevy::{
     render::render_graph::{self, RenderGraph},
 };
 
-pub fn add_nodes<T: FromWorld + render_graph::Node>(
+/// Adds a node made by `new_node` to both the 2D and the 3D graph, running after the nodes in `after`
+/// and before the nodes in `before`.
+///
+/// Nodes which are not in a graph, for example since the plugin adding them is not used
+/// or is added after this one, are skipped with a warning.
+pub fn add_nodes<T: render_graph::Node>(
     render_app: &mut App,
     name_2d: &str,
     name_3d: &str,
+    after: &[&str],
+    before: &[&str],
+    new_node: impl Fn(&mut World) -> T,
 ) {
-    {
-        let node = <T as FromWorld>::from_world(&mut render_app.world);
+    for (graph_name, view_entity, name) in [
+        (
+            core_3d::graph::NAME,
+            core_3d::graph::input::VIEW_ENTITY,
+            name_3d,
+        ),
+        (
+            core_2d::graph::NAME,
+            core_2d::graph::input::VIEW_ENTITY,
+            name_2d,
+        ),
+    ] {
+        let node = new_node(&mut render_app.world);
         let mut binding = render_app.world.resource_mut::<RenderGraph>();
         let graph = binding
-            .get_sub_graph_mut(core_3d::graph::NAME)
+            .get_sub_graph_mut(graph_name)
             .expect("Graph should be available");
 
-        graph.add_node(name_3d.to_owned(), node);
+        graph.add_node(name.to_owned(), node);
 
-        graph.add_slot_edge(
-            graph.input_node().id,
-            core_3d::graph::input::VIEW_ENTITY,
-            name_3d.to_owned(),
-            "view",
-        );
+        graph.add_slot_edge(graph.input_node().id, view_entity, name.to_owned(), "view");
 
-        graph.add_node_edge(core_3d::graph::node::MAIN_PASS, name_3d.to_owned());
+        for &other in after {
+            if has_node(graph, graph_name, name, other) {
+                graph.add_node_edge(other.to_owned(), name.to_owned());
+            }
+        }
 
-        graph.add_node_edge(
-            name_3d.to_owned(),
-            core_3d::graph::node::END_MAIN_PASS_POST_PROCESSING,
-        );
+        for &other in before {
+            if has_node(graph, graph_name, name, other) {
+                graph.add_node_edge(name.to_owned(), other.to_owned());
+            }
+        }
     }
-    {
-        let node = <T as FromWorld>::from_world(&mut render_app.world);
-        let mut binding = render_app.world.resource_mut::<RenderGraph>();
-        let graph = binding
-            .get_sub_graph_mut(core_2d::graph::NAME)
-            .expect("Graph should be available");
-
-        graph.add_node(name_2d.to_owned(), node);
+}
 
-        graph.add_slot_edge(
-            graph.input_node().id,
-            core_2d::graph::input::VIEW_ENTITY,
-            name_2d.to_owned(),
-            "view",
-        );
-
-        graph.add_node_edge(
-            name_2d.to_owned(),
-            core_2d::graph::node::END_MAIN_PASS_POST_PROCESSING,
-        );
+/// Whether the graph has the node `other` to order the node `name` against, warning if it does not.
+fn has_node(graph: &RenderGraph, graph_name: &str, name: &str, other: &str) -> bool {
+    if graph.get_node_state(other.to_owned()).is_ok() {
+        return true;
     }
+
+    warn!(
+        "The {graph_name:?} render graph has no node {other:?}, so {name:?} is not ordered against it. \
+        If the plugin adding {other:?} is used, it must be added before the plugin adding {name:?}."
+    );
+
+    false
 }
@@ -0,0 +1,24 @@
+//! Fixtures shared between tests which run apps without rendering.
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+
+/// An app with only a [`Time`] resource, and the instant its time starts at.
+pub fn app() -> (App, Instant) {
+    let mut app = App::new();
+    app.init_resource::<Time>();
+
+    let start = Instant::now();
+    app.world.resource_mut::<Time>().update_with_instant(start);
+
+    (app, start)
+}
+
+/// Runs a frame at the given amount of seconds after the start.
+pub fn frame(app: &mut App, start: Instant, seconds: f32) {
+    app.world
+        .resource_mut::<Time>()
+        .update_with_instant(start + Duration::from_secs_f32(seconds));
+    app.update();
+}
@@ -0,0 +1,750 @@
+//! Tests of the CPU implementations of effects.
+//!
+//! Besides edge cases, the CPU implementations are compared against the golden images rendered on the GPU,
+//! using the unaffected golden image as input.
+
+use std::path::Path;
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::{CompressedImageFormats, ImageType},
+    },
+};
+use bevy_vfx_bag::{
+    cpu::{CpuEffect, CpuError, CpuLut},
+    post_processing::{
+        blur::{Blur, BlurKernel},
+        chromatic_aberration::ChromaticAberration,
+        color_grade::ColorGrade,
+        flip::Flip,
+        lens_distortion::LensDistortion,
+        lut::{Lut, LutSplit},
+        masks::Mask,
+        palette::{Dither, Palette},
+        pixelate::{Pixelate, PixelateShape},
+        wave::Wave,
+    },
+};
+
+/// Channels differing by at most this much are considered equal,
+/// since the GPU stores intermediate results in 8 bits and filters with less precision.
+const CHANNEL_TOLERANCE: u8 = 3;
+
+/// Like [`CHANNEL_TOLERANCE`], but for LUTs.
+/// GPUs may filter 8-bit LUTs in 8-bit precision, which shows after encoding dark colors as sRGB.
+const LUT_CHANNEL_TOLERANCE: u8 = 12;
+
+/// The fraction of pixels which may differ by more than [`CHANNEL_TOLERANCE`].
+const PIXEL_TOLERANCE: f32 = 0.01;
+
+fn image(width: u32, height: u32, format: TextureFormat, data: Vec<u8>) -> Image {
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+    )
+}
+
+/// A small image where every texel has a different color.
+fn distinct_texels() -> Image {
+    let data = (0..4u8 * 4)
+        .flat_map(|texel| [texel * 16, 255 - texel * 8, (texel % 4) * 60, 255])
+        .collect();
+
+    image(4, 4, TextureFormat::Rgba8UnormSrgb, data)
+}
+
+fn texel(image: &Image, x: u32, y: u32) -> [u8; 4] {
+    let index = ((x + y * image.texture_descriptor.size.width) * 4) as usize;
+    image.data[index..index + 4]
+        .try_into()
+        .expect("Texel has four channels")
+}
+
+fn lut_image(name: &str) -> Image {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("assets/luts")
+        .join(name);
+    let bytes = std::fs::read(&path).expect("Should be able to read LUT");
+
+    Image::from_buffer(
+        &bytes,
+        ImageType::Extension("png"),
+        CompressedImageFormats::NONE,
+        false,
+    )
+    .expect("Should be able to decode LUT")
+}
+
+fn golden(name: &str) -> Image {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden/images")
+        .join(format!("{name}.png"));
+    let golden = image::open(&path)
+        .expect("Should be able to open golden image")
+        .to_rgba8();
+
+    image(
+        golden.width(),
+        golden.height(),
+        TextureFormat::Rgba8UnormSrgb,
+        golden.into_raw(),
+    )
+}
+
+/// Applies the effect to the unaffected golden image, and compares the result to the golden image `name`.
+fn check_golden(name: &str, effect: &dyn CpuEffect, channel_tolerance: u8) {
+    let output = effect
+        .apply_cpu(&golden("none"))
+        .expect("Should be able to apply effect");
+    let expected = golden(name);
+
+    let differing = output
+        .data
+        .chunks_exact(4)
+        .zip(expected.data.chunks_exact(4))
+        .filter(|(a, b)| {
+            a.iter()
+                .zip(*b)
+                .any(|(a, b)| a.abs_diff(*b) > channel_tolerance)
+        })
+        .count();
+
+    let fraction = differing as f32 / (output.data.len() / 4) as f32;
+    assert!(
+        fraction <= PIXEL_TOLERANCE,
+        "{name}: {differing} pixels ({:.2}%) differ by more than {channel_tolerance}",
+        fraction * 100.
+    );
+}
+
+#[test]
+fn pixelate_with_block_size_below_one_uses_one() {
+    let input = distinct_texels();
+    let expected = Pixelate::new(1.0)
+        .apply_cpu(&input)
+        .expect("Should pixelate");
+
+    for block_size in [0.5, 0.0, -3.0] {
+        let output = Pixelate::new(block_size)
+            .apply_cpu(&input)
+            .expect("Should pixelate");
+
+        assert_eq!(output.data, expected.data, "block size {block_size}");
+    }
+}
+
+#[test]
+fn pixelate_blocks_have_one_color() {
+    let output = Pixelate::new(2.0)
+        .apply_cpu(&distinct_texels())
+        .expect("Should pixelate");
+
+    for (block_x, block_y) in [(0, 0), (2, 0), (0, 2), (2, 2)] {
+        let color = texel(&output, block_x, block_y);
+
+        for (x, y) in [(1, 0), (0, 1), (1, 1)] {
+            assert_eq!(texel(&output, block_x + x, block_y + y), color);
+        }
+    }
+}
+
+#[test]
+fn pixelate_with_block_size_one_changes_nothing() {
+    let input = distinct_texels();
+    let output = Pixelate::new(1.0)
+        .apply_cpu(&input)
+        .expect("Should pixelate");
+
+    assert_eq!(output.data, input.data);
+}
+
+#[test]
+fn pixelate_samples_block_centers() {
+    let input = distinct_texels();
+    let output = Pixelate::new(3.0)
+        .apply_cpu(&input)
+        .expect("Should pixelate");
+
+    // The block from 0 to 3 has its center in texel 1, the next block is clamped to the edge.
+    for (x, y) in [(0, 0), (2, 2), (0, 2)] {
+        assert_eq!(texel(&output, x, y), texel(&input, 1, 1));
+    }
+    assert_eq!(texel(&output, 3, 3), texel(&input, 3, 3));
+}
+
+#[test]
+fn pixelate_blocks_start_at_anchor() {
+    let input = distinct_texels();
+    let output = Pixelate {
+        anchor: Vec2::new(1.0, 1.0),
+        ..Pixelate::with_size(2.0, 4.0)
+    }
+    .apply_cpu(&input)
+    .expect("Should pixelate");
+
+    // Blocks are two texels wide and four tall, starting from the texel at one, one.
+    for y in 1..4 {
+        assert_eq!(texel(&output, 1, y), texel(&output, 2, 1));
+    }
+    assert_ne!(texel(&output, 0, 1), texel(&output, 1, 1));
+    assert_ne!(texel(&output, 1, 0), texel(&output, 1, 1));
+}
+
+#[test]
+fn flip_horizontal_vertical_rotates_half_a_turn() {
+    let input = distinct_texels();
+    let output = Flip::HorizontalVertical
+        .apply_cpu(&input)
+        .expect("Should flip");
+
+    for y in 0..4 {
+        for x in 0..4 {
+            assert_eq!(texel(&output, x, y), texel(&input, 3 - x, 3 - y));
+        }
+    }
+}
+
+#[test]
+fn flip_none_is_identity() {
+    let input = distinct_texels();
+    let output = Flip::None.apply_cpu(&input).expect("Should flip");
+
+    assert_eq!(output.data, input.data);
+}
+
+#[test]
+fn blur_without_amount_is_identity() {
+    let input = distinct_texels();
+
+    for kernel in [
+        BlurKernel::Box3x3,
+        BlurKernel::Gaussian {
+            sigma: 0.5,
+            taps: 4,
+        },
+        BlurKernel::Kawase,
+    ] {
+        let blur = Blur {
+            amount: 0.0,
+            kernel_radius: 0.2,
+            kernel,
+        };
+        let output = blur.apply_cpu(&input).expect("Should blur");
+
+        assert_eq!(output.data, input.data, "{kernel:?}");
+    }
+}
+
+#[test]
+fn blur_with_no_taps_is_identity() {
+    let input = distinct_texels();
+    let blur = Blur {
+        amount: 1.0,
+        kernel_radius: 0.0,
+        kernel: BlurKernel::Gaussian {
+            sigma: 0.0,
+            taps: 0,
+        },
+    };
+    let output = blur.apply_cpu(&input).expect("Should blur");
+
+    assert_eq!(output.data, input.data);
+}
+
+#[test]
+fn blur_taps_are_clamped() {
+    let input = distinct_texels();
+    let blur = |taps| Blur {
+        amount: 1.0,
+        kernel_radius: 0.5,
+        kernel: BlurKernel::Gaussian { sigma: 0.5, taps },
+    };
+
+    let clamped = blur(64).apply_cpu(&input).expect("Should blur");
+    let output = blur(1000).apply_cpu(&input).expect("Should blur");
+
+    assert_eq!(output.data, clamped.data);
+}
+
+#[test]
+fn still_effects_are_identity() {
+    let input = distinct_texels();
+
+    let effects: [&dyn CpuEffect; 5] = [
+        &ChromaticAberration {
+            magnitude_r: 0.0,
+            magnitude_g: 0.0,
+            magnitude_b: 0.0,
+            ..default()
+        },
+        &ChromaticAberration::radial(0.0),
+        &LensDistortion {
+            k1: 0.0,
+            k2: 0.0,
+            chromatic_aberration: 0.0,
+            ..default()
+        },
+        &Mask {
+            fade: 1.0,
+            ..Mask::vignette()
+        },
+        &Wave {
+            waves_x: 2.0,
+            waves_y: 2.0,
+            speed_x: 1.0,
+            speed_y: 1.0,
+            amplitude_x: 0.0,
+            amplitude_y: 0.0,
+        },
+    ];
+
+    for effect in effects {
+        let output = effect.apply_cpu(&input).expect("Should apply effect");
+        assert_eq!(output.data, input.data);
+    }
+}
+
+#[test]
+fn lens_distortion_zoomed_to_fill_covers_the_view() {
+    let input = image(
+        16,
+        9,
+        TextureFormat::Rgba8UnormSrgb,
+        [255, 255, 255, 255].repeat(16 * 9),
+    );
+
+    for distortion in [
+        LensDistortion::barrel(0.4),
+        LensDistortion::pincushion(0.2),
+        LensDistortion {
+            k1: 0.5,
+            k2: -0.02,
+            chromatic_aberration: -0.05,
+            center: Vec2::new(0.3, 0.7),
+            ..default()
+        },
+    ] {
+        let output = distortion.apply_cpu(&input).expect("Should distort");
+        assert_eq!(output.data, input.data, "{distortion:?}");
+
+        let uncovered = LensDistortion {
+            zoom_to_fill: false,
+            ..distortion
+        }
+        .apply_cpu(&input)
+        .expect("Should distort");
+        let barrel = distortion.k1 > 0.0;
+        assert_eq!(
+            uncovered.data.contains(&0),
+            barrel,
+            "{distortion:?} without zooming"
+        );
+    }
+}
+
+#[test]
+fn radial_chromatic_aberration_grows_from_the_center() {
+    let input = distinct_texels();
+    let output = ChromaticAberration {
+        center: Vec2::new(0.375, 0.375),
+        ..ChromaticAberration::radial(0.5)
+    }
+    .apply_cpu(&input)
+    .expect("Should offset channels");
+
+    // The center of texel one, one stays in place, while the texels around it are offset.
+    assert_eq!(texel(&output, 1, 1), texel(&input, 1, 1));
+    assert_ne!(texel(&output, 3, 3), texel(&input, 3, 3));
+}
+
+#[test]
+fn wave_moves_over_time() {
+    let input = distinct_texels();
+    let wave = Wave {
+        waves_x: 1.0,
+        speed_x: 1.0,
+        amplitude_x: 0.1,
+        ..default()
+    };
+
+    let start = wave.apply_cpu_at(&input, 0.0).expect("Should wave");
+    let later = wave.apply_cpu_at(&input, 0.5).expect("Should wave");
+    let full_period = wave.apply_cpu_at(&input, 2.0).expect("Should wave");
+
+    assert_eq!(
+        wave.apply_cpu(&input).expect("Should wave").data,
+        start.data
+    );
+    assert_ne!(later.data, start.data);
+    assert_eq!(full_period.data, start.data);
+}
+
+#[test]
+fn neutral_lut_is_identity() {
+    // Linear input, since the LUT is only 8-bit which would show in dark sRGB colors.
+    let input = image(4, 4, TextureFormat::Rgba8Unorm, distinct_texels().data);
+    let lut = Lut::neutral();
+    let neutral = lut_image("neutral.png");
+
+    let output = CpuLut {
+        lut: &lut,
+        texture: &neutral,
+        cross_fade_texture: None,
+    }
+    .apply_cpu(&input)
+    .expect("Should grade");
+
+    for (a, b) in output.data.iter().zip(&input.data) {
+        assert!(a.abs_diff(*b) <= 1, "{a} differs from {b}");
+    }
+}
+
+#[test]
+fn lut_split_keeps_one_side() {
+    let input = distinct_texels();
+    let arctic = lut_image("arctic.png");
+    let mut lut = Lut::arctic();
+    lut.split = Some(LutSplit::default());
+
+    let output = CpuLut {
+        lut: &lut,
+        texture: &arctic,
+        cross_fade_texture: None,
+    }
+    .apply_cpu(&input)
+    .expect("Should grade");
+
+    for y in 0..4 {
+        for x in 0..2 {
+            assert_eq!(texel(&output, x, y), texel(&input, x, y));
+        }
+        for x in 2..4 {
+            assert_ne!(texel(&output, x, y), texel(&input, x, y));
+        }
+    }
+}
+
+#[test]
+fn default_color_grade_is_identity() {
+    let input = distinct_texels();
+
+    let output = ColorGrade::default()
+        .apply_cpu(&input)
+        .expect("Should grade");
+
+    for (a, b) in output.data.iter().zip(&input.data) {
+        assert!(a.abs_diff(*b) <= 1, "{a} differs from {b}");
+    }
+}
+
+#[test]
+fn baked_color_grade_matches_grade() {
+    // Linear input, since interpolating between the entries of the LUT would show in dark sRGB colors.
+    let input = image(4, 4, TextureFormat::Rgba8Unorm, distinct_texels().data);
+    let grade = ColorGrade {
+        contrast: 1.3,
+        hue_shift: 90.0,
+        temperature: -0.5,
+        gain: Vec3::new(0.9, 1.0, 1.1),
+        ..default()
+    };
+
+    let baked = grade.bake_to_lut(33);
+    let lut = Lut::new(Handle::default());
+    let output = CpuLut {
+        lut: &lut,
+        texture: &baked,
+        cross_fade_texture: None,
+    }
+    .apply_cpu(&input)
+    .expect("Should grade via the LUT");
+
+    let expected = grade.apply_cpu(&input).expect("Should grade");
+    assert_ne!(expected.data, input.data);
+
+    for (a, b) in output.data.iter().zip(&expected.data) {
+        assert!(a.abs_diff(*b) <= 3, "{a} differs from {b}");
+    }
+}
+
+#[test]
+fn palette_maps_to_nearest_color() {
+    let input = distinct_texels();
+    let palette = Palette::new([Color::BLACK, Color::WHITE]);
+
+    let output = palette.apply_cpu(&input).expect("Should quantize");
+
+    for (texel, original) in output.data.chunks_exact(4).zip(input.data.chunks_exact(4)) {
+        let bright = original[..3].iter().map(|&c| c as u32).sum::<u32>() > 3 * 128;
+        let expected = if bright { [255; 4] } else { [0, 0, 0, 255] };
+        assert_eq!(texel, expected);
+    }
+}
+
+#[test]
+fn dithering_gray_gives_half_white() {
+    let input = image(
+        16,
+        16,
+        TextureFormat::Rgba8UnormSrgb,
+        [128, 128, 128, 255].repeat(16 * 16),
+    );
+
+    for dither in [
+        Dither::Bayer2x2,
+        Dither::Bayer4x4,
+        Dither::Bayer8x8,
+        Dither::BlueNoise,
+    ] {
+        let palette = Palette {
+            dither,
+            dither_strength: 1.0,
+            ..Palette::new([Color::BLACK, Color::WHITE])
+        };
+
+        let output = palette.apply_cpu(&input).expect("Should dither");
+        let white = output.data.chunks_exact(4).filter(|t| t[0] == 255).count();
+
+        assert!(
+            (124..=132).contains(&white),
+            "{dither:?}: {white} of 256 texels are white"
+        );
+    }
+}
+
+#[test]
+fn dithering_follows_pixelate_blocks() {
+    let input = image(
+        8,
+        8,
+        TextureFormat::Rgba8UnormSrgb,
+        [128, 128, 128, 255].repeat(8 * 8),
+    );
+    let palette = Palette {
+        dither_strength: 1.0,
+        ..Palette::new([Color::BLACK, Color::WHITE])
+            .with_dither(Dither::Bayer2x2)
+            .with_block_size(2.0)
+    };
+
+    let output = palette.apply_cpu(&input).expect("Should dither");
+
+    for block_y in 0..4 {
+        for block_x in 0..4 {
+            let first = texel(&output, block_x * 2, block_y * 2);
+            for (x, y) in [(1, 0), (0, 1), (1, 1)] {
+                assert_eq!(texel(&output, block_x * 2 + x, block_y * 2 + y), first);
+            }
+        }
+    }
+}
+
+#[test]
+fn dithering_follows_anchored_pixelate_blocks() {
+    let input = image(
+        12,
+        8,
+        TextureFormat::Rgba8UnormSrgb,
+        [128, 128, 128, 255].repeat(12 * 8),
+    );
+    let pixelate = Pixelate {
+        anchor: Vec2::new(1.0, 1.0),
+        ..Pixelate::with_size(3.0, 2.0)
+    };
+    let palette = Palette {
+        dither_strength: 1.0,
+        ..Palette::new([Color::BLACK, Color::WHITE])
+            .with_dither(Dither::Bayer2x2)
+            .with_blocks_of(&pixelate)
+    };
+
+    let output = palette.apply_cpu(&input).expect("Should dither");
+
+    // Texels in the same block, including the partial blocks before the anchor, are equal.
+    let block = |x: u32, y: u32| {
+        (
+            ((x as f32 + 0.5 - 1.0) / 3.0).floor() as i32,
+            ((y as f32 + 0.5 - 1.0) / 2.0).floor() as i32,
+        )
+    };
+    for y in 0..8 {
+        for x in 0..12 {
+            for (other_x, other_y) in [(x + 1, y), (x, y + 1)] {
+                if other_x < 12 && other_y < 8 && block(x, y) == block(other_x, other_y) {
+                    assert_eq!(texel(&output, x, y), texel(&output, other_x, other_y));
+                }
+            }
+        }
+    }
+
+    // Neighbouring blocks get different thresholds.
+    assert_ne!(texel(&output, 1, 1), texel(&output, 4, 1));
+}
+
+#[test]
+fn unsupported_images_are_errors() {
+    let gray = image(2, 2, TextureFormat::R8Unorm, vec![0; 4]);
+    assert_eq!(
+        Flip::Horizontal.apply_cpu(&gray).unwrap_err(),
+        CpuError::UnsupportedFormat(TextureFormat::R8Unorm)
+    );
+
+    let not_a_lut = image(8, 4, TextureFormat::Rgba8Unorm, vec![0; 8 * 4 * 4]);
+    let lut = Lut::neutral();
+    let error = CpuLut {
+        lut: &lut,
+        texture: &not_a_lut,
+        cross_fade_texture: None,
+    }
+    .apply_cpu(&distinct_texels())
+    .unwrap_err();
+
+    assert_eq!(
+        error,
+        CpuError::InvalidLutSize {
+            width: 8,
+            height: 4
+        }
+    );
+}
+
+#[test]
+fn matches_golden_images() {
+    let color_grade = ColorGrade {
+        exposure: 0.3,
+        contrast: 1.2,
+        saturation: 0.8,
+        vibrance: 0.3,
+        hue_shift: 30.0,
+        temperature: 0.3,
+        lift: Vec3::new(0.02, 0.0, 0.04),
+        gamma: Vec3::new(1.0, 1.1, 0.9),
+        ..default()
+    };
+
+    let wave = Wave {
+        waves_x: 3.,
+        amplitude_x: 0.02,
+        waves_y: 5.,
+        amplitude_y: 0.01,
+        ..default()
+    };
+
+    check_golden("blur-box", &Blur::default(), CHANNEL_TOLERANCE);
+    check_golden("blur-gaussian", &Blur::gaussian(), CHANNEL_TOLERANCE);
+    check_golden("blur-kawase", &Blur::kawase(), CHANNEL_TOLERANCE);
+    check_golden(
+        "chromatic-aberration",
+        &ChromaticAberration::default(),
+        CHANNEL_TOLERANCE,
+    );
+    check_golden(
+        "chromatic-aberration-radial",
+        &ChromaticAberration::radial(0.03),
+        CHANNEL_TOLERANCE,
+    );
+    check_golden("color-grade", &color_grade, CHANNEL_TOLERANCE);
+    check_golden("flip", &Flip::HorizontalVertical, CHANNEL_TOLERANCE);
+    check_golden(
+        "lens-distortion-barrel",
+        &LensDistortion {
+            k2: 0.05,
+            chromatic_aberration: 0.03,
+            ..LensDistortion::barrel(0.3)
+        },
+        CHANNEL_TOLERANCE,
+    );
+    check_golden(
+        "lens-distortion-pincushion",
+        &LensDistortion {
+            center: Vec2::new(0.4, 0.6),
+            zoom_to_fill: false,
+            ..LensDistortion::pincushion(0.2)
+        },
+        CHANNEL_TOLERANCE,
+    );
+    check_golden("masks-square", &Mask::square(), CHANNEL_TOLERANCE);
+    check_golden("masks-crt", &Mask::crt(), CHANNEL_TOLERANCE);
+    check_golden("masks-vignette", &Mask::vignette(), CHANNEL_TOLERANCE);
+    check_golden(
+        "palette-blue-noise",
+        &Palette::cga().with_dither(Dither::BlueNoise),
+        CHANNEL_TOLERANCE,
+    );
+    check_golden("pixelate", &Pixelate::new(8.0), CHANNEL_TOLERANCE);
+    check_golden(
+        "pixelate-averaged",
+        &Pixelate::with_size(12.0, 6.0).averaged(),
+        CHANNEL_TOLERANCE,
+    );
+    check_golden(
+        "pixelate-hexagon",
+        &Pixelate::new(10.0).with_shape(PixelateShape::Hexagon),
+        CHANNEL_TOLERANCE,
+    );
+    check_golden(
+        "pixelate-circle",
+        &Pixelate {
+            anchor: Vec2::new(3.0, 5.0),
+            ..Pixelate::new(8.0).with_shape(PixelateShape::Circle)
+        },
+        CHANNEL_TOLERANCE,
+    );
+    check_golden(
+        "pixelate-triangle",
+        &Pixelate::new(12.0).with_shape(PixelateShape::Triangle),
+        CHANNEL_TOLERANCE,
+    );
+    check_golden("wave", &wave, CHANNEL_TOLERANCE);
+}
+
+#[test]
+fn lut_matches_golden_images() {
+    let (neutral, arctic, sauna, slate) = (
+        lut_image("neutral.png"),
+        lut_image("arctic.png"),
+        lut_image("sauna.png"),
+        lut_image("slate.png"),
+    );
+
+    let lut = Lut::neutral();
+    check_golden(
+        "lut-neutral",
+        &CpuLut {
+            lut: &lut,
+            texture: &neutral,
+            cross_fade_texture: None,
+        },
+        LUT_CHANNEL_TOLERANCE,
+    );
+
+    let lut = Lut::arctic();
+    check_golden(
+        "lut-arctic",
+        &CpuLut {
+            lut: &lut,
+            texture: &arctic,
+            cross_fade_texture: None,
+        },
+        LUT_CHANNEL_TOLERANCE,
+    );
+
+    let lut = Lut::sauna().cross_fade(&Lut::slate(), 0.5);
+    check_golden(
+        "lut-cross-fade",
+        &CpuLut {
+            lut: &lut,
+            texture: &sauna,
+            cross_fade_texture: Some(&slate),
+        },
+        LUT_CHANNEL_TOLERANCE,
+    );
+}
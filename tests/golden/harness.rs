@@ -0,0 +1,453 @@
+//! Renders a fixed input image through post processing effects without a window,
+//! and compares the result against golden images.
+//!
+//! Set `VFX_BAG_BLESS=1` to write the rendered images as the new golden images.
+//! On a mismatch the rendered image and a diff image are written to the test temporary directory.
+
+use std::{
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    asset::AssetPlugin,
+    core::{FrameCountPlugin, TaskPoolPlugin, TypeRegistrationPlugin},
+    core_pipeline::{prepass::DepthPrepass, tonemapping::Tonemapping, CorePipelinePlugin},
+    ecs::world::EntityMut,
+    pbr::{MaterialPlugin, PbrPlugin},
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssets,
+        render_resource::{
+            AsBindGroup, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer,
+            ImageDataLayout, MapMode, ShaderRef, TextureDimension, TextureFormat, TextureUsages,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        texture::ImagePlugin,
+        view::Msaa,
+        Extract, RenderApp, RenderPlugin, RenderSet,
+    },
+    sprite::SpritePlugin,
+    time::{TimePlugin, TimeUpdateStrategy},
+    window::{ExitCondition, WindowPlugin},
+};
+use bevy_vfx_bag::BevyVfxBagPlugin;
+use image::{Rgba, RgbaImage};
+
+/// The width and height of the rendered images.
+const SIZE: u32 = 128;
+
+/// Channels differing by at most this much are considered equal,
+/// which allows for small differences between adapters.
+const CHANNEL_TOLERANCE: u8 = 4;
+
+/// The fraction of pixels which may differ by more than [`CHANNEL_TOLERANCE`].
+const PIXEL_TOLERANCE: f32 = 0.002;
+
+/// Rendering stops when two frames in a row are equal, but at least this many frames are rendered
+/// such that pipelines have been compiled and assets prepared.
+const MIN_FRAMES: u32 = 4;
+
+/// Rendering gives up after this many frames.
+const MAX_FRAMES: u32 = 60;
+
+/// What the camera looks at.
+#[derive(Clone, Copy)]
+pub enum Scene {
+    /// The input image, filling the view of a 2D camera.
+    Flat,
+
+    /// The input image on quads at several distances from a 3D camera with a depth prepass,
+    /// for effects which read depth.
+    Depth,
+}
+
+/// Renders the input image through the effects added by `add_effects` to the camera,
+/// and compares the result with the golden image `name`.
+pub fn check(name: &str, add_effects: impl FnOnce(&mut EntityMut)) {
+    check_scene(name, Scene::Flat, add_effects);
+}
+
+/// Like [`check`], but renders the given [`Scene`].
+pub fn check_scene(name: &str, scene: Scene, add_effects: impl FnOnce(&mut EntityMut)) {
+    let rendered = render(scene, add_effects);
+    let golden_path = golden_dir().join(format!("{name}.png"));
+
+    if std::env::var_os("VFX_BAG_BLESS").is_some() {
+        rendered
+            .save(&golden_path)
+            .expect("Should be able to write golden image");
+        return;
+    }
+
+    let golden = image::open(&golden_path)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Could not open golden image {}: {e}. Run with VFX_BAG_BLESS=1 to create it.",
+                golden_path.display()
+            )
+        })
+        .to_rgba8();
+
+    if let Err(message) = compare(&rendered, &golden) {
+        let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("golden");
+        std::fs::create_dir_all(&out_dir).expect("Should be able to create output directory");
+
+        let actual_path = out_dir.join(format!("{name}.actual.png"));
+        let diff_path = out_dir.join(format!("{name}.diff.png"));
+
+        rendered
+            .save(&actual_path)
+            .expect("Should be able to write rendered image");
+        if let Some(diff) = diff_image(&rendered, &golden) {
+            diff.save(&diff_path)
+                .expect("Should be able to write diff image");
+        }
+
+        panic!(
+            "{name} does not match its golden image: {message}.\nRendered: {}\nDiff: {}",
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/images")
+}
+
+/// A gradient with a checkerboard, some bright spots, and thin lines,
+/// such that blurring, shifting, and color changes are all visible.
+fn input_image() -> Image {
+    let mut data = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let u = x as f32 / SIZE as f32;
+            let v = y as f32 / SIZE as f32;
+
+            let checker = ((x / 16) + (y / 16)) % 2 == 0;
+            let spot = ((u - 0.7).powi(2) + (v - 0.3).powi(2)).sqrt() < 0.08;
+            let line = x % 32 == 5 || y % 32 == 21;
+
+            let color = if spot {
+                [255, 250, 240]
+            } else if line {
+                [20, 20, 30]
+            } else {
+                let shade = if checker { 1.0 } else { 0.6 };
+                [
+                    (u * 200. * shade) as u8,
+                    (v * 200. * shade) as u8,
+                    ((1. - u) * 160. * shade) as u8,
+                ]
+            };
+
+            data.extend_from_slice(&[color[0], color[1], color[2], 255]);
+        }
+    }
+
+    Image::new(
+        extent(),
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn extent() -> Extent3d {
+    Extent3d {
+        width: SIZE,
+        height: SIZE,
+        depth_or_array_layers: 1,
+    }
+}
+
+/// The most recent frame read back from the render target.
+#[derive(Resource, Clone, Default)]
+struct Readback(Arc<Mutex<Option<Vec<u8>>>>);
+
+#[derive(Resource, Clone)]
+struct RenderTargetImage(Handle<Image>);
+
+fn render(scene: Scene, add_effects: impl FnOnce(&mut EntityMut)) -> RgbaImage {
+    let mut app = App::new();
+
+    app.add_plugin(TaskPoolPlugin::default())
+        .add_plugin(TypeRegistrationPlugin)
+        .add_plugin(FrameCountPlugin)
+        .add_plugin(TimePlugin)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(WindowPlugin {
+            primary_window: None,
+            exit_condition: ExitCondition::DontExit,
+            close_when_requested: false,
+        })
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(RenderPlugin::default())
+        .add_plugin(ImagePlugin::default_nearest())
+        .add_plugin(CorePipelinePlugin)
+        .add_plugin(SpritePlugin);
+
+    if let Scene::Depth = scene {
+        app.add_plugin(PbrPlugin::default())
+            .add_plugin(MaterialPlugin::<InputMaterial>::default());
+
+        app.world.resource_mut::<Assets<Shader>>().set_untracked(
+            INPUT_SHADER_HANDLE,
+            Shader::from_wgsl(include_str!("input.wgsl")),
+        );
+    }
+
+    app.add_plugin(BevyVfxBagPlugin)
+        // Effects animated over time should look the same in every run.
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Default::default()))
+        .insert_resource(Msaa::Off);
+
+    let mut target = Image::new_fill(
+        extent(),
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    target.texture_descriptor.usage =
+        TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_SRC;
+
+    let mut images = app.world.resource_mut::<Assets<Image>>();
+    let target = images.add(target);
+    let input = images.add(input_image());
+
+    let readback = Readback::default();
+    app.insert_resource(RenderTargetImage(target.clone()));
+
+    app.sub_app_mut(RenderApp)
+        .insert_resource(readback.clone())
+        .add_system(extract_render_target_image.in_schedule(ExtractSchedule))
+        .add_system(read_render_target.in_set(RenderSet::Cleanup));
+
+    let camera = Camera {
+        target: RenderTarget::Image(target),
+        ..default()
+    };
+
+    let camera = match scene {
+        Scene::Flat => spawn_flat_scene(&mut app.world, input, camera),
+        Scene::Depth => spawn_depth_scene(&mut app.world, input, camera),
+    };
+    add_effects(&mut app.world.entity_mut(camera));
+
+    let mut previous = None;
+    for frame in 0..MAX_FRAMES {
+        app.update();
+
+        let current = readback
+            .0
+            .lock()
+            .expect("Readback should not be poisoned")
+            .take();
+
+        let settled = frame >= MIN_FRAMES && current == previous;
+        match current {
+            Some(data) if settled => {
+                return RgbaImage::from_raw(SIZE, SIZE, data).expect("Readback has the right size");
+            }
+            current => previous = current,
+        }
+    }
+
+    panic!("The rendered image did not settle within {MAX_FRAMES} frames");
+}
+
+fn spawn_flat_scene(world: &mut World, input: Handle<Image>, camera: Camera) -> Entity {
+    world.spawn(SpriteBundle {
+        texture: input,
+        sprite: Sprite {
+            custom_size: Some(Vec2::splat(SIZE as f32)),
+            ..default()
+        },
+        ..default()
+    });
+
+    world
+        .spawn(Camera2dBundle {
+            camera,
+            tonemapping: Tonemapping::None,
+            ..default()
+        })
+        .id()
+}
+
+const INPUT_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 0x5a8c_31e0_94d7_4b2f);
+
+/// Shows the input image on a mesh.
+///
+/// The standard material is not used, since its shader does not compile on all backends.
+#[derive(AsBindGroup, TypeUuid, Clone)]
+#[uuid = "a4b1a3f6-2d0e-4c55-9b7e-3f0c1d2e8a61"]
+struct InputMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    texture: Handle<Image>,
+}
+
+impl Material for InputMaterial {
+    fn fragment_shader() -> ShaderRef {
+        INPUT_SHADER_HANDLE.typed().into()
+    }
+}
+
+fn spawn_depth_scene(world: &mut World, input: Handle<Image>, camera: Camera) -> Entity {
+    let material = world
+        .resource_mut::<Assets<InputMaterial>>()
+        .add(InputMaterial { texture: input });
+
+    // The farthest quad fills the view, the others cover parts of it closer to the camera.
+    for (size, translation) in [
+        (17.0, Vec3::new(0.0, 0.0, -20.0)),
+        (3.0, Vec3::new(-1.0, 0.5, -6.0)),
+        (0.8, Vec3::new(0.3, -0.25, -2.0)),
+    ] {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(shape::Quad::new(Vec2::splat(size)).into());
+
+        world.spawn(MaterialMeshBundle {
+            mesh,
+            material: material.clone(),
+            transform: Transform::from_translation(translation),
+            ..default()
+        });
+    }
+
+    world
+        .spawn((
+            Camera3dBundle {
+                camera,
+                tonemapping: Tonemapping::None,
+                ..default()
+            },
+            DepthPrepass,
+        ))
+        .id()
+}
+
+fn extract_render_target_image(mut commands: Commands, target: Extract<Res<RenderTargetImage>>) {
+    commands.insert_resource(target.clone());
+}
+
+/// Copies the render target to a buffer and waits for it to be readable.
+fn read_render_target(
+    target: Res<RenderTargetImage>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    readback: Res<Readback>,
+) {
+    let Some(gpu_image) = images.get(&target.0) else {
+        return;
+    };
+
+    // Rows in a texture to buffer copy must be aligned.
+    let row_bytes = SIZE * 4;
+    let padded_row_bytes = row_bytes.next_multiple_of(256);
+
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("Golden Readback Buffer"),
+        size: (padded_row_bytes * SIZE) as u64,
+        usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = render_device.create_command_encoder(&Default::default());
+    encoder.copy_texture_to_buffer(
+        gpu_image.texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(padded_row_bytes),
+                rows_per_image: None,
+            },
+        },
+        extent(),
+    );
+    render_queue.submit([encoder.finish()]);
+
+    let slice = buffer.slice(..);
+    slice.map_async(MapMode::Read, |result| {
+        result.expect("Should be able to map readback buffer")
+    });
+    render_device.poll(wgpu::Maintain::Wait);
+
+    let data = slice
+        .get_mapped_range()
+        .chunks(padded_row_bytes as usize)
+        .flat_map(|row| &row[..row_bytes as usize])
+        .copied()
+        .collect();
+
+    *readback.0.lock().expect("Readback should not be poisoned") = Some(data);
+}
+
+fn compare(rendered: &RgbaImage, golden: &RgbaImage) -> Result<(), String> {
+    if rendered.dimensions() != golden.dimensions() {
+        return Err(format!(
+            "rendered size {:?} differs from golden size {:?}",
+            rendered.dimensions(),
+            golden.dimensions()
+        ));
+    }
+
+    let differing = rendered
+        .pixels()
+        .zip(golden.pixels())
+        .filter(|(a, b)| max_channel_difference(a, b) > CHANNEL_TOLERANCE)
+        .count();
+
+    let fraction = differing as f32 / (rendered.width() * rendered.height()) as f32;
+    if fraction > PIXEL_TOLERANCE {
+        return Err(format!(
+            "{differing} pixels ({:.2}%) differ by more than {CHANNEL_TOLERANCE}",
+            fraction * 100.
+        ));
+    }
+
+    Ok(())
+}
+
+fn max_channel_difference(a: &Rgba<u8>, b: &Rgba<u8>) -> u8 {
+    a.0.iter()
+        .zip(b.0.iter())
+        .map(|(a, b)| a.abs_diff(*b))
+        .max()
+        .unwrap_or_default()
+}
+
+/// Differing pixels are red, brighter for larger differences, on top of a dimmed golden image.
+fn diff_image(rendered: &RgbaImage, golden: &RgbaImage) -> Option<RgbaImage> {
+    if rendered.dimensions() != golden.dimensions() {
+        return None;
+    }
+
+    Some(RgbaImage::from_fn(
+        golden.width(),
+        golden.height(),
+        |x, y| {
+            let a = rendered.get_pixel(x, y);
+            let b = golden.get_pixel(x, y);
+
+            let difference = max_channel_difference(a, b);
+            if difference > CHANNEL_TOLERANCE {
+                Rgba([128 + difference / 2, 0, 0, 255])
+            } else {
+                let gray = (b.0[0] as u16 + b.0[1] as u16 + b.0[2] as u16) / 12;
+                Rgba([gray as u8, gray as u8, gray as u8, 255])
+            }
+        },
+    ))
+}
@@ -0,0 +1,13 @@
+// Shows the input image on a mesh, without lighting.
+
+@group(1) @binding(0)
+var input_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var input_sampler: sampler;
+
+@fragment
+fn fragment(
+    #import bevy_pbr::mesh_vertex_output
+) -> @location(0) vec4<f32> {
+    return textureSample(input_texture, input_sampler, uv);
+}
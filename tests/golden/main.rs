@@ -0,0 +1,404 @@
+//! Golden image tests for the post processing effects.
+//!
+//! See the harness module for how images are rendered and compared.
+mod harness;
+
+use bevy::{prelude::*, render::camera::Viewport};
+use bevy_vfx_bag::post_processing::{
+    bloom::Bloom,
+    blur::Blur,
+    chromatic_aberration::ChromaticAberration,
+    color_grade::ColorGrade,
+    crt::Crt,
+    depth_fog::DepthFog,
+    depth_of_field::DepthOfField,
+    effect_mask::{EffectMask, PostProcessingMask},
+    film_grain::FilmGrain,
+    flip::Flip,
+    lens_distortion::LensDistortion,
+    lut::Lut,
+    masks::Mask,
+    palette::{Dither, Palette},
+    pixelate::{Pixelate, PixelateShape},
+    raindrops::Raindrops,
+    transition::{Transition, TransitionVariant},
+    wave::Wave,
+    EffectStack, PostProcessingOrder,
+};
+
+use harness::{check, check_scene, Scene};
+
+#[test]
+fn none() {
+    check("none", |_| {});
+}
+
+#[test]
+fn bloom() {
+    check("bloom", |camera| {
+        camera.insert(Bloom {
+            threshold: 0.5,
+            intensity: 4.0,
+            ..default()
+        });
+    });
+}
+
+#[test]
+fn blur_box() {
+    check("blur-box", |camera| {
+        camera.insert(Blur::default());
+    });
+}
+
+#[test]
+fn blur_gaussian() {
+    check("blur-gaussian", |camera| {
+        camera.insert(Blur::gaussian());
+    });
+}
+
+#[test]
+fn blur_kawase() {
+    check("blur-kawase", |camera| {
+        camera.insert(Blur::kawase());
+    });
+}
+
+#[test]
+fn chromatic_aberration() {
+    check("chromatic-aberration", |camera| {
+        camera.insert(ChromaticAberration::default());
+    });
+}
+
+#[test]
+fn chromatic_aberration_radial() {
+    check("chromatic-aberration-radial", |camera| {
+        camera.insert(ChromaticAberration::radial(0.03));
+    });
+}
+
+#[test]
+fn color_grade() {
+    check("color-grade", |camera| {
+        camera.insert(ColorGrade {
+            exposure: 0.3,
+            contrast: 1.2,
+            saturation: 0.8,
+            vibrance: 0.3,
+            hue_shift: 30.0,
+            temperature: 0.3,
+            lift: Vec3::new(0.02, 0.0, 0.04),
+            gamma: Vec3::new(1.0, 1.1, 0.9),
+            ..default()
+        });
+    });
+}
+
+#[test]
+fn crt() {
+    check("crt", |camera| {
+        camera.insert(Crt {
+            lines: 32.0,
+            ..Crt::arcade()
+        });
+    });
+}
+
+#[test]
+#[ignore = "needs a golden image rendered on a backend which can copy the depth prepass, which OpenGL cannot"]
+fn depth_fog() {
+    check_scene("depth-fog", Scene::Depth, |camera| {
+        camera.insert(DepthFog {
+            color: Color::rgba(0.6, 0.65, 0.7, 1.0),
+            start: 3.0,
+            density: 0.1,
+        });
+    });
+}
+
+#[test]
+#[ignore = "needs a golden image rendered on a backend which can copy the depth prepass, which OpenGL cannot"]
+fn depth_of_field() {
+    check_scene("depth-of-field", Scene::Depth, |camera| {
+        camera.insert(DepthOfField {
+            focal_distance: 6.0,
+            ..default()
+        });
+    });
+}
+
+#[test]
+fn film_grain() {
+    check("film-grain", |camera| {
+        camera.insert(FilmGrain {
+            intensity: 0.3,
+            size: 20.0,
+            colored: true,
+            // Keep the grain still, since time passes during the test.
+            frame_rate: 0.0,
+            ..default()
+        });
+    });
+}
+
+#[test]
+fn flip() {
+    check("flip", |camera| {
+        camera.insert(Flip::HorizontalVertical);
+    });
+}
+
+#[test]
+fn lens_distortion_barrel() {
+    check("lens-distortion-barrel", |camera| {
+        camera.insert(LensDistortion {
+            k2: 0.05,
+            chromatic_aberration: 0.03,
+            ..LensDistortion::barrel(0.3)
+        });
+    });
+}
+
+#[test]
+fn lens_distortion_pincushion() {
+    check("lens-distortion-pincushion", |camera| {
+        camera.insert(LensDistortion {
+            center: Vec2::new(0.4, 0.6),
+            zoom_to_fill: false,
+            ..LensDistortion::pincushion(0.2)
+        });
+    });
+}
+
+#[test]
+fn lut_neutral() {
+    check("lut-neutral", |camera| {
+        camera.insert(Lut::neutral());
+    });
+}
+
+#[test]
+fn lut_arctic() {
+    check("lut-arctic", |camera| {
+        camera.insert(Lut::arctic());
+    });
+}
+
+#[test]
+fn lut_cross_fade() {
+    check("lut-cross-fade", |camera| {
+        camera.insert(Lut::sauna().cross_fade(&Lut::slate(), 0.5));
+    });
+}
+
+#[test]
+fn masks_square() {
+    check("masks-square", |camera| {
+        camera.insert(Mask::square());
+    });
+}
+
+#[test]
+fn masks_crt() {
+    check("masks-crt", |camera| {
+        camera.insert(Mask::crt());
+    });
+}
+
+#[test]
+fn masks_vignette() {
+    check("masks-vignette", |camera| {
+        camera.insert(Mask::vignette());
+    });
+}
+
+#[test]
+fn palette_blue_noise() {
+    check("palette-blue-noise", |camera| {
+        camera.insert(Palette::cga().with_dither(Dither::BlueNoise));
+    });
+}
+
+#[test]
+fn palette_pixelated() {
+    check("palette-pixelated", |camera| {
+        let pixelate = Pixelate {
+            anchor: Vec2::new(2.0, 1.0),
+            ..Pixelate::with_size(6.0, 4.0)
+        };
+
+        camera.insert((
+            Palette::pico8()
+                .with_dither(Dither::Bayer4x4)
+                .with_blocks_of(&pixelate),
+            pixelate,
+        ));
+    });
+}
+
+#[test]
+fn pixelate() {
+    check("pixelate", |camera| {
+        camera.insert(Pixelate::new(8.0));
+    });
+}
+
+#[test]
+fn pixelate_averaged() {
+    check("pixelate-averaged", |camera| {
+        camera.insert(Pixelate::with_size(12.0, 6.0).averaged());
+    });
+}
+
+#[test]
+fn pixelate_hexagon() {
+    check("pixelate-hexagon", |camera| {
+        camera.insert(Pixelate::new(10.0).with_shape(PixelateShape::Hexagon));
+    });
+}
+
+#[test]
+fn pixelate_circle() {
+    check("pixelate-circle", |camera| {
+        camera.insert(Pixelate {
+            anchor: Vec2::new(3.0, 5.0),
+            ..Pixelate::new(8.0).with_shape(PixelateShape::Circle)
+        });
+    });
+}
+
+#[test]
+fn pixelate_triangle() {
+    check("pixelate-triangle", |camera| {
+        camera.insert(Pixelate::new(12.0).with_shape(PixelateShape::Triangle));
+    });
+}
+
+#[test]
+#[ignore = "needs a golden image rendered with the raindrops texture"]
+fn raindrops() {
+    check("raindrops", |camera| {
+        camera.insert(Raindrops::default());
+    });
+}
+
+#[test]
+fn transition_dissolve() {
+    check("transition-dissolve", |camera| {
+        camera.insert(Transition {
+            progress: 0.5,
+            ..Transition::dissolve()
+        });
+    });
+}
+
+#[test]
+fn transition_fade() {
+    check("transition-fade", |camera| {
+        camera.insert(Transition {
+            progress: 0.5,
+            ..Transition::fade().with_color(Color::WHITE)
+        });
+    });
+}
+
+#[test]
+fn transition_iris() {
+    check("transition-iris", |camera| {
+        camera.insert(Transition {
+            progress: 0.5,
+            ..Transition::iris()
+        });
+    });
+}
+
+#[test]
+fn transition_pixelate_dissolve() {
+    check("transition-pixelate-dissolve", |camera| {
+        camera.insert(Transition {
+            progress: 0.5,
+            color: Color::WHITE,
+            variant: TransitionVariant::PixelateDissolve {
+                max_block_size: 16.0,
+            },
+            ..default()
+        });
+    });
+}
+
+#[test]
+fn transition_wipe() {
+    check("transition-wipe", |camera| {
+        camera.insert(Transition {
+            progress: 0.5,
+            softness: 0.1,
+            ..Transition::wipe()
+        });
+    });
+}
+
+#[test]
+fn wave() {
+    check("wave", |camera| {
+        camera.insert(Wave {
+            waves_x: 3.,
+            amplitude_x: 0.02,
+            waves_y: 5.,
+            amplitude_y: 0.01,
+            ..default()
+        });
+    });
+}
+
+#[test]
+fn ordering() {
+    check("ordering", |camera| {
+        camera
+            .insert(Flip::Horizontal.order(1.))
+            .insert(Pixelate::new(8.0).order(0.));
+    });
+}
+
+#[test]
+fn effect_stack() {
+    check("effect-stack", |camera| {
+        camera
+            .insert(
+                EffectStack::new()
+                    .with(Blur::default(), 0.)
+                    .with(Blur::default(), 2.),
+            )
+            .insert(Pixelate::new(8.0).order(1.));
+    });
+}
+
+#[test]
+fn effect_mask() {
+    check("effect-mask", |camera| {
+        camera.insert(
+            Pixelate::new(8.0)
+                .masked(EffectMask::circle(Vec2::new(0.5, 0.5), 0.25).with_feather(0.05)),
+        );
+    });
+}
+
+#[test]
+fn effect_mask_viewport() {
+    check("effect-mask-viewport", |camera| {
+        camera
+            .get_mut::<Camera>()
+            .expect("Camera should have a camera component")
+            .viewport = Some(Viewport {
+            physical_position: UVec2::new(32, 16),
+            physical_size: UVec2::new(64, 96),
+            ..default()
+        });
+
+        camera.insert(
+            Pixelate::new(8.0)
+                .masked(EffectMask::circle(Vec2::new(0.5, 0.5), 0.25).with_feather(0.05)),
+        );
+    });
+}
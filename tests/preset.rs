@@ -0,0 +1,204 @@
+//! Tests of post processing presets and their RON format.
+
+use std::path::Path;
+
+use bevy::{ecs::system::CommandQueue, prelude::*};
+use bevy_vfx_bag::post_processing::{
+    bloom::Bloom,
+    blur::Blur,
+    effect_mask::{EffectMask, Masked},
+    lut::{Lut, LutImage},
+    pixelate::Pixelate,
+    preset::{PostProcessingPreset, PresetEffect, VfxStack},
+    wave::Wave,
+    EffectStack, Order, PostProcessingStage,
+};
+
+fn bundled_preset(name: &str) -> PostProcessingPreset {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("assets/presets")
+        .join(name);
+    let ron = std::fs::read_to_string(&path).expect("Should be able to read preset");
+
+    PostProcessingPreset::from_ron(&ron)
+        .unwrap_or_else(|e| panic!("Could not parse {}: {e}", path.display()))
+}
+
+#[test]
+fn bundled_presets_parse() {
+    for entry in std::fs::read_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/presets"))
+        .expect("Should be able to list presets")
+    {
+        let name = entry.expect("Should be able to read entry").file_name();
+        let name = name.to_str().expect("Preset names are UTF-8");
+
+        assert!(name.ends_with(".vfx.ron"), "{name} is not a preset");
+        assert!(!bundled_preset(name).effects.is_empty(), "{name} is empty");
+    }
+}
+
+#[test]
+fn left_out_settings_use_defaults() {
+    let preset = PostProcessingPreset::from_ron(
+        "(effects: [(effect: Blur(amount: 0.5)), (effect: Lut(image: Arctic), order: 1.0)])",
+    )
+    .expect("Should parse");
+
+    let [blur, lut] = &preset.effects[..] else {
+        panic!("Should have two effects");
+    };
+
+    let PresetEffect::Blur(effect) = &blur.effect else {
+        panic!("Should be a blur");
+    };
+    assert_eq!(effect.amount, 0.5);
+    assert_eq!(effect.kernel_radius, Blur::default().kernel_radius);
+    assert_eq!(effect.kernel, Blur::default().kernel);
+    assert_eq!(blur.order, 0.0);
+    assert!(blur.enabled);
+    assert_eq!(blur.mask, None);
+
+    let PresetEffect::Lut(effect) = &lut.effect else {
+        panic!("Should be a LUT");
+    };
+    assert_eq!(effect.intensity, 1.0);
+    assert_eq!(lut.order, 1.0);
+}
+
+#[test]
+fn round_trips_through_ron() {
+    let preset = PostProcessingPreset::new()
+        .with(Pixelate::new(4.0), 0.0)
+        .with(
+            Lut::sauna().cross_fade(
+                &Lut::from_image(LutImage::Path("luts/teal-orange.cube".into())),
+                0.25,
+            ),
+            1.0,
+        )
+        .with_masked(
+            Wave {
+                waves_x: 2.0,
+                amplitude_x: 0.01,
+                ..default()
+            },
+            2.0,
+            EffectMask::circle(Vec2::new(0.5, 0.5), 0.25)
+                .inverted()
+                .with_feather(0.1),
+        );
+
+    let ron = preset.to_ron().expect("Should serialize");
+    let parsed = PostProcessingPreset::from_ron(&ron).expect("Should parse");
+
+    assert_eq!(
+        parsed.to_ron().expect("Should serialize"),
+        ron,
+        "Parsing changed the preset"
+    );
+    assert_eq!(parsed.effects.len(), 3);
+    assert_eq!(parsed.effects[2].mask, preset.effects[2].mask);
+}
+
+#[test]
+fn luts_from_handles_are_not_serializable() {
+    let preset = PostProcessingPreset::new().with(Lut::new(Handle::default()), 0.0);
+
+    assert!(preset.to_ron().is_err());
+}
+
+/// Applies the preset to the entity, like when the handle of a camera changes.
+fn apply(world: &mut World, entity: Entity, preset: &VfxStack) {
+    let mut queue = CommandQueue::default();
+    preset.apply(&mut Commands::new(&mut queue, world).entity(entity));
+    queue.apply(world);
+}
+
+#[test]
+fn applying_replaces_previous_effects() {
+    let mut world = World::new();
+    let camera = world.spawn(Wave::default()).id();
+
+    let first = VfxStack::new()
+        .with(Blur::default(), 0.0)
+        .with(Blur::kawase(), 2.0)
+        .with_masked(Lut::arctic(), 1.0, EffectMask::rect(Vec2::ZERO, Vec2::ONE))
+        .with(Bloom::default(), 3.0);
+    apply(&mut world, camera, &first);
+
+    let camera_ref = world.entity(camera);
+    let blurs = camera_ref
+        .get::<EffectStack<Blur>>()
+        .expect("Blurs should be stacked");
+    assert_eq!(
+        blurs
+            .instances
+            .iter()
+            .map(|instance| instance.order)
+            .collect::<Vec<_>>(),
+        [0.0, 2.0]
+    );
+    assert_eq!(
+        camera_ref.get::<Order<Lut>>().map(|order| order.order),
+        Some(1.0)
+    );
+    assert!(camera_ref.contains::<Masked<Lut>>());
+    assert_eq!(
+        camera_ref.get::<Order<Bloom>>().map(|order| order.order),
+        Some(3.0)
+    );
+
+    let second = VfxStack::new()
+        .with(Pixelate::default(), 0.0)
+        .with(Lut::sauna(), 5.0);
+    apply(&mut world, camera, &second);
+
+    let camera_ref = world.entity(camera);
+    assert!(!camera_ref.contains::<EffectStack<Blur>>());
+    assert!(camera_ref.contains::<EffectStack<Pixelate>>());
+    assert_eq!(
+        camera_ref.get::<Order<Lut>>().map(|order| order.order),
+        Some(5.0)
+    );
+    assert!(!camera_ref.contains::<Masked<Lut>>());
+    assert!(!camera_ref.contains::<Bloom>());
+    assert!(!camera_ref.contains::<Order<Bloom>>());
+
+    // Effects added directly which can be stacked are left as is.
+    assert!(camera_ref.contains::<Wave>());
+}
+
+#[test]
+fn stages_are_kept() {
+    let preset = PostProcessingPreset::from_ron(
+        "(effects: [
+            (effect: Wave(waves_x: 2.0), stage: PostUi),
+            (effect: Lut(image: Arctic), order: 1.0, stage: PreTonemap),
+            (effect: Bloom()),
+        ])",
+    )
+    .expect("Should parse");
+
+    assert_eq!(preset.effects[0].stage, Some(PostProcessingStage::PostUi));
+    assert_eq!(preset.effects[2].stage, None);
+
+    let mut world = World::new();
+    let camera = world.spawn_empty().id();
+    apply(&mut world, camera, &preset);
+
+    let camera_ref = world.entity(camera);
+    let waves = camera_ref
+        .get::<EffectStack<Wave>>()
+        .expect("Waves should be stacked");
+    assert_eq!(waves.instances[0].stage, Some(PostProcessingStage::PostUi));
+    assert_eq!(
+        camera_ref.get::<Order<Lut>>().and_then(|order| order.stage),
+        Some(PostProcessingStage::PreTonemap)
+    );
+    assert_eq!(
+        camera_ref
+            .get::<Order<Bloom>>()
+            .and_then(|order| order.stage),
+        None
+    );
+}
@@ -0,0 +1,143 @@
+//! Tests of shaking cameras via trauma.
+
+mod common;
+
+use std::time::Instant;
+
+use bevy::prelude::*;
+use bevy_vfx_bag::post_processing::{
+    chromatic_aberration::ChromaticAberration,
+    screen_shake::{ScreenShake, ScreenShakeImpulse, ScreenShakePlugin},
+    wave::Wave,
+};
+
+use common::frame;
+
+/// An app which shakes cameras without rendering, and the instant its time starts at.
+fn app() -> (App, Instant) {
+    let (mut app, start) = common::app();
+    app.add_plugin(ScreenShakePlugin);
+
+    (app, start)
+}
+
+fn trauma(app: &App, camera: Entity) -> f32 {
+    app.world.get::<ScreenShake>(camera).unwrap().trauma()
+}
+
+#[test]
+fn impulses_shake_until_trauma_decays() {
+    let (mut app, start) = app();
+    let camera = app
+        .world
+        .spawn(ScreenShake::default().with_chromatic_aberration(ChromaticAberration::default()))
+        .id();
+
+    frame(&mut app, start, 0.0);
+    assert!(!app.world.entity(camera).contains::<Wave>());
+
+    app.world
+        .send_event(ScreenShakeImpulse::camera(camera, 0.5));
+    frame(&mut app, start, 0.1);
+
+    assert!((trauma(&app, camera) - 0.4).abs() < 1e-5);
+    let wave = app.world.get::<Wave>(camera).expect("Should shake");
+    assert!(wave.amplitude_x > 0.0);
+    assert!(wave.amplitude_x <= ScreenShake::default().max_wave.amplitude_x * 0.16);
+    // Changing the speeds would make the waves jump.
+    assert_eq!(wave.speed_x, ScreenShake::default().max_wave.speed_x);
+    assert!(app.world.entity(camera).contains::<ChromaticAberration>());
+
+    frame(&mut app, start, 0.6);
+    assert_eq!(trauma(&app, camera), 0.0);
+    assert!(!app.world.entity(camera).contains::<Wave>());
+    assert!(!app.world.entity(camera).contains::<ChromaticAberration>());
+}
+
+#[test]
+fn trauma_accumulates_up_to_one() {
+    let (mut app, start) = app();
+    let camera = app.world.spawn(ScreenShake::default()).id();
+
+    for _ in 0..3 {
+        app.world
+            .send_event(ScreenShakeImpulse::camera(camera, 0.4));
+    }
+    frame(&mut app, start, 0.0);
+
+    assert_eq!(trauma(&app, camera), 1.0);
+}
+
+#[test]
+fn impulses_without_camera_shake_all_cameras() {
+    let (mut app, start) = app();
+    let first = app.world.spawn(ScreenShake::default()).id();
+    let second = app.world.spawn(ScreenShake::default()).id();
+    let other = app.world.spawn(ScreenShake::default()).id();
+
+    app.world.send_event(ScreenShakeImpulse::all(0.3));
+    app.world.send_event(ScreenShakeImpulse::camera(other, 0.5));
+    frame(&mut app, start, 0.0);
+
+    assert!((trauma(&app, first) - 0.3).abs() < 1e-6);
+    assert!((trauma(&app, second) - 0.3).abs() < 1e-6);
+    assert!((trauma(&app, other) - 0.8).abs() < 1e-6);
+}
+
+#[test]
+fn other_effects_are_left_alone() {
+    let (mut app, start) = app();
+    let camera = app
+        .world
+        .spawn((ScreenShake::default(), ChromaticAberration::default()))
+        .id();
+
+    app.world
+        .send_event(ScreenShakeImpulse::camera(camera, 0.1));
+    frame(&mut app, start, 0.0);
+    frame(&mut app, start, 1.0);
+
+    assert!(!app.world.entity(camera).contains::<Wave>());
+    assert!(app.world.entity(camera).contains::<ChromaticAberration>());
+}
+
+#[test]
+fn previous_effects_are_restored() {
+    let (mut app, start) = app();
+    let wave = Wave {
+        waves_x: 4.0,
+        amplitude_x: 0.1,
+        ..default()
+    };
+    let chromatic_aberration = ChromaticAberration {
+        magnitude_r: 0.5,
+        ..default()
+    };
+    let camera = app
+        .world
+        .spawn((
+            ScreenShake::default().with_chromatic_aberration(ChromaticAberration::default()),
+            wave,
+            chromatic_aberration,
+        ))
+        .id();
+
+    app.world
+        .send_event(ScreenShakeImpulse::camera(camera, 0.5));
+    frame(&mut app, start, 0.0);
+    frame(&mut app, start, 0.1);
+
+    let shaking = app.world.get::<Wave>(camera).unwrap();
+    assert_eq!(shaking.waves_x, ScreenShake::default().max_wave.waves_x);
+
+    frame(&mut app, start, 1.0);
+
+    let restored = app
+        .world
+        .get::<Wave>(camera)
+        .expect("Should restore the wave");
+    assert_eq!(restored.waves_x, wave.waves_x);
+    assert_eq!(restored.amplitude_x, wave.amplitude_x);
+    let restored = app.world.get::<ChromaticAberration>(camera).unwrap();
+    assert_eq!(restored.magnitude_r, chromatic_aberration.magnitude_r);
+}
@@ -0,0 +1,391 @@
+//! Validates the bundled WGSL shaders without a GPU.
+//!
+//! Each shader is preprocessed with every combination of shader definitions the crate specializes it with,
+//! and the result is validated with naga.
+//! Only the shader imports registered by `bevy_render`, `bevy_core_pipeline`, and this crate are available,
+//! so importing from for example `bevy_pbr` fails, since this crate does not depend on it.
+
+use std::path::Path;
+
+use bevy::{
+    asset::{AssetPlugin, HandleId},
+    core::{TaskPoolPlugin, TypeRegistrationPlugin},
+    core_pipeline::CorePipelinePlugin,
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        globals::GlobalsPlugin,
+        render_resource::{ProcessedShader, ShaderDefVal, ShaderImport, ShaderProcessor},
+        view::ViewPlugin,
+    },
+    utils::HashMap,
+};
+use bevy_vfx_bag::post_processing::{
+    blur::Blur,
+    chromatic_aberration::ChromaticAberration,
+    color_grade::ColorGrade,
+    crt::{Crt, PhosphorMask},
+    depth_fog::DepthFog,
+    depth_of_field::DepthOfField,
+    film_grain::FilmGrain,
+    flip::Flip,
+    lens_distortion::LensDistortion,
+    masks::Mask,
+    palette::Palette,
+    pixelate::{Pixelate, PixelateShape},
+    transition::Transition,
+    wave::Wave,
+    PostProcessingEffect,
+};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+
+/// Shader definitions added for a view, which apply to every shader.
+///
+/// Pipelines are only specialized on the format of the view target so far, without any definitions,
+/// so the only combination is the empty one.
+fn view_shader_defs() -> Vec<Vec<ShaderDefVal>> {
+    vec![vec![]]
+}
+
+/// The shader definitions for each pass of each of the given effects.
+fn effect_shader_defs<T: PostProcessingEffect>(effects: &[T]) -> Vec<Vec<ShaderDefVal>> {
+    effects
+        .iter()
+        .flat_map(|effect| {
+            (0..effect.passes()).map(|pass| {
+                let mut shader_defs = effect.shader_defs();
+                shader_defs.push(ShaderDefVal::UInt("PASS".into(), pass));
+                shader_defs
+            })
+        })
+        .collect()
+}
+
+/// The shader definitions of effects reading depth, which are specialized on whether the depth prepass is multisampled.
+fn depth_shader_defs(shader_defs: Vec<Vec<ShaderDefVal>>) -> Vec<Vec<ShaderDefVal>> {
+    shader_defs
+        .into_iter()
+        .flat_map(|shader_defs| {
+            [
+                [shader_defs.clone(), vec!["DEPTH_PREPASS".into()]].concat(),
+                [
+                    shader_defs,
+                    vec!["DEPTH_PREPASS".into(), "MULTISAMPLED".into()],
+                ]
+                .concat(),
+            ]
+        })
+        .collect()
+}
+
+/// Shaders of this crate which only exist to be imported by other shaders.
+const CRATE_IMPORTS: &[&str] = &[
+    "assets/shaders/depth.wgsl",
+    "assets/shaders/pixelate-functions.wgsl",
+];
+
+/// Shaders importable by the bundled shaders, by their import path.
+struct Imports {
+    shaders: HashMap<Handle<Shader>, Shader>,
+    import_handles: HashMap<ShaderImport, Handle<Shader>>,
+}
+
+impl Imports {
+    /// Collects the shaders registered by the Bevy plugins the crate depends on,
+    /// and the importable shaders of this crate.
+    /// None of these need a GPU to register their shaders.
+    fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugin(TaskPoolPlugin::default())
+            .add_plugin(TypeRegistrationPlugin)
+            .add_plugin(AssetPlugin::default())
+            .add_asset::<Shader>()
+            // The tonemapping plugin adds its look-up textures as images.
+            .add_asset::<Image>()
+            .add_plugin(GlobalsPlugin)
+            .add_plugin(ViewPlugin)
+            .add_plugin(CorePipelinePlugin);
+
+        let assets = app.world.resource::<Assets<Shader>>();
+
+        let mut shaders = HashMap::default();
+        let mut import_handles = HashMap::default();
+
+        for (id, shader) in assets.iter() {
+            let handle = Handle::weak(id);
+
+            if let Some(import_path) = shader.import_path() {
+                import_handles.insert(import_path.clone(), handle.clone());
+            }
+            shaders.insert(handle, shader.clone());
+        }
+
+        for (index, path) in CRATE_IMPORTS.iter().enumerate() {
+            let shader = Shader::from_wgsl(read(path));
+            let handle = Handle::weak(HandleId::new(Shader::TYPE_UUID, index as u64));
+
+            if let Some(import_path) = shader.import_path() {
+                import_handles.insert(import_path.clone(), handle.clone());
+            }
+            shaders.insert(handle, shader);
+        }
+
+        Self {
+            shaders,
+            import_handles,
+        }
+    }
+}
+
+/// Reads a file relative to the root of the crate.
+fn read(path: &str) -> String {
+    let full_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    std::fs::read_to_string(&full_path)
+        .unwrap_or_else(|e| panic!("Could not read {}: {e}", full_path.display()))
+}
+
+/// Preprocesses and validates the shader at `path` with each set of shader definitions,
+/// combined with each set of view shader definitions.
+fn check(path: &str, shader_defs: Vec<Vec<ShaderDefVal>>) {
+    let imports = Imports::new();
+
+    let shader = Shader::from_wgsl(read(path));
+
+    let mut failures = vec![];
+
+    for view_shader_defs in view_shader_defs() {
+        for shader_defs in &shader_defs {
+            let shader_defs = [view_shader_defs.clone(), shader_defs.clone()].concat();
+
+            if let Err(e) = validate(&shader, &shader_defs, &imports) {
+                failures.push(format!("With {shader_defs:?}:\n{e}"));
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{path} is not valid:\n\n{}",
+        failures.join("\n\n")
+    );
+}
+
+fn validate(
+    shader: &Shader,
+    shader_defs: &[ShaderDefVal],
+    imports: &Imports,
+) -> Result<(), String> {
+    let processed = ShaderProcessor::default()
+        .process(
+            shader,
+            shader_defs,
+            &imports.shaders,
+            &imports.import_handles,
+        )
+        .map_err(|e| format!("Could not preprocess: {e}"))?;
+
+    let ProcessedShader::Wgsl(source) = processed else {
+        return Err("Not a WGSL shader".to_string());
+    };
+
+    let module = naga::front::wgsl::parse_str(&source).map_err(|e| e.emit_to_string(&source))?;
+
+    Validator::new(ValidationFlags::all(), Capabilities::default())
+        .validate(&module)
+        .map_err(|e| e.emit_to_string(&source))?;
+
+    Ok(())
+}
+
+#[test]
+fn bloom() {
+    let stages = ["FIRST_DOWNSAMPLE", "DOWNSAMPLE", "UPSAMPLE"]
+        .into_iter()
+        .map(|stage| vec![stage.into()]);
+    let composite = (1..=8).map(|mip_levels| {
+        vec![
+            "COMPOSITE".into(),
+            ShaderDefVal::UInt("MIP_LEVELS".into(), mip_levels),
+        ]
+    });
+
+    check(
+        "assets/shaders/bloom.wgsl",
+        stages.chain(composite).collect(),
+    );
+}
+
+#[test]
+fn blur() {
+    check(
+        "assets/shaders/blur.wgsl",
+        effect_shader_defs(&[Blur::default(), Blur::gaussian(), Blur::kawase()]),
+    );
+}
+
+#[test]
+fn chromatic_aberration() {
+    check(
+        "assets/shaders/chromatic-aberration.wgsl",
+        effect_shader_defs(&[
+            ChromaticAberration::default(),
+            ChromaticAberration::radial(0.01),
+        ]),
+    );
+}
+
+#[test]
+fn color_grade() {
+    check(
+        "assets/shaders/color-grade.wgsl",
+        effect_shader_defs(&[ColorGrade::default()]),
+    );
+}
+
+#[test]
+fn crt() {
+    check(
+        "assets/shaders/crt.wgsl",
+        effect_shader_defs(&[
+            Crt::trinitron(),
+            Crt::consumer_tv(),
+            Crt::arcade(),
+            Crt {
+                phosphor_mask: PhosphorMask::None,
+                ..default()
+            },
+        ]),
+    );
+}
+
+#[test]
+fn depth_fog() {
+    check(
+        "assets/shaders/depth-fog.wgsl",
+        depth_shader_defs(effect_shader_defs(&[DepthFog::default()])),
+    );
+}
+
+#[test]
+fn depth_of_field() {
+    check(
+        "assets/shaders/depth-of-field.wgsl",
+        depth_shader_defs(effect_shader_defs(&[DepthOfField::default()])),
+    );
+}
+
+#[test]
+fn effect_mask() {
+    check(
+        "assets/shaders/effect-mask.wgsl",
+        vec![vec![], vec!["SAVE".into()]],
+    );
+}
+
+#[test]
+fn film_grain() {
+    check(
+        "assets/shaders/film-grain.wgsl",
+        effect_shader_defs(&[
+            FilmGrain::default(),
+            FilmGrain {
+                colored: true,
+                ..default()
+            },
+        ]),
+    );
+}
+
+#[test]
+fn flip() {
+    check(
+        "assets/shaders/flip.wgsl",
+        effect_shader_defs(&[Flip::default()]),
+    );
+}
+
+#[test]
+fn lens_distortion() {
+    check(
+        "assets/shaders/lens-distortion.wgsl",
+        effect_shader_defs(&[
+            LensDistortion::default(),
+            LensDistortion {
+                zoom_to_fill: false,
+                ..default()
+            },
+        ]),
+    );
+}
+
+#[test]
+fn lut() {
+    check("assets/shaders/lut.wgsl", vec![vec![]]);
+}
+
+#[test]
+fn masks() {
+    check(
+        "assets/shaders/masks.wgsl",
+        effect_shader_defs(&[Mask::square(), Mask::crt(), Mask::vignette()]),
+    );
+}
+
+#[test]
+fn palette() {
+    check(
+        "assets/shaders/palette.wgsl",
+        effect_shader_defs(&[Palette::game_boy(), Palette::default()]),
+    );
+}
+
+#[test]
+fn pixelate() {
+    check(
+        "assets/shaders/pixelate.wgsl",
+        effect_shader_defs(&[
+            Pixelate::default(),
+            Pixelate::default().averaged(),
+            Pixelate::default().with_shape(PixelateShape::Hexagon),
+            Pixelate::default().with_shape(PixelateShape::Circle),
+            Pixelate::default()
+                .with_shape(PixelateShape::Triangle)
+                .averaged(),
+        ]),
+    );
+}
+
+#[test]
+fn raindrops() {
+    check("assets/shaders/raindrops.wgsl", vec![vec![]]);
+}
+
+#[test]
+fn transition() {
+    check(
+        "assets/shaders/transition.wgsl",
+        effect_shader_defs(&[
+            Transition::fade(),
+            Transition::wipe(),
+            Transition::iris(),
+            Transition::dissolve(),
+            Transition::pixelate_dissolve(),
+        ]),
+    );
+}
+
+#[test]
+fn wave() {
+    check(
+        "assets/shaders/wave.wgsl",
+        effect_shader_defs(&[Wave::default()]),
+    );
+}
+
+#[test]
+fn custom_effect_example() {
+    check(
+        "examples/custom-effect/desaturate.wgsl",
+        vec![vec![ShaderDefVal::UInt("PASS".into(), 0)]],
+    );
+}
@@ -0,0 +1,200 @@
+//! Tests of tweening effect parameters.
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use bevy::{asset::HandleId, prelude::*};
+use bevy_vfx_bag::post_processing::{
+    blur::{Blur, BlurKernel},
+    palette::Palette,
+    tween::{Easing, EffectTween, EffectTweenCompleted, EffectTweenPlugin, Tweenable},
+    wave::Wave,
+};
+
+const EASINGS: [Easing; 12] = [
+    Easing::Linear,
+    Easing::QuadraticIn,
+    Easing::QuadraticOut,
+    Easing::QuadraticInOut,
+    Easing::CubicIn,
+    Easing::CubicOut,
+    Easing::CubicInOut,
+    Easing::SineIn,
+    Easing::SineOut,
+    Easing::SineInOut,
+    Easing::ExponentialOut,
+    Easing::BackOut,
+];
+
+/// An app which tweens waves, and the instant its time starts at.
+fn app() -> (App, Instant) {
+    let (mut app, start) = common::app();
+    app.add_plugin(EffectTweenPlugin::<Wave>::default());
+
+    (app, start)
+}
+
+/// Runs a frame at the given amount of seconds after the start, and returns the amount of completion events.
+fn frame(app: &mut App, start: Instant, seconds: f32) -> usize {
+    common::frame(app, start, seconds);
+
+    app.world
+        .resource_mut::<Events<EffectTweenCompleted<Wave>>>()
+        .drain()
+        .count()
+}
+
+fn shake() -> Wave {
+    Wave {
+        waves_x: 2.0,
+        speed_x: 30.0,
+        amplitude_x: 0.02,
+        ..default()
+    }
+}
+
+#[test]
+fn once_ends_at_target_and_is_removed() {
+    let (mut app, start) = app();
+    let still = Wave {
+        amplitude_x: 0.0,
+        ..shake()
+    };
+    let camera = app
+        .world
+        .spawn((
+            shake(),
+            EffectTween::new(shake(), still, Duration::from_secs(1)),
+        ))
+        .id();
+
+    assert_eq!(frame(&mut app, start, 0.5), 0);
+    let amplitude = app.world.get::<Wave>(camera).unwrap().amplitude_x;
+    assert!((amplitude - 0.01).abs() < 1e-6, "{amplitude}");
+
+    assert_eq!(frame(&mut app, start, 1.25), 1);
+    assert_eq!(app.world.get::<Wave>(camera).unwrap().amplitude_x, 0.0);
+    assert!(!app.world.entity(camera).contains::<EffectTween<Wave>>());
+
+    // The effect itself is left in place.
+    assert_eq!(frame(&mut app, start, 2.0), 0);
+    assert!(app.world.entity(camera).contains::<Wave>());
+}
+
+#[test]
+fn ping_pong_returns_to_start() {
+    let (mut app, start) = app();
+    let still = Wave {
+        amplitude_x: 0.0,
+        ..shake()
+    };
+    let camera = app
+        .world
+        .spawn((
+            shake(),
+            EffectTween::new(shake(), still, Duration::from_secs(1)).ping_pong(),
+        ))
+        .id();
+
+    assert_eq!(frame(&mut app, start, 1.0), 0);
+    assert!(app.world.get::<Wave>(camera).unwrap().amplitude_x.abs() < 1e-6);
+
+    assert_eq!(frame(&mut app, start, 1.5), 0);
+    assert_eq!(frame(&mut app, start, 2.1), 1);
+    assert!(app.world.entity(camera).contains::<EffectTween<Wave>>());
+
+    let amplitude = app.world.get::<Wave>(camera).unwrap().amplitude_x;
+    assert!((amplitude - 0.018).abs() < 1e-5, "{amplitude}");
+}
+
+#[test]
+fn zero_duration_ends_immediately() {
+    let (mut app, start) = app();
+    let still = Wave {
+        amplitude_x: 0.0,
+        ..shake()
+    };
+    let camera = app
+        .world
+        .spawn((
+            shake(),
+            EffectTween::new(shake(), still, Duration::ZERO).looping(),
+        ))
+        .id();
+
+    assert_eq!(frame(&mut app, start, 0.0), 1);
+    assert_eq!(app.world.get::<Wave>(camera).unwrap().amplitude_x, 0.0);
+    assert!(!app.world.entity(camera).contains::<EffectTween<Wave>>());
+}
+
+#[test]
+fn easings_start_and_end_at_the_ends() {
+    for easing in EASINGS {
+        assert!(easing.ease(0.0).abs() < 1e-6, "{easing:?}");
+        assert!((easing.ease(1.0) - 1.0).abs() < 1e-6, "{easing:?}");
+    }
+}
+
+#[test]
+fn discrete_parameters_switch_halfway() {
+    let from = Blur::default();
+    let to = Blur::kawase();
+    let mut blur = from;
+
+    blur.tween(&from, &to, 0.4);
+    assert_eq!(blur.kernel, BlurKernel::Box3x3);
+
+    blur.tween(&from, &to, 0.6);
+    assert_eq!(blur.kernel, BlurKernel::Kawase);
+
+    let from = Blur::gaussian();
+    let to = Blur {
+        kernel: BlurKernel::Gaussian {
+            sigma: 1.0,
+            taps: 16,
+        },
+        ..Blur::gaussian()
+    };
+    let BlurKernel::Gaussian { sigma, taps } = from.kernel else {
+        panic!("Should be gaussian");
+    };
+
+    blur.tween(&from, &to, 0.5);
+    assert_eq!(
+        blur.kernel,
+        BlurKernel::Gaussian {
+            sigma: (sigma + 1.0) / 2.0,
+            taps: ((taps + 16) as f32 / 2.0).round() as u32,
+        }
+    );
+}
+
+#[test]
+fn palette_colors_interpolate() {
+    let from = Palette::new([Color::BLACK, Color::WHITE]);
+    let to = Palette::new([Color::WHITE, Color::BLACK]);
+    let mut palette = from.clone();
+
+    palette.tween(&from, &to, 0.5);
+
+    let gray = Color::rgb_linear(0.5, 0.5, 0.5);
+    assert_eq!(palette.colors, [gray, gray]);
+}
+
+#[test]
+fn palette_colors_from_images_are_kept() {
+    let image = Handle::weak(HandleId::random::<Image>());
+    let from = Palette::from_image(image.clone());
+    let to = Palette::new([Color::WHITE]);
+
+    let read_colors = vec![Color::RED, Color::BLUE];
+    let mut palette = Palette {
+        colors: read_colors.clone(),
+        ..from.clone()
+    };
+
+    palette.tween(&from, &to, 0.4);
+    assert_eq!(palette.colors, read_colors);
+    assert_eq!(palette.image, Some(image));
+}